@@ -11,6 +11,8 @@ use green_rs::api::address::AsyncAddressApi;
 use green_rs::api::builder::GetReceiveAddressBuilder;
 use green_rs::api::wallet::AsyncWalletExt;
 use green_rs::client::AsyncGreenClient;
+use green_rs::types::FeePriority;
+use green_rs::Network;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -47,9 +49,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("New address generated successfully:");
             println!("  Address: {}", address.address);
             println!("  Pointer: {}", address.pointer);
-            if !address.address_type.is_empty() {
-                println!("    Type: {}", address.address_type);
-            }
+            println!("    Type: {}", address.address_type);
         }
         Err(e) => {
             eprintln!("Failed to generate new address: {}", e);
@@ -60,9 +60,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\nGetting fee estimates...");
     match client.get_fee_estimates().await {
         Ok(fee_estimates) => {
-            println!("Fee estimates (sat/vB):");
+            println!("Fee estimates:");
             for (blocks, fee_rate) in &fee_estimates.fees {
-                println!("  {} blocks: {} sat/vB", blocks, fee_rate);
+                println!("  {} blocks: {}", blocks, fee_rate);
             }
         }
         Err(e) => {
@@ -84,8 +84,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             balance.get("btc").unwrap_or(0)
         );
         println!(
-            "  Fastest fee: {} sat/vB",
-            fees.fees.get(&1).copied().unwrap_or(0)
+            "  Fastest fee: {}",
+            fees.for_priority(FeePriority::Fast, Network::Mainnet)
         );
     }
 
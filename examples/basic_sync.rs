@@ -44,9 +44,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("New address generated successfully:");
             println!("  Address: {}", address.address);
             println!("  Pointer: {}", address.pointer);
-            if !address.address_type.is_empty() {
-                println!("    Type: {}", address.address_type);
-            }
+            println!("    Type: {}", address.address_type);
         }
         Err(e) => {
             eprintln!("Failed to generate new address: {}", e);
@@ -57,9 +55,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\nGetting fee estimates...");
     match client.get_fee_estimates() {
         Ok(fee_estimates) => {
-            println!("Fee estimates (sat/vB):");
+            println!("Fee estimates:");
             for (blocks, fee_rate) in &fee_estimates.fees {
-                println!("  {} blocks: {} sat/vB", blocks, fee_rate);
+                println!("  {} blocks: {}", blocks, fee_rate);
             }
         }
         Err(e) => {
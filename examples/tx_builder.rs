@@ -13,6 +13,7 @@ use green_rs::api::builder::GetReceiveAddressBuilder;
 use green_rs::api::transaction::TxBuilder;
 use green_rs::api::wallet::WalletExt;
 use green_rs::client::GreenClient;
+use green_rs::types::FeeRate;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Create a client for getting addresses
@@ -54,7 +55,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let simple_tx = TxBuilder::new()
         .add_output(destination_address.clone(), 10000) // 10,000 satoshis
-        .set_fee_rate(5) // 5 sat/vB
+        .set_fee_rate(FeeRate::from_sat_per_vb(5))
         .set_subaccount(0);
 
     // Dump the transaction data
@@ -93,7 +94,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let complex_tx = TxBuilder::new()
         .add_output(destination_address, 25000) // 25,000 satoshis to first address
         .add_output(second_address, 15000) // 15,000 satoshis to second address
-        .set_fee_rate(10) // Higher fee rate for faster confirmation
+        .set_fee_rate(FeeRate::from_sat_per_vb(10)) // Higher fee rate for faster confirmation
         .set_subaccount(0);
 
     match complex_tx.dump() {
@@ -107,24 +108,24 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("\n3. Transaction workflow demonstration");
             println!("------------------------------------");
 
-            // Sign the transaction (demonstration only)
+            // Sign the transaction with green-cli
             match tx.sign() {
                 Ok(signed_tx) => {
                     println!("Transaction signed successfully!");
 
-                    // Send/broadcast the transaction (demonstration only)
+                    // Send/broadcast the transaction with green-cli
                     match signed_tx.send() {
                         Ok(txid) => {
                             println!("Transaction broadcast successfully!");
                             println!("Transaction ID: {}", txid);
                         }
                         Err(e) => {
-                            println!("Note: Broadcasting not implemented in demo: {}", e);
+                            eprintln!("Failed to broadcast transaction: {}", e);
                         }
                     }
                 }
                 Err(e) => {
-                    println!("Note: Signing not implemented in demo: {}", e);
+                    eprintln!("Failed to sign transaction: {}", e);
                 }
             }
         }
@@ -140,15 +141,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let chained_result = TxBuilder::new()
         .add_output("bc1qexampleaddress...".to_string(), 50000)
         .add_output("bc1qanotheraddress...".to_string(), 30000)
-        .set_fee_rate(20)
+        .set_fee_rate(FeeRate::from_sat_per_vb(20))
         .set_subaccount(0)
         .dump()
-        .and_then(|tx| {
+        .inspect(|tx| {
             println!("Transaction built with method chaining");
             if let Some(json) = tx.to_json() {
                 println!("Transaction JSON: {}", json);
             }
-            Ok(tx)
         });
 
     match chained_result {
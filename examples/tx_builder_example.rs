@@ -1,6 +1,7 @@
 //! Example demonstrating the TxBuilder for transaction creation
 
 use green_rs::api::TxBuilder;
+use green_rs::types::FeeRate;
 use green_rs::Result;
 
 fn main() -> Result<()> {
@@ -14,7 +15,7 @@ fn main() -> Result<()> {
             "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_string(),
             50000,
         )
-        .set_fee_rate(10)
+        .set_fee_rate(FeeRate::from_sat_per_vb(10))
         .set_subaccount(0)
         .add_input("previous_txid:0".to_string());
 
@@ -31,10 +32,10 @@ fn main() -> Result<()> {
         println!("Transaction file: {}", path);
     }
 
-    // Sign the transaction (would call green-cli tx sign)
+    // Sign the transaction (runs green-cli tx sign)
     let tx_builder = tx_builder.sign()?;
 
-    // Broadcast the transaction (would call green-cli tx send)
+    // Broadcast the transaction (runs green-cli tx send)
     let txid = tx_builder.broadcast()?;
     println!("Transaction ID: {}", txid);
 
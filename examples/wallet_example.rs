@@ -35,12 +35,12 @@ fn sync_example() -> Result<()> {
     // Get fee estimates
     match client.get_fee_estimates() {
         Ok(fee_estimates) => {
-            println!("\nFee estimates (sats/vbyte):");
+            println!("\nFee estimates:");
             let mut blocks: Vec<_> = fee_estimates.fees.keys().collect();
             blocks.sort();
             for blocks in blocks {
                 if let Some(fee_rate) = fee_estimates.fees.get(blocks) {
-                    println!("  {} blocks: {} sats/vbyte", blocks, fee_rate);
+                    println!("  {} blocks: {}", blocks, fee_rate);
                 }
             }
         }
@@ -74,12 +74,12 @@ async fn async_example() -> Result<()> {
     // Get fee estimates
     match client.get_fee_estimates().await {
         Ok(fee_estimates) => {
-            println!("\nFee estimates (sats/vbyte):");
+            println!("\nFee estimates:");
             let mut blocks: Vec<_> = fee_estimates.fees.keys().collect();
             blocks.sort();
             for blocks in blocks {
                 if let Some(fee_rate) = fee_estimates.fees.get(blocks) {
-                    println!("  {} blocks: {} sats/vbyte", blocks, fee_rate);
+                    println!("  {} blocks: {}", blocks, fee_rate);
                 }
             }
         }
@@ -0,0 +1,174 @@
+//! Client-side address book of external counterparties
+
+use super::{validate_address, ParsedAddress};
+use crate::config::Network;
+use crate::error::{Error, ErrorKind, Result};
+use crate::types::common::Address;
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind as IoErrorKind;
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+/// A named counterparty address
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AddressBookEntry {
+    /// Unique name of the entry
+    pub name: String,
+    /// Address of the counterparty
+    pub address: Address,
+    /// Network the address is used on
+    pub network: Network,
+    /// Free-form notes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl AddressBookEntry {
+    /// Create an entry without notes
+    #[must_use]
+    pub fn new(name: impl Into<String>, address: impl Into<Address>, network: Network) -> Self {
+        Self {
+            name: name.into(),
+            address: address.into(),
+            network,
+            notes: None,
+        }
+    }
+
+    /// Set the notes
+    #[must_use]
+    pub fn notes(mut self, notes: impl Into<String>) -> Self {
+        self.notes = Some(notes.into());
+        self
+    }
+
+    /// Check the entry has a name and an address for its network
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Validation`] if the name is blank (`Other`), or
+    /// the address is malformed or for another network (`InvalidAddress`)
+    pub fn validate(&self) -> Result<ParsedAddress> {
+        if self.name.trim().is_empty() {
+            return Err(Error::validation(
+                ErrorKind::Other,
+                format!("Address book entry for {} has no name", self.address),
+            ));
+        }
+        validate_address(&self.address, Some(self.network))
+    }
+}
+
+/// Address book persisted to a JSON file
+///
+/// Entries are validated as they are inserted and when the file is opened;
+/// changes are written back with [`save`](Self::save).
+///
+/// # Example
+///
+/// ```no_run
+/// use green_rs::address::{AddressBook, AddressBookEntry};
+/// use green_rs::Network;
+///
+/// let mut book = AddressBook::open("contacts.json")?;
+/// book.insert(
+///     AddressBookEntry::new("exchange", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", Network::Mainnet)
+///         .notes("Deposit address"),
+/// )?;
+/// book.save()?;
+/// # Ok::<(), green_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressBook {
+    path: PathBuf,
+    entries: Vec<AddressBookEntry>,
+}
+
+impl AddressBook {
+    /// Open the address book stored at `path`
+    ///
+    /// A missing file opens an empty book, created on the first save.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed, or holds an
+    /// invalid or duplicate entry
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let stored: Vec<AddressBookEntry> = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(err) if err.kind() == IoErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let mut book = Self {
+            path,
+            entries: Vec::with_capacity(stored.len()),
+        };
+        for entry in stored {
+            book.insert(entry)?;
+        }
+        Ok(book)
+    }
+
+    /// File the book is stored in
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Entries in insertion order
+    #[must_use]
+    pub fn entries(&self) -> &[AddressBookEntry] {
+        &self.entries
+    }
+
+    /// Entry named `name`
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&AddressBookEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Add an entry after validating it
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Validation`] if the entry fails
+    /// [`AddressBookEntry::validate`], or another entry has the same name
+    /// (`Other`)
+    pub fn insert(&mut self, entry: AddressBookEntry) -> Result<()> {
+        entry.validate()?;
+        if self.get(&entry.name).is_some() {
+            return Err(Error::validation(
+                ErrorKind::Other,
+                format!("Address book already has an entry named {}", entry.name),
+            ));
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Remove and return the entry named `name`
+    pub fn remove(&mut self, name: &str) -> Option<AddressBookEntry> {
+        let index = self.entries.iter().position(|entry| entry.name == name)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// Write the book to its file
+    ///
+    /// The file is replaced atomically, so a failed save leaves the previous
+    /// contents in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written
+    pub fn save(&self) -> Result<()> {
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let file = NamedTempFile::new_in(dir)?;
+        serde_json::to_writer_pretty(&file, &self.entries)?;
+        file.persist(&self.path).map_err(|err| err.error)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,567 @@
+//! Offline address parsing and network validation
+//!
+//! [`ParsedAddress::parse`] decodes an address without asking green-cli or a
+//! node: legacy base58 P2PKH/P2SH, bech32/bech32m segwit v0 and v1, and the
+//! Liquid blech32 confidential and base58 confidential forms next to their
+//! unconfidential counterparts. The result names the network the address
+//! belongs to, its script type and witness program, and the blinding pubkey
+//! of a confidential address.
+//!
+//! [`validate_address`] additionally checks the address against a
+//! [`Network`]. [`TxBuilder`](crate::api::TxBuilder) and `send_to_address`
+//! use it with the network the client is configured for, so a mainnet
+//! address is rejected on testnet before green-cli is invoked.
+//!
+//! [`PaymentUri`] reads and writes `bitcoin:` and `liquidnetwork:` payment
+//! URIs around an address, and [`AddressBook`] keeps validated counterparty
+//! addresses by name in a JSON file.
+//!
+//! # Example
+//!
+//! ```
+//! use green_rs::address::{validate_address, AddressScriptType, ParsedAddress};
+//! use green_rs::Network;
+//!
+//! let address = ParsedAddress::parse("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").unwrap();
+//! assert_eq!(address.network, Network::Mainnet);
+//! assert_eq!(address.script_type, AddressScriptType::P2wpkh);
+//! assert_eq!(address.witness_version, Some(0));
+//! assert!(!address.is_confidential());
+//!
+//! assert!(validate_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", Some(Network::Testnet)).is_err());
+//! ```
+
+use crate::config::Network;
+use crate::error::{Error, ErrorKind, Result};
+use crate::types::common::Script;
+use bech32::primitives::decode::CheckedHrpstring;
+use bech32::{segwit, ByteIterExt, Checksum, Fe32, Fe32IterExt, Hrp};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+
+mod book;
+mod uri;
+
+pub use book::{AddressBook, AddressBookEntry};
+pub use uri::PaymentUri;
+
+/// Networks in the order an ambiguous base58 prefix resolves to
+const NETWORKS: [Network; 5] = [
+    Network::Mainnet,
+    Network::Testnet,
+    Network::ElectrumLocaltest,
+    Network::Liquid,
+    Network::LiquidTestnet,
+];
+
+/// Alphabet of base58check addresses
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Length of a compressed blinding pubkey
+const BLINDING_PUBKEY_LEN: usize = 33;
+
+/// Length of the hash in a P2PKH or P2SH address
+const HASH_LEN: usize = 20;
+
+/// Blech32 checksum of Liquid confidential segwit v0 addresses
+///
+/// The 12-character checksum of Elements' blech32, which covers the longer
+/// payload of a blinding pubkey followed by the witness program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Blech32 {}
+
+impl Checksum for Blech32 {
+    type MidstateRepr = u64;
+    const CODE_LENGTH: usize = 1024;
+    const CHECKSUM_LENGTH: usize = 12;
+    const GENERATOR_SH: [u64; 5] = [
+        0x007d_52fb_a40b_d886,
+        0x005e_8dbf_1a03_950c,
+        0x001c_3a3c_7407_2a18,
+        0x0038_5d72_fa0e_5139,
+        0x0070_93e5_a608_865b,
+    ];
+    const TARGET_RESIDUE: u64 = 1;
+}
+
+/// Blech32m checksum of Liquid confidential segwit v1+ addresses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Blech32m {}
+
+impl Checksum for Blech32m {
+    type MidstateRepr = u64;
+    const CODE_LENGTH: usize = Blech32::CODE_LENGTH;
+    const CHECKSUM_LENGTH: usize = Blech32::CHECKSUM_LENGTH;
+    const GENERATOR_SH: [u64; 5] = Blech32::GENERATOR_SH;
+    const TARGET_RESIDUE: u64 = 0x0455_972a_3350_f7a1;
+}
+
+/// How an address is encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressEncoding {
+    /// Base58check, used by legacy P2PKH and P2SH addresses
+    Base58,
+    /// Bech32, used by segwit v0 addresses
+    Bech32,
+    /// Bech32m, used by segwit v1 and later addresses
+    Bech32m,
+    /// Blech32, used by Liquid confidential segwit v0 addresses
+    Blech32,
+    /// Blech32m, used by Liquid confidential segwit v1 and later addresses
+    Blech32m,
+}
+
+/// Script an address pays to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressScriptType {
+    /// Pay to public key hash
+    P2pkh,
+    /// Pay to script hash
+    P2sh,
+    /// Native segwit v0 pay to witness public key hash
+    P2wpkh,
+    /// Native segwit v0 pay to witness script hash
+    P2wsh,
+    /// Segwit v1 pay to taproot
+    P2tr,
+    /// A witness version or program length without a defined script type
+    WitnessUnknown,
+}
+
+impl AddressScriptType {
+    /// Conventional name of the script type
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::P2pkh => "p2pkh",
+            Self::P2sh => "p2sh",
+            Self::P2wpkh => "p2wpkh",
+            Self::P2wsh => "p2wsh",
+            Self::P2tr => "p2tr",
+            Self::WitnessUnknown => "witness_unknown",
+        }
+    }
+
+    const fn for_witness(version: u8, program: &[u8]) -> Self {
+        match (version, program.len()) {
+            (0, 20) => Self::P2wpkh,
+            (0, 32) => Self::P2wsh,
+            (1, 32) => Self::P2tr,
+            _ => Self::WitnessUnknown,
+        }
+    }
+}
+
+impl fmt::Display for AddressScriptType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An address decoded offline
+///
+/// Formats back to its canonical string with [`Display`](fmt::Display);
+/// bech32 and blech32 addresses are written in lowercase.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParsedAddress {
+    /// Network the address belongs to
+    ///
+    /// Base58 prefixes are shared by testnet and the local regtest network
+    /// and decode as [`Network::Testnet`]; use
+    /// [`is_valid_for`](Self::is_valid_for) to check an address.
+    pub network: Network,
+    /// How the address is encoded
+    pub encoding: AddressEncoding,
+    /// Script the address pays to
+    pub script_type: AddressScriptType,
+    /// Segwit version, if this is a segwit address
+    pub witness_version: Option<u8>,
+    /// Witness program of a segwit address, or the hash of a base58 address
+    pub program: Vec<u8>,
+    /// Blinding pubkey of a Liquid confidential address
+    pub blinding_pubkey: Option<Vec<u8>>,
+}
+
+impl ParsedAddress {
+    /// Decode an address and detect its network
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidAddress`] validation error if the
+    /// address is not a well-formed address of a known network.
+    pub fn parse(address: &str) -> Result<Self> {
+        let address = address.trim();
+        let hrp = address
+            .rfind('1')
+            .map(|separator| address[..separator].to_ascii_lowercase());
+        let parsed = match hrp.as_deref() {
+            Some("bc" | "tb" | "bcrt" | "ex" | "tex") => Self::decode_segwit(address),
+            Some("lq" | "tlq") => Self::decode_blech32(address),
+            _ => Self::decode_base58(address),
+        };
+        parsed.map_err(|reason| invalid(format!("Invalid address {address}: {reason}")))
+    }
+
+    /// Whether this is a Liquid confidential address
+    #[must_use]
+    pub const fn is_confidential(&self) -> bool {
+        self.blinding_pubkey.is_some()
+    }
+
+    /// Witness program, if this is a segwit address
+    #[must_use]
+    pub fn witness_program(&self) -> Option<&[u8]> {
+        self.witness_version.map(|_| self.program.as_slice())
+    }
+
+    /// Whether the address can be used on `network`
+    ///
+    /// Base58 addresses are accepted on both testnet and the local regtest
+    /// network, which share their prefixes.
+    #[must_use]
+    pub fn is_valid_for(&self, network: Network) -> bool {
+        let testnets = [Network::Testnet, Network::ElectrumLocaltest];
+        self.network == network
+            || (self.encoding == AddressEncoding::Base58
+                && testnets.contains(&self.network)
+                && testnets.contains(&network))
+    }
+
+    /// Hex encoded output script the address pays to
+    #[must_use]
+    pub fn script_pubkey(&self) -> Script {
+        let hash = hex::encode(&self.program);
+        match (self.script_type, self.witness_version) {
+            (AddressScriptType::P2pkh, _) => format!("76a914{hash}88ac"),
+            (AddressScriptType::P2sh, _) => format!("a914{hash}87"),
+            (_, version) => {
+                let opcode = match version.unwrap_or(0) {
+                    0 => 0,
+                    version => 0x50 + version,
+                };
+                format!("{opcode:02x}{:02x}{hash}", self.program.len())
+            }
+        }
+    }
+
+    /// The same address without its blinding pubkey
+    #[must_use]
+    pub fn to_unconfidential(&self) -> Self {
+        let encoding = match self.encoding {
+            AddressEncoding::Blech32 => AddressEncoding::Bech32,
+            AddressEncoding::Blech32m => AddressEncoding::Bech32m,
+            other => other,
+        };
+        Self {
+            encoding,
+            blinding_pubkey: None,
+            ..self.clone()
+        }
+    }
+
+    fn decode_segwit(address: &str) -> std::result::Result<Self, String> {
+        let (hrp, version, program) = segwit::decode(address).map_err(|e| e.to_string())?;
+        let network = NETWORKS
+            .into_iter()
+            .find(|network| {
+                hrp.as_str()
+                    .eq_ignore_ascii_case(segwit_hrp(*network, false))
+            })
+            .ok_or_else(|| format!("unknown prefix {hrp}"))?;
+        let version = version.to_u8();
+        Ok(Self {
+            network,
+            encoding: if version == 0 {
+                AddressEncoding::Bech32
+            } else {
+                AddressEncoding::Bech32m
+            },
+            script_type: AddressScriptType::for_witness(version, &program),
+            witness_version: Some(version),
+            program,
+            blinding_pubkey: None,
+        })
+    }
+
+    fn decode_blech32(address: &str) -> std::result::Result<Self, String> {
+        let (encoding, mut checked) = match CheckedHrpstring::new::<Blech32>(address) {
+            Ok(checked) => (AddressEncoding::Blech32, checked),
+            Err(_) => CheckedHrpstring::new::<Blech32m>(address)
+                .map(|checked| (AddressEncoding::Blech32m, checked))
+                .map_err(|e| e.to_string())?,
+        };
+        let network = NETWORKS
+            .into_iter()
+            .find(|network| {
+                network.is_liquid()
+                    && checked
+                        .hrp()
+                        .as_str()
+                        .eq_ignore_ascii_case(segwit_hrp(*network, true))
+            })
+            .ok_or_else(|| format!("unknown prefix {}", checked.hrp()))?;
+        let version = checked
+            .remove_witness_version()
+            .ok_or("missing witness version")?
+            .to_u8();
+        if version > 16 {
+            return Err(format!("invalid witness version {version}"));
+        }
+        if (version == 0) != (encoding == AddressEncoding::Blech32) {
+            return Err("checksum does not match the witness version".to_string());
+        }
+        checked
+            .validate_segwit_padding()
+            .map_err(|e| e.to_string())?;
+
+        let mut payload: Vec<u8> = checked.byte_iter().collect();
+        if payload.len() < BLINDING_PUBKEY_LEN {
+            return Err("missing blinding pubkey".to_string());
+        }
+        let program = payload.split_off(BLINDING_PUBKEY_LEN);
+        check_blinding_pubkey(&payload)?;
+        check_witness_program(version, &program)?;
+        Ok(Self {
+            network,
+            encoding,
+            script_type: AddressScriptType::for_witness(version, &program),
+            witness_version: Some(version),
+            program,
+            blinding_pubkey: Some(payload),
+        })
+    }
+
+    fn decode_base58(address: &str) -> std::result::Result<Self, String> {
+        let mut payload = base58check_decode(address)?;
+        let prefix = payload.remove(0);
+
+        let blinded = NETWORKS
+            .into_iter()
+            .find(|network| base58_prefixes(*network).2 == Some(prefix));
+        let (network, prefix, blinding_pubkey) = if let Some(network) = blinded {
+            if payload.len() != 1 + BLINDING_PUBKEY_LEN + HASH_LEN {
+                return Err("invalid confidential payload length".to_string());
+            }
+            let pubkey = payload.drain(1..=BLINDING_PUBKEY_LEN).collect::<Vec<_>>();
+            check_blinding_pubkey(&pubkey)?;
+            (network, payload.remove(0), Some(pubkey))
+        } else {
+            let network = NETWORKS
+                .into_iter()
+                .find(|network| {
+                    let (p2pkh, p2sh, _) = base58_prefixes(*network);
+                    prefix == p2pkh || prefix == p2sh
+                })
+                .ok_or_else(|| format!("unknown version byte {prefix}"))?;
+            (network, prefix, None)
+        };
+
+        let (p2pkh, p2sh, _) = base58_prefixes(network);
+        let script_type = if prefix == p2pkh {
+            AddressScriptType::P2pkh
+        } else if prefix == p2sh {
+            AddressScriptType::P2sh
+        } else {
+            return Err(format!("unknown version byte {prefix}"));
+        };
+        if payload.len() != HASH_LEN {
+            return Err("invalid payload length".to_string());
+        }
+        Ok(Self {
+            network,
+            encoding: AddressEncoding::Base58,
+            script_type,
+            witness_version: None,
+            program: payload,
+            blinding_pubkey,
+        })
+    }
+}
+
+impl fmt::Display for ParsedAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(version) = self.witness_version else {
+            let (p2pkh, p2sh, blinded) = base58_prefixes(self.network);
+            let prefix = if self.script_type == AddressScriptType::P2pkh {
+                p2pkh
+            } else {
+                p2sh
+            };
+            let mut payload = Vec::with_capacity(2 + BLINDING_PUBKEY_LEN + HASH_LEN);
+            if let (Some(blinded), Some(pubkey)) = (blinded, &self.blinding_pubkey) {
+                payload.push(blinded);
+                payload.push(prefix);
+                payload.extend_from_slice(pubkey);
+            } else {
+                payload.push(prefix);
+            }
+            payload.extend_from_slice(&self.program);
+            return f.write_str(&base58check_encode(&payload));
+        };
+
+        let version = Fe32::try_from(version).map_err(|_| fmt::Error)?;
+        let hrp =
+            Hrp::parse(segwit_hrp(self.network, self.is_confidential())).map_err(|_| fmt::Error)?;
+        let Some(ref pubkey) = self.blinding_pubkey else {
+            let address = segwit::encode(hrp, version, &self.program).map_err(|_| fmt::Error)?;
+            return f.write_str(&address);
+        };
+        let bytes = pubkey.iter().chain(&self.program).copied();
+        let address: String = if version == Fe32::Q {
+            bytes
+                .bytes_to_fes()
+                .with_checksum::<Blech32>(&hrp)
+                .with_witness_version(version)
+                .chars()
+                .collect()
+        } else {
+            bytes
+                .bytes_to_fes()
+                .with_checksum::<Blech32m>(&hrp)
+                .with_witness_version(version)
+                .chars()
+                .collect()
+        };
+        f.write_str(&address)
+    }
+}
+
+impl FromStr for ParsedAddress {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+/// Decode an address and check it belongs to `network`
+///
+/// With no network only the address format is checked, as green-cli then
+/// uses its own default network.
+///
+/// # Errors
+///
+/// Returns an [`ErrorKind::InvalidAddress`] validation error if the address
+/// is malformed or belongs to another network.
+pub fn validate_address(address: &str, network: Option<Network>) -> Result<ParsedAddress> {
+    let parsed = ParsedAddress::parse(address)?;
+    match network {
+        Some(network) if !parsed.is_valid_for(network) => Err(invalid(format!(
+            "Address {address} is for {}, not {network}",
+            parsed.network
+        ))),
+        _ => Ok(parsed),
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::validation(ErrorKind::InvalidAddress, message)
+}
+
+/// Human readable part of segwit addresses on `network`
+const fn segwit_hrp(network: Network, confidential: bool) -> &'static str {
+    match (network, confidential) {
+        (Network::Mainnet, _) => "bc",
+        (Network::Testnet, _) => "tb",
+        (Network::ElectrumLocaltest, _) => "bcrt",
+        (Network::Liquid, false) => "ex",
+        (Network::Liquid, true) => "lq",
+        (Network::LiquidTestnet, false) => "tex",
+        (Network::LiquidTestnet, true) => "tlq",
+    }
+}
+
+/// P2PKH, P2SH and confidential version bytes of base58 addresses
+const fn base58_prefixes(network: Network) -> (u8, u8, Option<u8>) {
+    match network {
+        Network::Mainnet => (0, 5, None),
+        Network::Testnet | Network::ElectrumLocaltest => (111, 196, None),
+        Network::Liquid => (57, 39, Some(12)),
+        Network::LiquidTestnet => (36, 19, Some(23)),
+    }
+}
+
+fn check_blinding_pubkey(pubkey: &[u8]) -> std::result::Result<(), String> {
+    match pubkey.first() {
+        Some(0x02 | 0x03) if pubkey.len() == BLINDING_PUBKEY_LEN => Ok(()),
+        _ => Err("invalid blinding pubkey".to_string()),
+    }
+}
+
+fn check_witness_program(version: u8, program: &[u8]) -> std::result::Result<(), String> {
+    let valid = match version {
+        0 => matches!(program.len(), 20 | 32),
+        _ => (2..=40).contains(&program.len()),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid witness program length {}", program.len()))
+    }
+}
+
+fn sha256d(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(data)).into()
+}
+
+fn base58check_decode(address: &str) -> std::result::Result<Vec<u8>, String> {
+    // Big-endian base 256 digits of the decoded number
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in address.bytes() {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or("invalid base58 character")?;
+        for byte in bytes.iter_mut().rev() {
+            carry += usize::from(*byte) * 58;
+            *byte = carry.to_le_bytes()[0];
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, carry.to_le_bytes()[0]);
+            carry >>= 8;
+        }
+    }
+    let zeros = address.bytes().take_while(|&c| c == b'1').count();
+    let mut payload = vec![0; zeros];
+    payload.extend(bytes);
+
+    if payload.len() < 5 {
+        return Err("too short".to_string());
+    }
+    let checksum = payload.split_off(payload.len() - 4);
+    if sha256d(&payload)[..4] != checksum[..] {
+        return Err("invalid checksum".to_string());
+    }
+    Ok(payload)
+}
+
+fn base58check_encode(payload: &[u8]) -> String {
+    let mut data = payload.to_vec();
+    data.extend_from_slice(&sha256d(payload)[..4]);
+
+    // Little-endian base 58 digits of the encoded number
+    let mut digits: Vec<usize> = Vec::new();
+    for &byte in &data {
+        let mut carry = usize::from(byte);
+        for digit in &mut digits {
+            carry += *digit << 8;
+            *digit = carry % 58;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push(carry % 58);
+            carry /= 58;
+        }
+    }
+    let zeros = data.iter().take_while(|&&byte| byte == 0).count();
+    std::iter::repeat_n('1', zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|&digit| char::from(BASE58_ALPHABET[digit])),
+        )
+        .collect()
+}
@@ -0,0 +1,324 @@
+//! BIP21 and Liquid payment URIs
+
+use super::{validate_address, ParsedAddress};
+use crate::config::Network;
+use crate::error::{Error, ErrorKind, Result};
+use crate::types::common::{Address, AssetId, Satoshis};
+use crate::types::transaction::Addressee;
+use std::fmt;
+use std::str::FromStr;
+
+/// Satoshis in one coin, the unit of the `amount` parameter
+const COIN: Satoshis = 100_000_000;
+
+/// Decimal places of the `amount` parameter
+const AMOUNT_DECIMALS: usize = 8;
+
+/// A `bitcoin:` (BIP21) or `liquidnetwork:` payment URI
+///
+/// The scheme follows the network of the address. `amount` is written in
+/// whole coins with up to eight decimals and held here in satoshis; on Liquid
+/// a URI with an amount names the asset with `assetid`.
+///
+/// # Example
+///
+/// ```
+/// use green_rs::address::PaymentUri;
+///
+/// let uri = PaymentUri::new("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+///     .unwrap()
+///     .amount(150_000)
+///     .label("Order #42");
+/// assert_eq!(
+///     uri.to_string(),
+///     "bitcoin:bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4?amount=0.0015&label=Order%20%2342"
+/// );
+/// assert_eq!(uri.to_string().parse::<PaymentUri>().unwrap(), uri);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentUri {
+    /// Address to pay
+    pub address: Address,
+    /// Network of the address
+    pub network: Network,
+    /// Requested amount in satoshis
+    pub amount: Option<Satoshis>,
+    /// Label for the recipient
+    pub label: Option<String>,
+    /// Message describing the payment
+    pub message: Option<String>,
+    /// Asset to pay (Liquid)
+    pub asset_id: Option<AssetId>,
+}
+
+impl PaymentUri {
+    /// Payment URI for `address`, without parameters
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidAddress`] validation error if the
+    /// address is malformed
+    pub fn new(address: &str) -> Result<Self> {
+        let parsed = ParsedAddress::parse(address)?;
+        Ok(Self {
+            address: address.trim().to_string(),
+            network: parsed.network,
+            amount: None,
+            label: None,
+            message: None,
+            asset_id: None,
+        })
+    }
+
+    /// Parse a payment URI
+    ///
+    /// Unknown parameters are ignored, except `req-` parameters, which the
+    /// payer is required to understand.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Validation`] if the scheme does not match the
+    /// address's network (`InvalidAddress`), the address or amount is
+    /// malformed (`InvalidAddress`, `InvalidAmount`), a Liquid amount has no
+    /// asset, or a parameter is repeated or required but unknown (`Other`)
+    pub fn parse(uri: &str) -> Result<Self> {
+        let uri = uri.trim();
+        let (scheme, rest) = uri
+            .split_once(':')
+            .ok_or_else(|| invalid(ErrorKind::InvalidAddress, uri, "missing scheme"))?;
+        let liquid = if scheme.eq_ignore_ascii_case("bitcoin") {
+            false
+        } else if scheme.eq_ignore_ascii_case("liquidnetwork") {
+            true
+        } else {
+            return Err(invalid(ErrorKind::InvalidAddress, uri, "unknown scheme"));
+        };
+        let (address, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let mut payment = Self::new(address)?;
+        if payment.network.is_liquid() != liquid {
+            return Err(invalid(
+                ErrorKind::InvalidAddress,
+                uri,
+                &format!("{} address in a {scheme} URI", payment.network),
+            ));
+        }
+
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value)
+                .ok_or_else(|| invalid(ErrorKind::Other, uri, "malformed percent-encoding"))?;
+            let slot = match key {
+                "amount" => {
+                    if payment.amount.is_some() {
+                        return Err(invalid(ErrorKind::Other, uri, "amount is repeated"));
+                    }
+                    payment.amount = Some(parse_amount(&value).ok_or_else(|| {
+                        invalid(ErrorKind::InvalidAmount, uri, "malformed amount")
+                    })?);
+                    continue;
+                }
+                "label" => &mut payment.label,
+                "message" => &mut payment.message,
+                "assetid" => {
+                    if !is_asset_id(&value) {
+                        return Err(invalid(ErrorKind::Other, uri, "malformed assetid"));
+                    }
+                    &mut payment.asset_id
+                }
+                key if key.starts_with("req-") => {
+                    return Err(invalid(
+                        ErrorKind::Other,
+                        uri,
+                        &format!("unsupported required parameter {key}"),
+                    ));
+                }
+                _ => continue,
+            };
+            if slot.replace(value).is_some() {
+                return Err(invalid(
+                    ErrorKind::Other,
+                    uri,
+                    &format!("{key} is repeated"),
+                ));
+            }
+        }
+
+        if liquid && payment.amount.is_some() && payment.asset_id.is_none() {
+            return Err(invalid(ErrorKind::Other, uri, "amount without assetid"));
+        }
+        Ok(payment)
+    }
+
+    /// Set the requested amount in satoshis
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)] // Cannot be const due to Option::Some
+    pub fn amount(mut self, satoshi: Satoshis) -> Self {
+        self.amount = Some(satoshi);
+        self
+    }
+
+    /// Set the label
+    #[must_use]
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set the message
+    #[must_use]
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Set the asset to pay (Liquid)
+    #[must_use]
+    pub fn asset_id(mut self, asset_id: impl Into<AssetId>) -> Self {
+        self.asset_id = Some(asset_id.into());
+        self
+    }
+
+    /// URI scheme for the address's network
+    #[must_use]
+    pub const fn scheme(&self) -> &'static str {
+        if self.network.is_liquid() {
+            "liquidnetwork"
+        } else {
+            "bitcoin"
+        }
+    }
+
+    /// Check the address belongs to `network`
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidAddress`] validation error if the
+    /// address is for another network
+    pub fn validate(&self, network: Option<Network>) -> Result<ParsedAddress> {
+        validate_address(&self.address, network)
+    }
+
+    /// Recipient paying the requested amount
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidAmount`] validation error if the URI
+    /// has no amount
+    pub fn to_addressee(&self) -> Result<Addressee> {
+        let satoshi = self.amount.ok_or_else(|| {
+            Error::validation(
+                ErrorKind::InvalidAmount,
+                format!("Payment URI for {} has no amount", self.address),
+            )
+        })?;
+        Ok(Addressee {
+            address: self.address.clone(),
+            satoshi,
+            asset_id: self.asset_id.clone(),
+        })
+    }
+}
+
+impl fmt::Display for PaymentUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.scheme(), self.address)?;
+        let mut separator = '?';
+        let mut param = |f: &mut fmt::Formatter<'_>, key: &str, value: &str| {
+            let result = write!(f, "{separator}{key}={}", percent_encode(value));
+            separator = '&';
+            result
+        };
+        if let Some(amount) = self.amount {
+            param(f, "amount", &format_amount(amount))?;
+        }
+        if let Some(ref label) = self.label {
+            param(f, "label", label)?;
+        }
+        if let Some(ref message) = self.message {
+            param(f, "message", message)?;
+        }
+        if let Some(ref asset_id) = self.asset_id {
+            param(f, "assetid", asset_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PaymentUri {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+fn invalid(kind: ErrorKind, uri: &str, reason: &str) -> Error {
+    Error::validation(kind, format!("Invalid payment URI {uri}: {reason}"))
+}
+
+fn is_asset_id(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Parse a decimal coin amount into satoshis
+fn parse_amount(value: &str) -> Option<Satoshis> {
+    let (coins, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if coins.is_empty() && fraction.is_empty()
+        || fraction.len() > AMOUNT_DECIMALS
+        || !coins
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let coins: Satoshis = if coins.is_empty() {
+        0
+    } else {
+        coins.parse().ok()?
+    };
+    let fraction: Satoshis = format!("{fraction:0<AMOUNT_DECIMALS$}").parse().ok()?;
+    coins.checked_mul(COIN)?.checked_add(fraction)
+}
+
+/// Format satoshis as a decimal coin amount without trailing zeros
+fn format_amount(satoshi: Satoshis) -> String {
+    let fraction = format!("{:0>AMOUNT_DECIMALS$}", satoshi % COIN);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        (satoshi / COIN).to_string()
+    } else {
+        format!("{}.{fraction}", satoshi / COIN)
+    }
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail.get(..2)?;
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
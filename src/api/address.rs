@@ -10,10 +10,15 @@
 //! - Asset support for multisig setups
 //! - Address reuse is generally discouraged for privacy
 
+use crate::address::ParsedAddress;
+use crate::api::address_scan::{boxed_address_stream, AddressPager};
 use crate::types::address::{
-    AddressDetails, GetPreviousAddressesRequest, GetReceiveAddressRequest, ReceiveAddress,
+    AddressDetails, AddressUsageReport, GapLimitPolicy, GetPreviousAddressesRequest,
+    GetReceiveAddressRequest, ReceiveAddress, UpdateAddressRequest,
 };
+use crate::types::common::Pointer;
 use crate::Result;
+use futures::stream::{BoxStream, TryStreamExt};
 
 /// Address API trait for Green clients
 ///
@@ -50,11 +55,73 @@ pub trait AddressApi {
         request: GetPreviousAddressesRequest,
     ) -> Result<Vec<AddressDetails>>;
 
-    // TODO: Implement additional address methods
-    // - generate_address
-    // - validate_address
-    // - get_address_info
-    // - list_addresses
+    /// Update the label of one of the wallet's receive addresses
+    ///
+    /// Returns the address as updated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subaccount has no address at the pointer or
+    /// the CLI command fails
+    fn update_address(&self, request: UpdateAddressRequest) -> Result<AddressDetails>;
+
+    /// Set the label of the receive address at `pointer` in `subaccount`
+    ///
+    /// An empty label removes the existing one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address cannot be updated
+    fn set_address_label(
+        &self,
+        subaccount: u32,
+        pointer: Pointer,
+        label: &str,
+    ) -> Result<AddressDetails> {
+        self.update_address(UpdateAddressRequest::label(subaccount, pointer, label))
+    }
+
+    /// Parse an address offline and check it belongs to the configured network
+    ///
+    /// Without a configured network only the address format is checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidAddress`](crate::ErrorKind::InvalidAddress)
+    /// validation error if the address is malformed or for another network
+    fn validate_address(&self, address: &str) -> Result<ParsedAddress>;
+
+    /// Iterate over every previous receive address of `subaccount`, newest first
+    ///
+    /// Pages are fetched with
+    /// [`get_previous_addresses`](Self::get_previous_addresses) as the
+    /// iterator advances.
+    fn previous_address_pager(&self, subaccount: u32) -> AddressPager<'_, Self>
+    where
+        Self: Sized,
+    {
+        AddressPager::new(self, subaccount)
+    }
+
+    /// Report used and unused receive addresses of `subaccount`, the gap
+    /// after the last used one and reused addresses
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page of addresses cannot be retrieved
+    fn get_address_report(
+        &self,
+        subaccount: u32,
+        policy: GapLimitPolicy,
+    ) -> Result<AddressUsageReport>
+    where
+        Self: Sized,
+    {
+        let addresses = self
+            .previous_address_pager(subaccount)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(AddressUsageReport::new(subaccount, &addresses, policy))
+    }
 }
 
 /// Async Address API trait for Green clients
@@ -84,5 +151,72 @@ pub trait AsyncAddressApi {
         request: GetPreviousAddressesRequest,
     ) -> Result<Vec<AddressDetails>>;
 
-    // TODO: Implement additional async address methods
+    /// Update the label of one of the wallet's receive addresses
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subaccount has no address at the pointer or
+    /// the CLI command fails
+    async fn update_address(&self, request: UpdateAddressRequest) -> Result<AddressDetails>;
+
+    /// Set the label of the receive address at `pointer` in `subaccount`
+    ///
+    /// An empty label removes the existing one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address cannot be updated
+    async fn set_address_label(
+        &self,
+        subaccount: u32,
+        pointer: Pointer,
+        label: &str,
+    ) -> Result<AddressDetails> {
+        self.update_address(UpdateAddressRequest::label(subaccount, pointer, label))
+            .await
+    }
+
+    /// Parse an address offline and check it belongs to the configured network
+    ///
+    /// No command is run, so this does not need to be awaited.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidAddress`](crate::ErrorKind::InvalidAddress)
+    /// validation error if the address is malformed or for another network
+    fn validate_address(&self, address: &str) -> Result<ParsedAddress>;
+
+    /// Stream every previous receive address of `subaccount`, newest first
+    ///
+    /// Pages are fetched with
+    /// [`get_previous_addresses`](Self::get_previous_addresses) as the
+    /// stream is polled.
+    fn previous_address_stream(&self, subaccount: u32) -> BoxStream<'_, Result<AddressDetails>>
+    where
+        Self: Sync,
+    {
+        boxed_address_stream(self, subaccount)
+    }
+
+    /// Report used and unused receive addresses of `subaccount`
+    ///
+    /// See [`AddressApi::get_address_report`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page of addresses cannot be retrieved
+    async fn get_address_report(
+        &self,
+        subaccount: u32,
+        policy: GapLimitPolicy,
+    ) -> Result<AddressUsageReport>
+    where
+        Self: Sync,
+    {
+        let addresses: Vec<AddressDetails> = self
+            .previous_address_stream(subaccount)
+            .try_collect()
+            .await?;
+        Ok(AddressUsageReport::new(subaccount, &addresses, policy))
+    }
 }
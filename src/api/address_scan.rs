@@ -0,0 +1,151 @@
+//! Paginated address scanning
+//!
+//! [`AddressPager`] walks every previous receive address of a subaccount for
+//! a sync client, newest first, and [`address_stream`] does the same for an
+//! async client as a [`Stream`]. Both are returned by
+//! [`AddressApi::previous_address_pager`] and
+//! [`AsyncAddressApi::previous_address_stream`], and feed the
+//! [`AddressUsageReport`](crate::types::address::AddressUsageReport) of
+//! [`AddressApi::get_address_report`].
+//!
+//! Each page is requested with the lowest pointer of the page before it as
+//! `last_pointer`, until green-cli returns an empty page.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use green_rs::api::address::AddressApi;
+//! use green_rs::types::address::GapLimitPolicy;
+//! use green_rs::GreenClient;
+//!
+//! let client = GreenClient::new();
+//! let report = client.get_address_report(0, GapLimitPolicy::default())?;
+//! println!(
+//!     "{} used, {} unused, gap {}",
+//!     report.used_count, report.unused_count, report.gap
+//! );
+//! if let Some(warning) = report.gap_warning {
+//!     eprintln!("{warning}");
+//! }
+//! # Ok::<(), green_rs::Error>(())
+//! ```
+
+use crate::api::address::{AddressApi, AsyncAddressApi};
+use crate::api::paging::{next_item, page_stream, PageState};
+use crate::types::address::{AddressDetails, GetPreviousAddressesRequest};
+use crate::types::common::Pointer;
+use crate::Result;
+use futures::stream::{BoxStream, Stream, StreamExt};
+use std::collections::VecDeque;
+
+/// Iterator over the previous addresses of a subaccount, fetching pages as needed
+///
+/// Yields an error and stops if a page cannot be fetched.
+pub struct AddressPager<'a, C: ?Sized> {
+    client: &'a C,
+    pages: Pages,
+}
+
+impl<'a, C: AddressApi + ?Sized> AddressPager<'a, C> {
+    /// Page through the receive addresses of `subaccount`
+    #[must_use]
+    pub const fn new(client: &'a C, subaccount: u32) -> Self {
+        Self {
+            client,
+            pages: Pages::new(subaccount),
+        }
+    }
+}
+
+impl<C: AddressApi + ?Sized> Iterator for AddressPager<'_, C> {
+    type Item = Result<AddressDetails>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_item(&mut self.pages, |request| {
+            self.client.get_previous_addresses(request)
+        })
+    }
+}
+
+/// Stream of the previous addresses of `subaccount`
+///
+/// Yields an error and ends if a page cannot be fetched.
+pub fn address_stream<C>(
+    client: &C,
+    subaccount: u32,
+) -> impl Stream<Item = Result<AddressDetails>> + Send + '_
+where
+    C: AsyncAddressApi + Sync + ?Sized,
+{
+    page_stream(Pages::new(subaccount), move |request| {
+        client.get_previous_addresses(request)
+    })
+}
+
+/// Boxed form of [`address_stream`], as returned by the async trait
+pub(crate) fn boxed_address_stream<C>(
+    client: &C,
+    subaccount: u32,
+) -> BoxStream<'_, Result<AddressDetails>>
+where
+    C: AsyncAddressApi + Sync + ?Sized,
+{
+    address_stream(client, subaccount).boxed()
+}
+
+/// Paging state shared by the iterator and the stream
+struct Pages {
+    subaccount: u32,
+    buffer: VecDeque<AddressDetails>,
+    last_pointer: Option<Pointer>,
+    finished: bool,
+}
+
+impl Pages {
+    const fn new(subaccount: u32) -> Self {
+        Self {
+            subaccount,
+            buffer: VecDeque::new(),
+            last_pointer: None,
+            finished: false,
+        }
+    }
+}
+
+impl PageState for Pages {
+    type Item = AddressDetails;
+    type Request = GetPreviousAddressesRequest;
+    type Page = Vec<AddressDetails>;
+
+    fn request(&self) -> GetPreviousAddressesRequest {
+        GetPreviousAddressesRequest {
+            subaccount: Some(self.subaccount),
+            last_pointer: self.last_pointer,
+            unused_only: None,
+        }
+    }
+
+    fn accept(&mut self, list: Vec<AddressDetails>) {
+        let lowest = list.iter().map(|details| details.pointer).min();
+        match lowest {
+            // Stop on an empty page, or one that does not move towards pointer 0
+            Some(lowest) if self.last_pointer.is_none_or(|last| lowest < last) => {
+                self.last_pointer = Some(lowest);
+                self.buffer = list.into();
+            }
+            _ => self.finished = true,
+        }
+    }
+
+    fn next_buffered(&mut self) -> Option<AddressDetails> {
+        self.buffer.pop_front()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn finish(&mut self) {
+        self.finished = true;
+    }
+}
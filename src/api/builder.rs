@@ -13,7 +13,7 @@
 //! - Fluent API for chaining method calls
 
 use crate::error::Result;
-use crate::types::address::{GetPreviousAddressesRequest, GetReceiveAddressRequest};
+use crate::types::address::{AddressType, GetPreviousAddressesRequest, GetReceiveAddressRequest};
 use crate::types::common::Pointer;
 
 /// Builder trait for constructing API requests
@@ -41,15 +41,16 @@ pub trait RequestBuilder {
 ///
 /// ```no_run
 /// use green_rs::api::builder::GetReceiveAddressBuilder;
+/// use green_rs::types::address::AddressType;
 ///
 /// let request = GetReceiveAddressBuilder::new()
 ///     .subaccount(0)
-///     .address_type("p2wpkh".to_string())
+///     .address_type(AddressType::P2wpkh)
 ///     .build();
 /// ```
 pub struct GetReceiveAddressBuilder {
     subaccount: Option<u32>,
-    address_type: Option<String>,
+    address_type: Option<AddressType>,
 }
 
 impl Default for GetReceiveAddressBuilder {
@@ -76,9 +77,9 @@ impl GetReceiveAddressBuilder {
         self
     }
 
-    /// Set the address type (e.g., "p2wpkh", "p2sh-p2wpkh")
+    /// Set the address type
     #[must_use]
-    pub fn address_type(mut self, address_type: String) -> Self {
+    pub fn address_type(mut self, address_type: AddressType) -> Self {
         self.address_type = Some(address_type);
         self
     }
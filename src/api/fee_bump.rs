@@ -0,0 +1,174 @@
+//! Fee bumping for stuck transactions
+//!
+//! [`TransactionApi::bump_fee`] and [`AsyncTransactionApi::bump_fee`] replace
+//! an unconfirmed wallet transaction with one paying a higher fee
+//! (replace-by-fee). The replacement spends the same inputs to the same
+//! recipients, taking the extra fee from change.
+//!
+//! Before anything is broadcast the original is checked against the BIP125
+//! rules the network will apply: it must be ours, unconfirmed, not already
+//! replaced and signalling replaceability, and the new fee rate must pay
+//! the original fee plus the incremental relay fee for the replacement's
+//! own size.
+//!
+//! [`TransactionApi::bump_fee`]: crate::api::TransactionApi::bump_fee
+//! [`AsyncTransactionApi::bump_fee`]: crate::api::AsyncTransactionApi::bump_fee
+//!
+//! # Example
+//!
+//! ```no_run
+//! use green_rs::api::TransactionApi;
+//! use green_rs::types::FeeRate;
+//! use green_rs::GreenClient;
+//!
+//! let client = GreenClient::new();
+//! let bump = client.bump_fee("a1b2c3...", FeeRate::from_sat_per_vb(25))?;
+//! println!("{} replaced by {}", bump.old_txid, bump.new_txid);
+//! # Ok::<(), green_rs::Error>(())
+//! ```
+
+use crate::error::{Error, ErrorKind, Result};
+use crate::types::common::Satoshis;
+use crate::types::fee::FeeRate;
+use crate::types::transaction::{
+    Addressee, CreateTransactionRequest, FeeBump, Transaction, UtxoRef,
+};
+
+/// Fee rate a replacement must add on top of the original fee
+///
+/// This is the default `-incrementalrelayfee` of Bitcoin Core, 1 sat/vB.
+pub const INCREMENTAL_RELAY_FEE_RATE: FeeRate = FeeRate::from_sat_per_vb(1);
+
+/// Smallest fee a replacement of `original_fee` with size `vsize` may pay
+///
+/// BIP125 requires the replacement to pay at least the original's fee plus
+/// the incremental relay fee for its own size.
+#[must_use]
+pub fn min_replacement_fee(original_fee: Satoshis, vsize: u32) -> Satoshis {
+    original_fee + INCREMENTAL_RELAY_FEE_RATE.fee_for_vsize(vsize)
+}
+
+/// Smallest fee rate that can replace `original`
+///
+/// Assumes the replacement has the same size as the original, which holds
+/// when only its change output shrinks.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidResponse`] if the wallet did not report the
+/// original's fee or size
+pub fn min_replacement_fee_rate(original: &Transaction) -> Result<FeeRate> {
+    let (fee, vsize) = fee_and_vsize(original)?;
+    let min_fee = min_replacement_fee(fee, vsize);
+    Ok(FeeRate::from_sat_per_kvb(
+        (min_fee * 1_000).div_ceil(u64::from(vsize)),
+    ))
+}
+
+/// Check that `original` can be replaced at `fee_rate` and build the
+/// request that replaces it
+pub(crate) fn replacement_request(
+    original: &Transaction,
+    fee_rate: FeeRate,
+) -> Result<CreateTransactionRequest> {
+    let txid = &original.txid;
+    if !original.spends_wallet_outputs() {
+        return Err(Error::validation(
+            ErrorKind::Other,
+            format!("transaction {txid} does not spend wallet outputs"),
+        ));
+    }
+    if original.block_height.is_some() || original.confirmations > 0 {
+        return Err(Error::validation(
+            ErrorKind::Other,
+            format!("transaction {txid} is already confirmed"),
+        ));
+    }
+    if original.has_been_replaced {
+        return Err(Error::validation(
+            ErrorKind::Other,
+            format!("transaction {txid} has already been replaced"),
+        ));
+    }
+    if !original.signals_rbf() {
+        return Err(Error::validation(
+            ErrorKind::Other,
+            format!("transaction {txid} does not signal replace-by-fee"),
+        ));
+    }
+    let min_rate = min_replacement_fee_rate(original)?;
+    if fee_rate < min_rate {
+        return Err(Error::validation(
+            ErrorKind::InvalidFee,
+            format!(
+                "fee rate {fee_rate} is below the minimum replacement rate {min_rate} \
+                 for {txid}"
+            ),
+        ));
+    }
+
+    let addressees = original
+        .outputs
+        .iter()
+        .filter(|output| !output.is_change)
+        .filter_map(|output| {
+            Some(Addressee {
+                address: output.address.clone()?,
+                satoshi: output.satoshi,
+                asset_id: output.asset_id.clone(),
+            })
+        })
+        .collect();
+    let utxos = original
+        .inputs
+        .iter()
+        .filter(|input| input.is_relevant)
+        .map(|input| UtxoRef {
+            txid: input.txid.clone(),
+            vout: input.vout,
+        })
+        .collect();
+    let subaccount = original
+        .inputs
+        .iter()
+        .find_map(|input| input.subaccount)
+        .or_else(|| original.subaccounts.first().copied());
+
+    Ok(CreateTransactionRequest {
+        addressees: Some(addressees),
+        fee_rate: Some(fee_rate),
+        subaccount,
+        send_all: false,
+        memo: original.memo.clone(),
+        utxos: Some(utxos),
+        previous_transaction: Some(original.clone()),
+    })
+}
+
+/// Summarize a replacement of `original`
+pub(crate) fn fee_bump(
+    original: &Transaction,
+    replacement: Transaction,
+    fee_rate: FeeRate,
+) -> FeeBump {
+    FeeBump {
+        old_txid: original.txid.clone(),
+        new_txid: replacement.txid.clone(),
+        old_fee: original.fee.unwrap_or_default(),
+        new_fee: replacement.fee.unwrap_or_default(),
+        fee_rate,
+        transaction: replacement,
+    }
+}
+
+/// Fee and vsize the wallet reported for `tx`, from its weight if needed
+pub(crate) fn fee_and_vsize(tx: &Transaction) -> Result<(Satoshis, u32)> {
+    let vsize = tx
+        .vsize
+        .or_else(|| tx.weight.map(|weight| weight.div_ceil(4)))
+        .filter(|vsize| *vsize > 0);
+    match (tx.fee, vsize) {
+        (Some(fee), Some(vsize)) => Ok((fee, vsize)),
+        _ => Err(Error::InvalidResponse),
+    }
+}
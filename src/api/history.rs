@@ -0,0 +1,159 @@
+//! Paginated transaction history
+//!
+//! [`TransactionPager`] walks the history of a sync client one page at a
+//! time, and [`transaction_stream`] does the same for an async client as a
+//! [`Stream`]. Both are returned by
+//! [`TransactionApi::transaction_pager`] and
+//! [`AsyncTransactionApi::transaction_stream`].
+//!
+//! Pages are fetched only when the previous one has been consumed, so
+//! stopping early (with `take`, `find` or by dropping the pager) fetches
+//! nothing further. Transactions come newest first, filtered by a
+//! [`TransactionFilter`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use green_rs::api::TransactionApi;
+//! use green_rs::types::{TransactionFilter, TxDirection};
+//! use green_rs::GreenClient;
+//!
+//! let client = GreenClient::new();
+//! let filter = TransactionFilter::new()
+//!     .subaccount(0)
+//!     .since(1_700_000_000)
+//!     .direction(TxDirection::Incoming);
+//!
+//! for tx in client.transaction_pager(filter) {
+//!     let tx = tx?;
+//!     println!("{} received at {:?}", tx.txid, tx.timestamp);
+//! }
+//! # Ok::<(), green_rs::Error>(())
+//! ```
+
+use crate::api::paging::{next_item, page_stream, PageState};
+use crate::api::transaction::{AsyncTransactionApi, TransactionApi};
+use crate::types::transaction::{Transaction, TransactionFilter, TransactionList};
+use crate::Result;
+use futures::stream::{BoxStream, Stream, StreamExt};
+use std::collections::VecDeque;
+
+/// Transactions fetched per page unless the filter says otherwise
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// Iterator over transaction history, fetching pages as needed
+///
+/// Yields an error and stops if a page cannot be fetched.
+pub struct TransactionPager<'a, C: ?Sized> {
+    client: &'a C,
+    pages: Pages,
+}
+
+impl<'a, C: TransactionApi + ?Sized> TransactionPager<'a, C> {
+    /// Page through the history of `client` matching `filter`
+    #[must_use]
+    pub const fn new(client: &'a C, filter: TransactionFilter) -> Self {
+        Self {
+            client,
+            pages: Pages::new(filter),
+        }
+    }
+}
+
+impl<C: TransactionApi + ?Sized> Iterator for TransactionPager<'_, C> {
+    type Item = Result<Transaction>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_item(&mut self.pages, |(subaccount, first, count)| {
+            self.client
+                .get_transactions(subaccount, Some(first), Some(count))
+        })
+    }
+}
+
+/// Stream of the history of `client` matching `filter`
+///
+/// Yields an error and ends if a page cannot be fetched.
+pub fn transaction_stream<C>(
+    client: &C,
+    filter: TransactionFilter,
+) -> impl Stream<Item = Result<Transaction>> + Send + '_
+where
+    C: AsyncTransactionApi + Sync + ?Sized,
+{
+    page_stream(Pages::new(filter), move |(subaccount, first, count)| {
+        client.get_transactions(subaccount, Some(first), Some(count))
+    })
+}
+
+/// Boxed form of [`transaction_stream`], as returned by the async trait
+pub(crate) fn boxed_transaction_stream<C>(
+    client: &C,
+    filter: TransactionFilter,
+) -> BoxStream<'_, Result<Transaction>>
+where
+    C: AsyncTransactionApi + Sync + ?Sized,
+{
+    transaction_stream(client, filter).boxed()
+}
+
+/// Paging state shared by the iterator and the stream
+struct Pages {
+    filter: TransactionFilter,
+    buffer: VecDeque<Transaction>,
+    next_first: u32,
+    finished: bool,
+}
+
+impl Pages {
+    const fn new(filter: TransactionFilter) -> Self {
+        Self {
+            filter,
+            buffer: VecDeque::new(),
+            next_first: 0,
+            finished: false,
+        }
+    }
+}
+
+impl PageState for Pages {
+    type Item = Transaction;
+    // Subaccount, first transaction and count for `get_transactions`
+    type Request = (Option<u32>, u32, u32);
+    type Page = TransactionList;
+
+    fn request(&self) -> Self::Request {
+        let count = self.filter.page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+        (self.filter.subaccount, self.next_first, count)
+    }
+
+    fn accept(&mut self, list: TransactionList) {
+        let received = u32::try_from(list.transactions.len()).unwrap_or(u32::MAX);
+        self.next_first = self.next_first.saturating_add(received);
+        // Block times are not monotonic, so only a page entirely before the
+        // range ends the walk
+        let past_range = list
+            .transactions
+            .iter()
+            .all(|tx| self.filter.is_past_range(tx));
+        self.finished = !list.more || received == 0 || past_range;
+        self.buffer = list.transactions.into();
+    }
+
+    fn next_buffered(&mut self) -> Option<Transaction> {
+        while let Some(tx) = self.buffer.pop_front() {
+            if self.filter.matches(&tx) {
+                return Some(tx);
+            }
+        }
+        None
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn finish(&mut self) {
+        self.finished = true;
+    }
+}
@@ -1,7 +1,11 @@
 //! API modules for Green client
 
 pub mod address;
+pub mod address_scan;
 pub mod builder;
+pub mod fee_bump;
+pub mod history;
+pub(crate) mod paging;
 pub mod subaccount;
 pub mod transaction;
 pub mod utxo;
@@ -9,4 +13,4 @@ pub mod wallet;
 
 // Re-export commonly used traits
 pub use transaction::{AsyncTransactionApi, TransactionApi, TxBuilder};
-pub use wallet::{AsyncWalletExt, WalletExt};
+pub use wallet::{AsyncWalletApi, AsyncWalletExt, WalletApi, WalletExt};
@@ -0,0 +1,91 @@
+//! Paging shared by the history and address pagers
+//!
+//! A [`PageState`] knows how to request the next page and which fetched
+//! items are left to yield. [`next_item`] drives it for a sync iterator and
+//! [`page_stream`] for an async [`Stream`], both fetching a page through the
+//! given closure only once the previous one has been consumed.
+
+use crate::Result;
+use futures::stream::{self, Stream};
+use std::future::Future;
+
+/// Cursor and buffered items of a paged listing
+pub trait PageState {
+    /// Item yielded to the caller
+    type Item;
+    /// Arguments of the call fetching a page
+    type Request;
+    /// Page returned by that call
+    type Page;
+
+    /// Arguments for the next page
+    fn request(&self) -> Self::Request;
+
+    /// Buffer a fetched page and advance the cursor
+    fn accept(&mut self, page: Self::Page);
+
+    /// Next item already fetched
+    fn next_buffered(&mut self) -> Option<Self::Item>;
+
+    /// Whether no further page will be fetched
+    fn is_finished(&self) -> bool;
+
+    /// Stop fetching pages
+    fn finish(&mut self);
+}
+
+/// Next item of `pages`, fetching pages with `fetch` as needed
+///
+/// Yields an error and stops if a page cannot be fetched.
+pub fn next_item<S: PageState>(
+    pages: &mut S,
+    mut fetch: impl FnMut(S::Request) -> Result<S::Page>,
+) -> Option<Result<S::Item>> {
+    loop {
+        if let Some(item) = pages.next_buffered() {
+            return Some(Ok(item));
+        }
+        if pages.is_finished() {
+            return None;
+        }
+        match fetch(pages.request()) {
+            Ok(page) => pages.accept(page),
+            Err(err) => {
+                pages.finish();
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+/// Stream of the items of `pages`, fetching pages with `fetch` as needed
+///
+/// Yields an error and ends if a page cannot be fetched.
+pub fn page_stream<'a, S, F, Fut>(
+    pages: S,
+    fetch: F,
+) -> impl Stream<Item = Result<S::Item>> + Send + 'a
+where
+    S: PageState + Send + 'a,
+    S::Item: Send,
+    F: Fn(S::Request) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<S::Page>> + Send + 'a,
+{
+    stream::unfold((pages, fetch), |(mut pages, fetch)| async move {
+        loop {
+            if let Some(item) = pages.next_buffered() {
+                return Some((Ok(item), (pages, fetch)));
+            }
+            if pages.is_finished() {
+                return None;
+            }
+            match fetch(pages.request()).await {
+                Ok(page) => pages.accept(page),
+                Err(err) => {
+                    pages.finish();
+                    return Some((Err(err), (pages, fetch)));
+                }
+            }
+        }
+    })
+}
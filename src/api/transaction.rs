@@ -12,14 +12,26 @@
 //! - Blinding factors must be managed for confidential outputs
 //! - Asset issuance and reissuance are supported
 
-use crate::types::common::{Address, AssetId, Satoshis, Script};
+use crate::address::{validate_address, AddressBook, PaymentUri};
+use crate::api::fee_bump::{fee_bump, replacement_request};
+use crate::api::history::{boxed_transaction_stream, TransactionPager};
+use crate::client::GreenClient;
+use crate::coin_selection::DEFAULT_DUST_LIMIT;
+use crate::transport::{CliTransport, ProcessTransport};
+use crate::types::common::{Address, AssetId, Satoshis, Script, TxId};
+use crate::types::fee::FeeRate;
 use crate::types::transaction::{
-    CreateTransactionRequest, CreateTransactionResult, Transaction, TransactionList, TxOutput,
+    Addressee, CreateTransactionRequest, CreateTransactionResult, FeeBump, Transaction,
+    TransactionFilter, TransactionList, TxOutput, UtxoRef,
 };
-use crate::Result;
-use serde_json::json;
+use crate::{Error, ErrorKind, Result};
+use futures::stream::BoxStream;
+use serde_json::{json, Value};
 use tempfile::NamedTempFile;
 
+/// Largest fee a [`TxBuilder`] sweep may pay, in percent of the swept amount
+pub const DEFAULT_MAX_SWEEP_FEE_PERCENT: f64 = 10.0;
+
 /// Transaction API trait for Green clients
 ///
 /// Provides synchronous methods for transaction operations including
@@ -42,6 +54,13 @@ pub trait TransactionApi {
         request: CreateTransactionRequest,
     ) -> Result<CreateTransactionResult>;
 
+    /// Create, sign and broadcast a transaction in one step
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be created or sent.
+    fn send_transaction(&self, request: CreateTransactionRequest) -> Result<Transaction>;
+
     /// Send to an address
     ///
     /// # Errors
@@ -72,6 +91,36 @@ pub trait TransactionApi {
     ///
     /// Returns an error if details cannot be fetched.
     fn get_transaction_details(&self, txid: &str) -> Result<Transaction>;
+
+    /// Iterate over transaction history matching `filter`, newest first
+    ///
+    /// Pages are fetched with [`get_transactions`](Self::get_transactions)
+    /// as the iterator advances.
+    fn transaction_pager(&self, filter: TransactionFilter) -> TransactionPager<'_, Self>
+    where
+        Self: Sized,
+    {
+        TransactionPager::new(self, filter)
+    }
+
+    /// Replace an unconfirmed transaction with one paying `new_fee_rate`
+    ///
+    /// The replacement spends the same inputs to the same recipients and is
+    /// signed and broadcast in one step. See [`crate::api::fee_bump`] for the
+    /// checks made first.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if the transaction is not ours, is
+    /// confirmed or already replaced, does not signal RBF, or
+    /// `new_fee_rate` is below the BIP125 minimum; otherwise an error if
+    /// the transaction cannot be fetched or the replacement cannot be sent.
+    fn bump_fee(&self, txid: &str, new_fee_rate: FeeRate) -> Result<FeeBump> {
+        let original = self.get_transaction_details(txid)?;
+        let request = replacement_request(&original, new_fee_rate)?;
+        let replacement = self.send_transaction(request)?;
+        Ok(fee_bump(&original, replacement, new_fee_rate))
+    }
 }
 
 /// Builder object for creating transactions
@@ -80,6 +129,10 @@ pub trait TransactionApi {
 /// with multiple inputs and outputs. The builder pattern ensures
 /// transactions are built incrementally and validated before signing.
 ///
+/// Signing and broadcasting are delegated to `green-cli tx sign` and
+/// `green-cli tx send` through the builder's client, operating on the file
+/// written by [`TxBuilder::dump`].
+///
 /// # Liquid Network Support
 ///
 /// - Supports multi-asset transactions
@@ -91,10 +144,11 @@ pub trait TransactionApi {
 ///
 /// ```no_run
 /// use green_rs::api::TxBuilder;
+/// use green_rs::types::FeeRate;
 ///
 /// let tx = TxBuilder::new()
 ///     .add_output("ex1q...".to_string(), 100000)
-///     .set_fee_rate(1000)
+///     .set_fee_rate(FeeRate::from_sat_per_kvb(1000))
 ///     .dump()
 ///     .expect("Failed to dump transaction")
 ///     .sign()
@@ -102,13 +156,19 @@ pub trait TransactionApi {
 ///     .send()
 ///     .expect("Failed to send transaction");
 /// ```
-pub struct TxBuilder {
+pub struct TxBuilder<T = ProcessTransport> {
+    client: GreenClient<T>,
     outputs: Vec<TxOutput>,
     inputs: Vec<String>, // Store input UTXOs
-    fee_rate: Option<u64>,
+    fee_rate: Option<FeeRate>,
     subaccount: Option<u32>,
-    temp_file_path: Option<String>,
-    json_data: Option<String>, // Store JSON representation
+    send_all: bool,
+    sweep_asset: Option<AssetId>,
+    max_fee_percent: f64,
+    dust_limit: Satoshis,
+    temp_file: Option<NamedTempFile>,
+    json_data: Option<String>,   // Store JSON representation
+    signed_data: Option<String>, // Signed payload returned by green-cli
 }
 
 impl Default for TxBuilder {
@@ -118,20 +178,37 @@ impl Default for TxBuilder {
 }
 
 impl TxBuilder {
-    /// Initialize a new `TxBuilder`
+    /// Initialize a new `TxBuilder` using a default `GreenClient`
     #[must_use]
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
+        Self::with_client(GreenClient::new())
+    }
+}
+
+impl<T: CliTransport> TxBuilder<T> {
+    /// Initialize a new `TxBuilder` that signs and broadcasts through `client`
+    #[must_use]
+    pub const fn with_client(client: GreenClient<T>) -> Self {
         Self {
+            client,
             outputs: Vec::new(),
             inputs: Vec::new(),
             fee_rate: None,
             subaccount: None,
-            temp_file_path: None,
+            send_all: false,
+            sweep_asset: None,
+            max_fee_percent: DEFAULT_MAX_SWEEP_FEE_PERCENT,
+            dust_limit: DEFAULT_DUST_LIMIT,
+            temp_file: None,
             json_data: None,
+            signed_data: None,
         }
     }
 
     /// Add an output to the transaction
+    ///
+    /// The address is validated against the client's configured network
+    /// when the builder is dumped or turned into a request.
     #[must_use]
     pub fn add_output(mut self, address: Address, amount: Satoshis) -> Self {
         self.outputs.push(TxOutput {
@@ -143,10 +220,46 @@ impl TxBuilder {
         self
     }
 
+    /// Add an output paying a payment URI's amount (and asset, on Liquid)
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidAmount`] validation error if the URI
+    /// has no amount
+    pub fn add_payment_uri(mut self, uri: PaymentUri) -> Result<Self> {
+        let satoshi = uri.to_addressee()?.satoshi;
+        self.outputs.push(TxOutput {
+            address: Some(uri.address),
+            satoshi,
+            asset_id: uri.asset_id,
+            script_pubkey: Script::default(),
+            ..Default::default()
+        });
+        Ok(self)
+    }
+
+    /// Add an output paying the address book entry named `name`
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Validation`] if the book has no such entry
+    /// (`NotFound`), or its address is invalid for the client's network
+    /// (`InvalidAddress`)
+    pub fn add_book_output(self, book: &AddressBook, name: &str, amount: Satoshis) -> Result<Self> {
+        let entry = book.get(name).ok_or_else(|| {
+            Error::validation(
+                ErrorKind::NotFound,
+                format!("No address book entry named {name}"),
+            )
+        })?;
+        validate_address(&entry.address, self.client.config().network)?;
+        Ok(self.add_output(entry.address.clone(), amount))
+    }
+
     /// Set the fee rate
     #[must_use]
     #[allow(clippy::missing_const_for_fn)] // Cannot be const due to Option::Some
-    pub fn set_fee_rate(mut self, fee_rate: u64) -> Self {
+    pub fn set_fee_rate(mut self, fee_rate: FeeRate) -> Self {
         self.fee_rate = Some(fee_rate);
         self
     }
@@ -166,80 +279,198 @@ impl TxBuilder {
         self
     }
 
+    /// Send everything the transaction spends to `address`, after fees
+    ///
+    /// Drains the subaccount, or only the inputs added with
+    /// [`TxBuilder::add_input`], into a single output. [`TxBuilder::dump`]
+    /// then checks the sweep before anything is signed.
+    #[must_use]
+    pub fn send_all(mut self, address: Address) -> Self {
+        self.send_all = true;
+        self.add_output(address, 0)
+    }
+
+    /// Drain `subaccount` to `address`, after fees
+    #[must_use]
+    pub fn sweep_subaccount(self, subaccount: u32, address: Address) -> Self {
+        self.set_subaccount(subaccount).send_all(address)
+    }
+
+    /// Sweep only `asset_id` (Liquid)
+    ///
+    /// Fees are still paid in the policy asset.
+    #[must_use]
+    pub fn set_sweep_asset(mut self, asset_id: AssetId) -> Self {
+        self.sweep_asset = Some(asset_id);
+        self
+    }
+
+    /// Refuse sweeps whose fee exceeds `percent` of the swept amount
+    ///
+    /// In a single asset sweep the L-BTC fee is compared with the L-BTC the
+    /// sweep spends instead. Defaults to [`DEFAULT_MAX_SWEEP_FEE_PERCENT`].
+    #[must_use]
+    pub const fn set_max_fee_percent(mut self, percent: f64) -> Self {
+        self.max_fee_percent = percent;
+        self
+    }
+
+    /// Refuse sweeps leaving less than `dust_limit` after fees
+    ///
+    /// Defaults to 546 sat.
+    #[must_use]
+    pub const fn set_dust_limit(mut self, dust_limit: Satoshis) -> Self {
+        self.dust_limit = dust_limit;
+        self
+    }
+
+    /// Request creating the transaction described by the builder
+    ///
+    /// Inputs are spent explicitly through the request's `utxos`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Validation`] if an input is not of the form
+    /// `txid:vout`, or an output address is invalid for the client's network
+    pub fn to_request(&self) -> Result<CreateTransactionRequest> {
+        self.validate_outputs()?;
+        let utxos = self
+            .inputs
+            .iter()
+            .map(|input| {
+                input
+                    .split_once(':')
+                    .and_then(|(txid, vout)| {
+                        Some(UtxoRef {
+                            txid: txid.to_string(),
+                            vout: vout.parse().ok()?,
+                        })
+                    })
+                    .ok_or_else(|| {
+                        Error::validation(
+                            ErrorKind::Other,
+                            format!("Invalid input {input}, expected txid:vout"),
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(CreateTransactionRequest {
+            addressees: Some(
+                self.dumped_outputs()
+                    .into_iter()
+                    .map(|output| Addressee {
+                        address: output.address.unwrap_or_default(),
+                        satoshi: output.satoshi,
+                        asset_id: output.asset_id,
+                    })
+                    .collect(),
+            ),
+            fee_rate: self.fee_rate,
+            subaccount: self.subaccount,
+            send_all: self.send_all,
+            utxos: (!utxos.is_empty()).then_some(utxos),
+            ..Default::default()
+        })
+    }
+
     /// Serialize and dump transaction data
     ///
+    /// Any previously signed payload is discarded, since it no longer
+    /// matches the dumped transaction. A sweep is first created with
+    /// green-cli to check its amount and fee.
+    ///
     /// # Errors
     ///
-    /// Returns an error if creating the temp file or serializing fails
+    /// Returns an error if an output address is invalid for the client's
+    /// network, if creating the temp file or serializing fails, or if a sweep
+    /// fails the checks of [`TxBuilder::check_sweep`]
     pub fn dump(mut self) -> Result<Self> {
+        self.validate_outputs()?;
+        if self.send_all {
+            self.check_sweep()?;
+        }
+
         // Create a JSON representation of the transaction
         let tx_data = json!({
-            "outputs": self.outputs,
+            "outputs": self.dumped_outputs(),
             "inputs": self.inputs,
             "fee_rate": self.fee_rate,
-            "subaccount": self.subaccount
+            "subaccount": self.subaccount,
+            "send_all": self.send_all
         });
 
         let serialized = serde_json::to_string_pretty(&tx_data)?;
-        self.json_data = Some(serialized.clone());
 
-        // Write to a temporary file
+        // Write to a temporary file that lives as long as the builder
         let temp_file = NamedTempFile::new()?;
-        let temp_path = temp_file.path().to_string_lossy().to_string();
-        std::fs::write(&temp_path, &serialized)?;
+        std::fs::write(temp_file.path(), &serialized)?;
 
-        self.temp_file_path = Some(temp_path.clone());
-        println!("Transaction data dumped to: {temp_path}");
+        self.json_data = Some(serialized);
+        self.temp_file = Some(temp_file);
+        self.signed_data = None;
 
         Ok(self)
     }
 
     /// Sign the transaction using green-cli
     ///
+    /// Runs `green-cli tx sign` on the dumped file and keeps the signed
+    /// payload, which replaces the file contents so that [`TxBuilder::send`]
+    /// broadcasts the signed transaction.
+    ///
     /// # Errors
     ///
-    /// Returns an error if no transaction data exists or signing fails
-    pub fn sign(self) -> Result<Self> {
-        if let Some(ref path) = self.temp_file_path {
-            println!("Signing transaction from file: {path}");
-            // TODO: Execute green-cli tx sign command
-            // Example: green-cli tx sign --file <path>
-        } else {
-            return Err(crate::Error::unexpected(
-                "No transaction data to sign. Call dump() first.",
-            ));
-        }
+    /// Returns an error if no transaction data exists, green-cli fails,
+    /// or the signed payload is not valid JSON
+    pub fn sign(mut self) -> Result<Self> {
+        let path = self
+            .temp_path_str()
+            .ok_or_else(|| Error::unexpected("No transaction data to sign. Call dump() first."))?;
+
+        let output = self
+            .client
+            .run_command(&["tx", "sign", "--file", &path, "--json"])?;
+        let signed: Value = serde_json::from_str(&output)?;
+        let serialized = serde_json::to_string_pretty(&signed)?;
+        std::fs::write(&path, &serialized)?;
+
+        self.signed_data = Some(serialized);
         Ok(self)
     }
 
     /// Send or broadcast the transaction using green-cli
     ///
+    /// Runs `green-cli tx send` on the signed file and returns the
+    /// transaction ID reported by green-cli.
+    ///
     /// # Errors
     ///
-    /// Returns an error if no transaction data exists or broadcasting fails
-    pub fn send(self) -> Result<String> {
-        self.temp_file_path.as_ref().map_or_else(
-            || {
-                Err(crate::Error::unexpected(
-                    "No transaction data to broadcast. Call dump() and sign() first.",
-                ))
-            },
-            |path| {
-                println!("Broadcasting transaction from file: {path}");
-                // TODO: Execute green-cli tx send command
-                // Example: green-cli tx send --file <path>
-                // Return transaction ID
-                Ok("dummy_txid".to_string())
-            },
-        )
+    /// Returns an error if the transaction has not been dumped and signed,
+    /// green-cli fails, or its output does not contain a transaction ID
+    pub fn send(self) -> Result<TxId> {
+        let path = self.temp_path_str().ok_or_else(|| {
+            Error::unexpected("No transaction data to broadcast. Call dump() and sign() first.")
+        })?;
+        if self.signed_data.is_none() {
+            return Err(Error::unexpected(
+                "Transaction has not been signed. Call sign() first.",
+            ));
+        }
+
+        let output = self
+            .client
+            .run_command(&["tx", "send", "--file", &path, "--json"])?;
+        parse_txid(&output)
     }
 
     /// Alias for `send()`
     ///
     /// # Errors
     ///
-    /// Returns an error if no transaction data exists or broadcasting fails
-    pub fn broadcast(self) -> Result<String> {
+    /// Returns an error if the transaction has not been dumped and signed,
+    /// green-cli fails, or its output does not contain a transaction ID
+    pub fn broadcast(self) -> Result<TxId> {
         self.send()
     }
 
@@ -249,10 +480,145 @@ impl TxBuilder {
         self.json_data.as_deref()
     }
 
+    /// Get the signed payload returned by green-cli
+    #[must_use]
+    pub fn signed_json(&self) -> Option<&str> {
+        self.signed_data.as_deref()
+    }
+
     /// Get the temporary file path
     #[must_use]
     pub fn get_temp_path(&self) -> Option<&str> {
-        self.temp_file_path.as_deref()
+        self.temp_file
+            .as_ref()
+            .and_then(|file| file.path().to_str())
+    }
+
+    /// Create the sweep with green-cli, without signing it, and check it
+    ///
+    /// Returns the amount swept to the destination and the fee.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Validation`] of kind `InvalidAmount` unless the
+    /// sweep pays exactly one address, of kind `InsufficientFunds` if the
+    /// swept amount after fees is below the dust limit, and of kind
+    /// `InvalidFee` if the fee exceeds the maximum percentage of the amount
+    /// swept; returns an error if green-cli cannot create the transaction
+    ///
+    /// When sweeping an asset other than the fee asset, the fee is instead
+    /// compared with the L-BTC the sweep spends: the fee plus its change.
+    pub fn check_sweep(&self) -> Result<(Satoshis, Satoshis)> {
+        if self.outputs.len() != 1 {
+            return Err(Error::validation(
+                ErrorKind::InvalidAmount,
+                format!(
+                    "A sweep pays exactly one address, {} outputs given",
+                    self.outputs.len()
+                ),
+            ));
+        }
+        let result = self.client.create_transaction(self.to_request()?)?;
+        let fee = result.transaction.fee.unwrap_or(result.estimated_fee);
+        let swept = result
+            .transaction
+            .outputs
+            .iter()
+            .find(|output| !output.is_change)
+            .map_or(0, |output| output.satoshi);
+
+        if swept < self.dust_limit {
+            return Err(Error::validation(
+                ErrorKind::InsufficientFunds,
+                format!(
+                    "Sweep of {swept} sat after a {fee} sat fee is below the {} sat dust limit",
+                    self.dust_limit
+                ),
+            ));
+        }
+        let (total, spent) = match &self.sweep_asset {
+            None => (swept + fee, "swept"),
+            // The fee asset is not swept, so all of its change is L-BTC
+            Some(asset) => {
+                let change: Satoshis = result
+                    .transaction
+                    .outputs
+                    .iter()
+                    .filter(|output| output.is_change)
+                    .filter(|output| output.asset_id.as_ref() != Some(asset))
+                    .map(|output| output.satoshi)
+                    .sum();
+                (fee + change, "of L-BTC the asset sweep spends")
+            }
+        };
+        #[allow(clippy::cast_precision_loss)]
+        let percent = fee as f64 * 100.0 / total as f64;
+        if percent > self.max_fee_percent {
+            return Err(Error::validation(
+                ErrorKind::InvalidFee,
+                format!(
+                    "Fee of {fee} sat is {percent:.1}% of the {total} sat {spent}, above the \
+                     {}% limit",
+                    self.max_fee_percent
+                ),
+            ));
+        }
+        Ok((swept, fee))
+    }
+
+    /// Check every output address against the client's network
+    fn validate_outputs(&self) -> Result<()> {
+        let network = self.client.config().network;
+        for address in self
+            .outputs
+            .iter()
+            .filter_map(|output| output.address.as_deref())
+        {
+            validate_address(address, network)?;
+        }
+        Ok(())
+    }
+
+    /// Outputs as dumped, paying the swept asset in a sweep
+    fn dumped_outputs(&self) -> Vec<TxOutput> {
+        let mut outputs = self.outputs.clone();
+        if self.send_all {
+            for output in &mut outputs {
+                output.asset_id.clone_from(&self.sweep_asset);
+            }
+        }
+        outputs
+    }
+
+    fn temp_path_str(&self) -> Option<String> {
+        self.temp_file
+            .as_ref()
+            .map(|file| file.path().to_string_lossy().to_string())
+    }
+}
+
+/// Extract a transaction ID from `green-cli tx send` output
+///
+/// green-cli may print the txid as a bare hex string, a JSON string, or a
+/// JSON object with a `txhash` or `txid` field.
+fn parse_txid(output: &str) -> Result<TxId> {
+    let trimmed = output.trim();
+    let candidate = match serde_json::from_str::<Value>(trimmed) {
+        Ok(Value::String(txid)) => txid,
+        Ok(Value::Object(map)) => map
+            .get("txhash")
+            .or_else(|| map.get("txid"))
+            .and_then(Value::as_str)
+            .ok_or(Error::InvalidResponse)?
+            .to_string(),
+        Ok(_) => return Err(Error::InvalidResponse),
+        Err(_) => trimmed.to_string(),
+    };
+
+    if candidate.len() == 64 && candidate.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(candidate.to_ascii_lowercase())
+    } else {
+        Err(Error::InvalidResponse)
     }
 }
 
@@ -276,6 +642,9 @@ pub trait AsyncTransactionApi {
         request: CreateTransactionRequest,
     ) -> Result<CreateTransactionResult>;
 
+    /// Create, sign and broadcast a transaction in one step
+    async fn send_transaction(&self, request: CreateTransactionRequest) -> Result<Transaction>;
+
     /// Send to an address
     async fn send_to_address(
         &self,
@@ -294,4 +663,25 @@ pub trait AsyncTransactionApi {
 
     /// Get transaction details
     async fn get_transaction_details(&self, txid: &str) -> Result<Transaction>;
+
+    /// Stream transaction history matching `filter`, newest first
+    ///
+    /// Pages are fetched with [`get_transactions`](Self::get_transactions)
+    /// as the stream is polled.
+    fn transaction_stream(&self, filter: TransactionFilter) -> BoxStream<'_, Result<Transaction>>
+    where
+        Self: Sync,
+    {
+        boxed_transaction_stream(self, filter)
+    }
+
+    /// Replace an unconfirmed transaction with one paying `new_fee_rate`
+    ///
+    /// See [`TransactionApi::bump_fee`].
+    async fn bump_fee(&self, txid: &str, new_fee_rate: FeeRate) -> Result<FeeBump> {
+        let original = self.get_transaction_details(txid).await?;
+        let request = replacement_request(&original, new_fee_rate)?;
+        let replacement = self.send_transaction(request).await?;
+        Ok(fee_bump(&original, replacement, new_fee_rate))
+    }
 }
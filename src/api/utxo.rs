@@ -12,6 +12,7 @@
 //! - Freeze and thaw operations for UTXO management
 //! - Blinding factors for confidential output control
 
+use crate::types::utxo::{DustPolicy, UpdateUtxoRequest, UtxoReport, UtxoUpdateResult};
 use crate::types::{AssetId, GetUnspentOutputsParams, UnspentOutput};
 use crate::Result;
 use std::collections::HashMap;
@@ -19,7 +20,7 @@ use std::collections::HashMap;
 /// UTXO API trait for Green clients
 ///
 /// Provides synchronous methods for UTXO operations including
-/// retrieval, filtering, and potential future UTXO management extensions.
+/// retrieval, filtering, freezing and labelling outputs.
 ///
 /// # Liquid Network Considerations
 ///
@@ -59,16 +60,116 @@ pub trait UtxoApi {
         params: GetUnspentOutputsParams,
     ) -> Result<HashMap<AssetId, Vec<UnspentOutput>>>;
 
-    // TODO: Define other UTXO API methods
-    // - get_utxo_info
-    // - lock_utxo
-    // - unlock_utxo
+    /// Update the freeze status and/or memo of an output
+    ///
+    /// Returns the output as updated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output is not an unspent output of the wallet
+    /// or the CLI command fails
+    fn update_utxo(&self, request: UpdateUtxoRequest) -> Result<UnspentOutput>;
+
+    /// Summarize every unspent output of the wallet, frozen ones included
+    ///
+    /// The report gives counts, totals, frozen and dust amounts and a
+    /// confirmation-depth histogram per asset, both across the wallet and
+    /// per subaccount, and lists the outputs that are dust under `dust_policy`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use green_rs::api::utxo::UtxoApi;
+    /// # use green_rs::types::utxo::DustPolicy;
+    /// # use green_rs::types::FeeRate;
+    /// # use green_rs::types::wallet::WalletSettings;
+    /// # fn example(client: impl UtxoApi, settings: WalletSettings) -> green_rs::Result<()> {
+    /// let report = client.get_utxo_report(DustPolicy::from_settings(&settings, FeeRate::from_sat_per_vb(20)))?;
+    /// for (asset, summary) in &report.by_asset {
+    ///     println!("{asset}: {} outputs, {} dust", summary.utxo_count, summary.dust_count);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the outputs cannot be retrieved
+    fn get_utxo_report(&self, dust_policy: DustPolicy) -> Result<UtxoReport> {
+        let utxos = self.get_unspent_outputs(report_params())?;
+        Ok(UtxoReport::new(&utxos, dust_policy))
+    }
+
+    /// Freeze an output so coin selection never spends it
+    ///
+    /// Frozen outputs are left out of [`get_unspent_outputs`](Self::get_unspent_outputs)
+    /// unless `include_frozen` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output cannot be updated
+    fn freeze_utxo(&self, txhash: &str, vout: u32) -> Result<UnspentOutput> {
+        self.update_utxo(UpdateUtxoRequest::freeze(txhash, vout))
+    }
+
+    /// Thaw a frozen output, making it spendable again
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output cannot be updated
+    fn thaw_utxo(&self, txhash: &str, vout: u32) -> Result<UnspentOutput> {
+        self.update_utxo(UpdateUtxoRequest::thaw(txhash, vout))
+    }
+
+    /// Set the memo of an output, leaving its freeze status unchanged
+    ///
+    /// An empty memo removes the existing one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output cannot be updated
+    fn set_utxo_memo(&self, txhash: &str, vout: u32, memo: &str) -> Result<UnspentOutput> {
+        self.update_utxo(UpdateUtxoRequest::memo(txhash, vout, memo))
+    }
+
+    /// Apply several updates, one output at a time
+    ///
+    /// A failed update does not stop the others; the outcome of each is
+    /// reported in request order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use green_rs::api::utxo::UtxoApi;
+    /// # use green_rs::types::utxo::UpdateUtxoRequest;
+    /// # fn example(client: impl UtxoApi, dust: Vec<(String, u32)>) {
+    /// let requests = dust
+    ///     .into_iter()
+    ///     .map(|(txhash, vout)| UpdateUtxoRequest::freeze(txhash, vout))
+    ///     .collect();
+    /// for update in client.update_utxos(requests) {
+    ///     if let Err(err) = update.result {
+    ///         eprintln!("could not freeze {}:{}: {err}", update.txhash, update.vout);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    fn update_utxos(&self, requests: Vec<UpdateUtxoRequest>) -> Vec<UtxoUpdateResult> {
+        requests
+            .into_iter()
+            .map(|request| UtxoUpdateResult {
+                txhash: request.txhash.clone(),
+                vout: request.vout,
+                result: self.update_utxo(request),
+            })
+            .collect()
+    }
 }
 
 /// Async UTXO API trait for Green clients
 ///
 /// Provides asynchronous methods for UTXO operations including
-/// retrieval, filtering, and potential future UTXO management extensions.
+/// retrieval, filtering, freezing and labelling outputs.
 ///
 /// # Liquid Network Considerations
 ///
@@ -80,7 +181,7 @@ pub trait UtxoApi {
 pub trait AsyncUtxoApi {
     /// Get unspent outputs with filtering parameters
     ///
-    /// Returns a HashMap grouped by asset ID, where each asset ID maps to a vector of unspent outputs.
+    /// Returns a `HashMap` grouped by asset ID, where each asset ID maps to a vector of unspent outputs.
     ///
     /// # Arguments
     ///
@@ -109,5 +210,79 @@ pub trait AsyncUtxoApi {
         params: GetUnspentOutputsParams,
     ) -> Result<HashMap<AssetId, Vec<UnspentOutput>>>;
 
-    // TODO: Define other async UTXO API methods
+    /// Update the freeze status and/or memo of an output
+    ///
+    /// Returns the output as updated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output is not an unspent output of the wallet
+    /// or the CLI command fails
+    async fn update_utxo(&self, request: UpdateUtxoRequest) -> Result<UnspentOutput>;
+
+    /// Summarize every unspent output of the wallet, frozen ones included
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the outputs cannot be retrieved
+    async fn get_utxo_report(&self, dust_policy: DustPolicy) -> Result<UtxoReport> {
+        let utxos = self.get_unspent_outputs(report_params()).await?;
+        Ok(UtxoReport::new(&utxos, dust_policy))
+    }
+
+    /// Freeze an output so coin selection never spends it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output cannot be updated
+    async fn freeze_utxo(&self, txhash: &str, vout: u32) -> Result<UnspentOutput> {
+        self.update_utxo(UpdateUtxoRequest::freeze(txhash, vout))
+            .await
+    }
+
+    /// Thaw a frozen output, making it spendable again
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output cannot be updated
+    async fn thaw_utxo(&self, txhash: &str, vout: u32) -> Result<UnspentOutput> {
+        self.update_utxo(UpdateUtxoRequest::thaw(txhash, vout))
+            .await
+    }
+
+    /// Set the memo of an output, leaving its freeze status unchanged
+    ///
+    /// An empty memo removes the existing one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output cannot be updated
+    async fn set_utxo_memo(&self, txhash: &str, vout: u32, memo: &str) -> Result<UnspentOutput> {
+        self.update_utxo(UpdateUtxoRequest::memo(txhash, vout, memo))
+            .await
+    }
+
+    /// Apply several updates, one output at a time
+    ///
+    /// A failed update does not stop the others; the outcome of each is
+    /// reported in request order.
+    async fn update_utxos(&self, requests: Vec<UpdateUtxoRequest>) -> Vec<UtxoUpdateResult> {
+        let mut results = Vec::with_capacity(requests.len());
+        for request in requests {
+            results.push(UtxoUpdateResult {
+                txhash: request.txhash.clone(),
+                vout: request.vout,
+                result: self.update_utxo(request).await,
+            });
+        }
+        results
+    }
+}
+
+/// Parameters listing every output a report covers
+fn report_params() -> GetUnspentOutputsParams {
+    GetUnspentOutputsParams {
+        include_frozen: Some(true),
+        ..Default::default()
+    }
 }
@@ -2,7 +2,7 @@
 //!
 //! This module provides traits and implementations for wallet-related operations
 //! in the Green client. It supports both synchronous and asynchronous operations
-//! for managing wallet state, balances, and fee estimation.
+//! for managing the wallet session, balances, and fee estimation.
 //!
 //! # Liquid Network Support
 //!
@@ -13,6 +13,7 @@
 //! - Blinding factors are required to reveal confidential amounts
 
 use crate::error::Result;
+use crate::types::wallet::{CreateWalletRequest, LoginCredentials, WalletInfo};
 use crate::types::{Balance, FeeEstimates};
 
 /// Synchronous wallet extension trait for Green clients
@@ -87,22 +88,111 @@ pub trait AsyncWalletExt {
     async fn get_fee_estimates(&self) -> Result<FeeEstimates>;
 }
 
-/// Wallet API trait for Green clients
+/// Wallet session API trait for Green clients
 ///
-/// This trait is reserved for future wallet management operations.
-/// Currently, wallet operations are handled through the `green-cli`
-/// command-line interface outside of this library.
+/// Manages the wallet session green-cli keeps in its data directory: creating
+/// a wallet from a mnemonic, logging in and out, and protecting the wallet
+/// with a PIN. Credentials are always passed to green-cli out of band, never
+/// on the command line.
 ///
-/// Future methods may include:
-/// - Wallet creation and initialization
-/// - Wallet listing and discovery
-/// - Wallet metadata and configuration
-/// - Wallet deletion and cleanup
+/// # Example
+///
+/// ```
+/// use green_rs::api::WalletApi;
+/// use green_rs::testkit::FakeGreen;
+/// use green_rs::types::wallet::{CreateWalletRequest, LoginCredentials};
+///
+/// let client = FakeGreen::without_wallet().client();
+/// let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+///
+/// client.create_wallet(CreateWalletRequest::new(mnemonic)).unwrap();
+/// client.set_pin("123456").unwrap();
+/// client.logout().unwrap();
+/// assert!(client.get_wallet_info().unwrap().is_locked);
+///
+/// let info = client.login(LoginCredentials::from_pin("123456")).unwrap();
+/// assert!(!info.is_locked);
+/// ```
 pub trait WalletApi {
-    // TODO: Define wallet API methods
-    // Examples:
-    // - create_wallet
-    // - list_wallets
-    // - get_wallet_info
-    // - delete_wallet
+    /// Create a wallet from a mnemonic and log in to it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mnemonic is invalid, a wallet already exists,
+    /// or the CLI command fails
+    fn create_wallet(&self, request: CreateWalletRequest) -> Result<WalletInfo>;
+
+    /// Log in with a mnemonic, PIN or watch-only credentials
+    ///
+    /// # Errors
+    ///
+    /// Returns an error classified as `ErrorKind::InvalidCredentials` if the
+    /// credentials are rejected, or another error if the CLI command fails
+    fn login(&self, credentials: LoginCredentials) -> Result<WalletInfo>;
+
+    /// Protect the logged-in wallet with a PIN for later PIN logins
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no full (non watch-only) session is active or the
+    /// CLI command fails
+    fn set_pin(&self, pin: &str) -> Result<()>;
+
+    /// End the current session, locking the wallet
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CLI command fails
+    fn logout(&self) -> Result<()>;
+
+    /// Get information about the wallet and the current session
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no wallet exists or the CLI command fails
+    fn get_wallet_info(&self) -> Result<WalletInfo>;
+}
+
+/// Asynchronous wallet session API trait for Green clients
+///
+/// The non-blocking counterpart of [`WalletApi`].
+#[async_trait::async_trait]
+pub trait AsyncWalletApi {
+    /// Create a wallet from a mnemonic and log in to it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mnemonic is invalid, a wallet already exists,
+    /// or the CLI command fails
+    async fn create_wallet(&self, request: CreateWalletRequest) -> Result<WalletInfo>;
+
+    /// Log in with a mnemonic, PIN or watch-only credentials
+    ///
+    /// # Errors
+    ///
+    /// Returns an error classified as `ErrorKind::InvalidCredentials` if the
+    /// credentials are rejected, or another error if the CLI command fails
+    async fn login(&self, credentials: LoginCredentials) -> Result<WalletInfo>;
+
+    /// Protect the logged-in wallet with a PIN for later PIN logins
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no full (non watch-only) session is active or the
+    /// CLI command fails
+    async fn set_pin(&self, pin: &str) -> Result<()>;
+
+    /// End the current session, locking the wallet
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CLI command fails
+    async fn logout(&self) -> Result<()>;
+
+    /// Get information about the wallet and the current session
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no wallet exists or the CLI command fails
+    async fn get_wallet_info(&self) -> Result<WalletInfo>;
 }
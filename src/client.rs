@@ -9,6 +9,14 @@
 //! to JSON and parsing responses. This design allows the library to work with any
 //! Green wallet installation without requiring direct integration.
 //!
+//! Each client carries a [`ClientConfig`] that selects the executable, network,
+//! data directory, environment and global arguments used for every command.
+//!
+//! Commands are executed through a transport. Both clients default to
+//! [`ProcessTransport`], which spawns green-cli; any [`CliTransport`] or
+//! [`AsyncCliTransport`] can be plugged in with `with_transport`, for example
+//! a [`ScriptedTransport`](crate::transport::ScriptedTransport) in tests.
+//!
 //! # Liquid Network Support
 //!
 //! Both clients fully support Liquid network operations including:
@@ -17,16 +25,26 @@
 //! - Blinded amounts and asset IDs
 //! - Liquid-specific fee calculations
 
-use crate::api::subaccount::SubaccountExt;
-use crate::api::wallet::{AsyncWalletExt, WalletExt};
-use crate::error::{Error, Result};
-use crate::types::common::Pointer;
+use crate::api::subaccount::{AsyncSubaccountExt, SubaccountExt};
+use crate::api::transaction::{AsyncTransactionApi, TransactionApi};
+use crate::api::utxo::{AsyncUtxoApi, UtxoApi};
+use crate::api::wallet::{AsyncWalletApi, AsyncWalletExt, WalletApi, WalletExt};
+use crate::config::ClientConfig;
+use crate::error::Result;
+use crate::transport::{AsyncCliTransport, CliCommand, CliParams, CliTransport, ProcessTransport};
+use crate::types::common::{AssetId, Pointer, Satoshis};
 use crate::types::subaccount::{
     CreateSubaccountParams, Subaccount, SubaccountList, UpdateSubaccountParams,
 };
+use crate::types::transaction::{
+    Addressee, CreateTransactionRequest, CreateTransactionResult, GetTransactionsParams,
+    Transaction, TransactionList,
+};
+use crate::types::utxo::{GetUnspentOutputsParams, UnspentOutput, UpdateUtxoRequest, UtxoSortBy};
+use crate::types::wallet::{CreateWalletRequest, LoginCredentials, SetPinRequest, WalletInfo};
 use crate::types::{Balance, FeeEstimates};
-use std::process::Command;
-use tokio::process::Command as TokioCommand;
+use std::collections::HashMap;
+use std::time::Duration;
 
 /// Synchronous Green API client
 ///
@@ -52,20 +70,49 @@ use tokio::process::Command as TokioCommand;
 /// let balance = client.get_balance().expect("Failed to get balance");
 /// println!("Available balance: {} sats", balance.get("btc").unwrap_or(0));
 /// ```
-pub struct GreenClient {
-    // TODO: Add client configuration fields
+#[derive(Debug, Clone, Default)]
+pub struct GreenClient<T = ProcessTransport> {
+    config: ClientConfig,
+    transport: T,
 }
 
 impl GreenClient {
-    /// Create a new synchronous Green API client
+    /// Create a new synchronous Green API client with the default configuration
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new synchronous Green API client with the given configuration
+    #[must_use]
+    pub const fn with_config(config: ClientConfig) -> Self {
+        Self::with_transport(config, ProcessTransport)
+    }
+}
+
+impl<T: CliTransport> GreenClient<T> {
+    /// Create a new synchronous Green API client running commands through `transport`
+    #[must_use]
+    pub const fn with_transport(config: ClientConfig, transport: T) -> Self {
+        Self { config, transport }
+    }
+
+    /// Get the client configuration
+    #[must_use]
+    pub const fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    /// Get the transport commands are run through
     #[must_use]
-    pub const fn new() -> Self {
-        Self {}
+    pub const fn transport(&self) -> &T {
+        &self.transport
     }
 
     /// Run a green-cli command
     ///
-    /// This is a convenience method that delegates to `run_cli`
+    /// The command is built from the client configuration and executed
+    /// through the client's transport.
     ///
     /// # Arguments
     ///
@@ -80,17 +127,51 @@ impl GreenClient {
     ///
     /// Returns an error if the command execution fails
     pub fn run_command(&self, args: &[&str]) -> Result<String> {
-        run_cli(args)
+        self.execute(&self.config.command(args))
+    }
+
+    /// Run a green-cli command that takes JSON parameters
+    ///
+    /// The parameters are passed according to the configured
+    /// [`ParamsMode`](crate::transport::ParamsMode). Sensitive parameters are
+    /// never placed on the command line and are redacted from errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `params` cannot be serialized or the command
+    /// execution fails
+    pub fn run_command_with_params<P: CliParams + ?Sized>(
+        &self,
+        args: &[&str],
+        params: &P,
+    ) -> Result<String> {
+        self.execute(&self.config.command_with_params(args, params)?)
     }
-}
 
-impl Default for GreenClient {
-    fn default() -> Self {
-        Self::new()
+    fn execute(&self, command: &CliCommand) -> Result<String> {
+        command.finish(self.transport.execute(command)?)
+    }
+
+    /// Run a green-cli command with a timeout overriding the configured one
+    ///
+    /// A `timeout` of `None` lets the command run without a time limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if the command is killed after `timeout`
+    /// elapses, or another error if the command execution fails
+    pub fn run_command_with_timeout(
+        &self,
+        args: &[&str],
+        timeout: Option<Duration>,
+    ) -> Result<String> {
+        let mut command = self.config.command(args);
+        command.timeout = timeout;
+        self.execute(&command)
     }
 }
 
-impl WalletExt for GreenClient {
+impl<T: CliTransport> WalletExt for GreenClient<T> {
     fn get_balance(&self) -> Result<Balance> {
         let output = self.run_command(&["get", "balance", "--json"])?;
         let balance: Balance = serde_json::from_str(&output)?;
@@ -104,120 +185,82 @@ impl WalletExt for GreenClient {
     }
 }
 
-impl crate::api::utxo::UtxoApi for GreenClient {
+impl<T: CliTransport> WalletApi for GreenClient<T> {
+    fn create_wallet(&self, request: CreateWalletRequest) -> Result<WalletInfo> {
+        let output = self.run_command_with_params(&["create", "wallet", "--json"], &request)?;
+        let info: WalletInfo = serde_json::from_str(&output)?;
+        Ok(info)
+    }
+
+    fn login(&self, credentials: LoginCredentials) -> Result<WalletInfo> {
+        let output = self.run_command_with_params(&["login", "--json"], &credentials)?;
+        let info: WalletInfo = serde_json::from_str(&output)?;
+        Ok(info)
+    }
+
+    fn set_pin(&self, pin: &str) -> Result<()> {
+        let request = SetPinRequest {
+            pin: pin.to_string(),
+        };
+        self.run_command_with_params(&["set", "pin", "--json"], &request)?;
+        Ok(())
+    }
+
+    fn logout(&self) -> Result<()> {
+        self.run_command(&["logout", "--json"])?;
+        Ok(())
+    }
+
+    fn get_wallet_info(&self) -> Result<WalletInfo> {
+        let output = self.run_command(&["get", "wallet", "--json"])?;
+        let info: WalletInfo = serde_json::from_str(&output)?;
+        Ok(info)
+    }
+}
+
+impl<T: CliTransport> UtxoApi for GreenClient<T> {
     fn get_unspent_outputs(
         &self,
-        params: crate::types::GetUnspentOutputsParams,
-    ) -> crate::Result<
-        std::collections::HashMap<crate::types::AssetId, Vec<crate::types::UnspentOutput>>,
-    > {
-        let params_json = serde_json::to_string(&params)?;
-        let output = self.run_command(&["get", "utxos", "--params", &params_json, "--json"])?;
-        let utxos: Vec<crate::types::UnspentOutput> = serde_json::from_str(&output)?;
-        let mut grouped_utxos: std::collections::HashMap<
-            crate::types::AssetId,
-            Vec<crate::types::UnspentOutput>,
-        > = std::collections::HashMap::new();
-
-        // Group UTXOs by asset ID
-        // For Bitcoin mainnet, asset_id is None, so we use "btc" as the default key
-        for utxo in utxos {
-            let asset_id = utxo.asset_id.clone().unwrap_or_else(|| "btc".to_string());
-            grouped_utxos.entry(asset_id).or_default().push(utxo);
-        }
-
-        // Apply sorting if specified
-        if let Some(sort_by) = params.sort_by {
-            for utxos in grouped_utxos.values_mut() {
-                match sort_by {
-                    crate::types::UtxoSortBy::Value => {
-                        utxos.sort_by_key(|u| u.satoshi);
-                    }
-                    crate::types::UtxoSortBy::ValueDesc => {
-                        utxos.sort_by_key(|u| std::cmp::Reverse(u.satoshi));
-                    }
-                    crate::types::UtxoSortBy::Confirmations => {
-                        utxos.sort_by_key(|u| u.confirmations);
-                    }
-                    crate::types::UtxoSortBy::ConfirmationsDesc => {
-                        utxos.sort_by_key(|u| std::cmp::Reverse(u.confirmations));
-                    }
-                    crate::types::UtxoSortBy::Age => {
-                        utxos.sort_by_key(|u| u.block_height);
-                    }
-                    crate::types::UtxoSortBy::AgeDesc => {
-                        utxos.sort_by_key(|u| std::cmp::Reverse(u.block_height));
-                    }
-                }
-            }
-        }
+        params: GetUnspentOutputsParams,
+    ) -> Result<HashMap<AssetId, Vec<UnspentOutput>>> {
+        let output = self.run_command_with_params(&["get", "utxos", "--json"], &params)?;
+        parse_unspent_outputs(&output, params.sort_by)
+    }
 
-        Ok(grouped_utxos)
+    fn update_utxo(&self, request: UpdateUtxoRequest) -> Result<UnspentOutput> {
+        let output = self.run_command_with_params(&["update", "utxo", "--json"], &request)?;
+        let utxo: UnspentOutput = serde_json::from_str(&output)?;
+        Ok(utxo)
     }
 }
 
 #[async_trait::async_trait]
-impl crate::api::utxo::AsyncUtxoApi for AsyncGreenClient {
+impl<T: AsyncCliTransport> AsyncUtxoApi for AsyncGreenClient<T> {
     async fn get_unspent_outputs(
         &self,
-        params: crate::types::GetUnspentOutputsParams,
-    ) -> crate::Result<
-        std::collections::HashMap<crate::types::AssetId, Vec<crate::types::UnspentOutput>>,
-    > {
-        let params_json = serde_json::to_string(&params)?;
+        params: GetUnspentOutputsParams,
+    ) -> Result<HashMap<AssetId, Vec<UnspentOutput>>> {
         let output = self
-            .run_command(&["get", "utxos", "--params", &params_json, "--json"])
+            .run_command_with_params(&["get", "utxos", "--json"], &params)
             .await?;
-        let utxos: Vec<crate::types::UnspentOutput> = serde_json::from_str(&output)?;
-        let mut grouped_utxos: std::collections::HashMap<
-            crate::types::AssetId,
-            Vec<crate::types::UnspentOutput>,
-        > = std::collections::HashMap::new();
-
-        // Group UTXOs by asset ID
-        // For Bitcoin mainnet, asset_id is None, so we use "btc" as the default key
-        for utxo in utxos {
-            let asset_id = utxo.asset_id.clone().unwrap_or_else(|| "btc".to_string());
-            grouped_utxos.entry(asset_id).or_default().push(utxo);
-        }
-
-        // Apply sorting if specified
-        if let Some(sort_by) = params.sort_by {
-            for utxos in grouped_utxos.values_mut() {
-                match sort_by {
-                    crate::types::UtxoSortBy::Value => {
-                        utxos.sort_by_key(|u| u.satoshi);
-                    }
-                    crate::types::UtxoSortBy::ValueDesc => {
-                        utxos.sort_by_key(|u| std::cmp::Reverse(u.satoshi));
-                    }
-                    crate::types::UtxoSortBy::Confirmations => {
-                        utxos.sort_by_key(|u| u.confirmations);
-                    }
-                    crate::types::UtxoSortBy::ConfirmationsDesc => {
-                        utxos.sort_by_key(|u| std::cmp::Reverse(u.confirmations));
-                    }
-                    crate::types::UtxoSortBy::Age => {
-                        utxos.sort_by_key(|u| u.block_height);
-                    }
-                    crate::types::UtxoSortBy::AgeDesc => {
-                        utxos.sort_by_key(|u| std::cmp::Reverse(u.block_height));
-                    }
-                }
-            }
-        }
+        parse_unspent_outputs(&output, params.sort_by)
+    }
 
-        Ok(grouped_utxos)
+    async fn update_utxo(&self, request: UpdateUtxoRequest) -> Result<UnspentOutput> {
+        let output = self
+            .run_command_with_params(&["update", "utxo", "--json"], &request)
+            .await?;
+        let utxo: UnspentOutput = serde_json::from_str(&output)?;
+        Ok(utxo)
     }
 }
 
-impl crate::api::address::AddressApi for GreenClient {
+impl<T: CliTransport> crate::api::address::AddressApi for GreenClient<T> {
     fn get_receive_address(
         &self,
         request: crate::types::address::GetReceiveAddressRequest,
     ) -> Result<crate::types::address::ReceiveAddress> {
-        let params_json = serde_json::to_string(&request)?;
-        let output = self.run_command(&["get", "address", "--params", &params_json, "--json"])?;
+        let output = self.run_command_with_params(&["get", "address", "--json"], &request)?;
         let address: crate::types::address::ReceiveAddress = serde_json::from_str(&output)?;
         Ok(address)
     }
@@ -226,9 +269,7 @@ impl crate::api::address::AddressApi for GreenClient {
         &self,
         request: crate::types::address::GetReceiveAddressRequest,
     ) -> Result<crate::types::address::ReceiveAddress> {
-        let params_json = serde_json::to_string(&request)?;
-        let output =
-            self.run_command(&["get", "new-address", "--params", &params_json, "--json"])?;
+        let output = self.run_command_with_params(&["get", "new-address", "--json"], &request)?;
         let address: crate::types::address::ReceiveAddress = serde_json::from_str(&output)?;
         Ok(address)
     }
@@ -237,24 +278,80 @@ impl crate::api::address::AddressApi for GreenClient {
         &self,
         request: crate::types::address::GetPreviousAddressesRequest,
     ) -> Result<Vec<crate::types::address::AddressDetails>> {
-        let params_json = serde_json::to_string(&request)?;
-        let output = self.run_command(&[
-            "get",
-            "previous-addresses",
-            "--params",
-            &params_json,
-            "--json",
-        ])?;
+        let output =
+            self.run_command_with_params(&["get", "previous-addresses", "--json"], &request)?;
         let addresses: Vec<crate::types::address::AddressDetails> = serde_json::from_str(&output)?;
         Ok(addresses)
     }
+
+    fn update_address(
+        &self,
+        request: crate::types::address::UpdateAddressRequest,
+    ) -> Result<crate::types::address::AddressDetails> {
+        let output = self.run_command_with_params(&["update", "address", "--json"], &request)?;
+        let address: crate::types::address::AddressDetails = serde_json::from_str(&output)?;
+        Ok(address)
+    }
+
+    fn validate_address(&self, address: &str) -> Result<crate::address::ParsedAddress> {
+        crate::address::validate_address(address, self.config.network)
+    }
+}
+
+impl<T: CliTransport> TransactionApi for GreenClient<T> {
+    fn create_transaction(
+        &self,
+        request: CreateTransactionRequest,
+    ) -> Result<CreateTransactionResult> {
+        let output =
+            self.run_command_with_params(&["create", "transaction", "--json"], &request)?;
+        let result: CreateTransactionResult = serde_json::from_str(&output)?;
+        Ok(result)
+    }
+
+    fn send_transaction(&self, request: CreateTransactionRequest) -> Result<Transaction> {
+        let output = self.run_command_with_params(&["send", "transaction", "--json"], &request)?;
+        let transaction: Transaction = serde_json::from_str(&output)?;
+        Ok(transaction)
+    }
+
+    fn send_to_address(
+        &self,
+        address: &str,
+        amount: Satoshis,
+        asset_id: Option<AssetId>,
+    ) -> Result<Transaction> {
+        crate::address::validate_address(address, self.config.network)?;
+        self.send_transaction(send_to_address_request(address, amount, asset_id))
+    }
+
+    fn get_transactions(
+        &self,
+        subaccount: Option<u32>,
+        first: Option<u32>,
+        count: Option<u32>,
+    ) -> Result<TransactionList> {
+        let params = GetTransactionsParams {
+            subaccount,
+            first,
+            count,
+        };
+        let output = self.run_command_with_params(&["get", "transactions", "--json"], &params)?;
+        let list: TransactionList = serde_json::from_str(&output)?;
+        Ok(list)
+    }
+
+    fn get_transaction_details(&self, txid: &str) -> Result<Transaction> {
+        let output = self.run_command(&["get", "transaction", "--txid", txid, "--json"])?;
+        let transaction: Transaction = serde_json::from_str(&output)?;
+        Ok(transaction)
+    }
 }
 
-impl SubaccountExt for GreenClient {
+impl<T: CliTransport> SubaccountExt for GreenClient<T> {
     fn get_subaccounts(&self) -> Result<Vec<Subaccount>> {
         let output = self.run_command(&["get", "subaccounts", "--json"])?;
-        let list: SubaccountList = serde_json::from_str(&output)?;
-        Ok(list.subaccounts)
+        parse_subaccounts(&output)
     }
 
     fn get_subaccount(&self, pointer: Pointer) -> Result<Subaccount> {
@@ -270,9 +367,7 @@ impl SubaccountExt for GreenClient {
     }
 
     fn create_subaccount(&self, params: CreateSubaccountParams) -> Result<Subaccount> {
-        let params_json = serde_json::to_string(&params)?;
-        let output =
-            self.run_command(&["create", "subaccount", "--params", &params_json, "--json"])?;
+        let output = self.run_command_with_params(&["create", "subaccount", "--json"], &params)?;
         let subaccount: Subaccount = serde_json::from_str(&output)?;
         Ok(subaccount)
     }
@@ -282,16 +377,16 @@ impl SubaccountExt for GreenClient {
         pointer: Pointer,
         params: UpdateSubaccountParams,
     ) -> Result<Subaccount> {
-        let params_json = serde_json::to_string(&params)?;
-        let output = self.run_command(&[
-            "update",
-            "subaccount",
-            "--subaccount",
-            &pointer.to_string(),
-            "--params",
-            &params_json,
-            "--json",
-        ])?;
+        let output = self.run_command_with_params(
+            &[
+                "update",
+                "subaccount",
+                "--subaccount",
+                &pointer.to_string(),
+                "--json",
+            ],
+            &params,
+        )?;
         let subaccount: Subaccount = serde_json::from_str(&output)?;
         Ok(subaccount)
     }
@@ -325,20 +420,49 @@ impl SubaccountExt for GreenClient {
 ///     println!("Available balance: {} sats", balance.get("btc").unwrap_or(0));
 /// }
 /// ```
-pub struct AsyncGreenClient {
-    // TODO: Add client configuration fields
+#[derive(Debug, Clone, Default)]
+pub struct AsyncGreenClient<T = ProcessTransport> {
+    config: ClientConfig,
+    transport: T,
 }
 
 impl AsyncGreenClient {
-    /// Create a new asynchronous Green API client
+    /// Create a new asynchronous Green API client with the default configuration
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new asynchronous Green API client with the given configuration
+    #[must_use]
+    pub const fn with_config(config: ClientConfig) -> Self {
+        Self::with_transport(config, ProcessTransport)
+    }
+}
+
+impl<T: AsyncCliTransport> AsyncGreenClient<T> {
+    /// Create a new asynchronous Green API client running commands through `transport`
     #[must_use]
-    pub const fn new() -> Self {
-        Self {}
+    pub const fn with_transport(config: ClientConfig, transport: T) -> Self {
+        Self { config, transport }
+    }
+
+    /// Get the client configuration
+    #[must_use]
+    pub const fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    /// Get the transport commands are run through
+    #[must_use]
+    pub const fn transport(&self) -> &T {
+        &self.transport
     }
 
     /// Run a green-cli command asynchronously
     ///
-    /// This is a convenience method that delegates to `run_cli_async`
+    /// The command is built from the client configuration and executed
+    /// through the client's transport.
     ///
     /// # Arguments
     ///
@@ -352,25 +476,62 @@ impl AsyncGreenClient {
     ///
     /// Returns an error if the command execution fails.
     pub async fn run_command(&self, args: &[&str]) -> Result<String> {
-        run_cli_async(args).await
+        self.execute(&self.config.command(args)).await
     }
-}
 
-impl Default for AsyncGreenClient {
-    fn default() -> Self {
-        Self::new()
+    /// Run a green-cli command that takes JSON parameters asynchronously
+    ///
+    /// The parameters are passed according to the configured
+    /// [`ParamsMode`](crate::transport::ParamsMode). Sensitive parameters are
+    /// never placed on the command line and are redacted from errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `params` cannot be serialized or the command
+    /// execution fails
+    pub async fn run_command_with_params<P: CliParams + ?Sized + Sync>(
+        &self,
+        args: &[&str],
+        params: &P,
+    ) -> Result<String> {
+        self.execute(&self.config.command_with_params(args, params)?)
+            .await
+    }
+
+    async fn execute(&self, command: &CliCommand) -> Result<String> {
+        command.finish(self.transport.execute(command).await?)
+    }
+
+    /// Run a green-cli command asynchronously with a timeout overriding the
+    /// configured one
+    ///
+    /// A `timeout` of `None` lets the command run without a time limit. With
+    /// the default [`ProcessTransport`], the child process is killed when the
+    /// timeout expires or when the returned future is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if the command is killed after `timeout`
+    /// elapses, or another error if the command execution fails
+    pub async fn run_command_with_timeout(
+        &self,
+        args: &[&str],
+        timeout: Option<Duration>,
+    ) -> Result<String> {
+        let mut command = self.config.command(args);
+        command.timeout = timeout;
+        self.execute(&command).await
     }
 }
 
 #[async_trait::async_trait]
-impl crate::api::address::AsyncAddressApi for AsyncGreenClient {
+impl<T: AsyncCliTransport> crate::api::address::AsyncAddressApi for AsyncGreenClient<T> {
     async fn get_receive_address(
         &self,
         request: crate::types::address::GetReceiveAddressRequest,
     ) -> Result<crate::types::address::ReceiveAddress> {
-        let params_json = serde_json::to_string(&request)?;
         let output = self
-            .run_command(&["get", "address", "--params", &params_json, "--json"])
+            .run_command_with_params(&["get", "address", "--json"], &request)
             .await?;
         let address: crate::types::address::ReceiveAddress = serde_json::from_str(&output)?;
         Ok(address)
@@ -380,9 +541,8 @@ impl crate::api::address::AsyncAddressApi for AsyncGreenClient {
         &self,
         request: crate::types::address::GetReceiveAddressRequest,
     ) -> Result<crate::types::address::ReceiveAddress> {
-        let params_json = serde_json::to_string(&request)?;
         let output = self
-            .run_command(&["get", "new-address", "--params", &params_json, "--json"])
+            .run_command_with_params(&["get", "new-address", "--json"], &request)
             .await?;
         let address: crate::types::address::ReceiveAddress = serde_json::from_str(&output)?;
         Ok(address)
@@ -392,23 +552,90 @@ impl crate::api::address::AsyncAddressApi for AsyncGreenClient {
         &self,
         request: crate::types::address::GetPreviousAddressesRequest,
     ) -> Result<Vec<crate::types::address::AddressDetails>> {
-        let params_json = serde_json::to_string(&request)?;
         let output = self
-            .run_command(&[
-                "get",
-                "previous-addresses",
-                "--params",
-                &params_json,
-                "--json",
-            ])
+            .run_command_with_params(&["get", "previous-addresses", "--json"], &request)
             .await?;
         let addresses: Vec<crate::types::address::AddressDetails> = serde_json::from_str(&output)?;
         Ok(addresses)
     }
+
+    async fn update_address(
+        &self,
+        request: crate::types::address::UpdateAddressRequest,
+    ) -> Result<crate::types::address::AddressDetails> {
+        let output = self
+            .run_command_with_params(&["update", "address", "--json"], &request)
+            .await?;
+        let address: crate::types::address::AddressDetails = serde_json::from_str(&output)?;
+        Ok(address)
+    }
+
+    fn validate_address(&self, address: &str) -> Result<crate::address::ParsedAddress> {
+        crate::address::validate_address(address, self.config.network)
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: AsyncCliTransport> AsyncTransactionApi for AsyncGreenClient<T> {
+    async fn create_transaction(
+        &self,
+        request: CreateTransactionRequest,
+    ) -> Result<CreateTransactionResult> {
+        let output = self
+            .run_command_with_params(&["create", "transaction", "--json"], &request)
+            .await?;
+        let result: CreateTransactionResult = serde_json::from_str(&output)?;
+        Ok(result)
+    }
+
+    async fn send_transaction(&self, request: CreateTransactionRequest) -> Result<Transaction> {
+        let output = self
+            .run_command_with_params(&["send", "transaction", "--json"], &request)
+            .await?;
+        let transaction: Transaction = serde_json::from_str(&output)?;
+        Ok(transaction)
+    }
+
+    async fn send_to_address(
+        &self,
+        address: &str,
+        amount: Satoshis,
+        asset_id: Option<AssetId>,
+    ) -> Result<Transaction> {
+        crate::address::validate_address(address, self.config.network)?;
+        self.send_transaction(send_to_address_request(address, amount, asset_id))
+            .await
+    }
+
+    async fn get_transactions(
+        &self,
+        subaccount: Option<u32>,
+        first: Option<u32>,
+        count: Option<u32>,
+    ) -> Result<TransactionList> {
+        let params = GetTransactionsParams {
+            subaccount,
+            first,
+            count,
+        };
+        let output = self
+            .run_command_with_params(&["get", "transactions", "--json"], &params)
+            .await?;
+        let list: TransactionList = serde_json::from_str(&output)?;
+        Ok(list)
+    }
+
+    async fn get_transaction_details(&self, txid: &str) -> Result<Transaction> {
+        let output = self
+            .run_command(&["get", "transaction", "--txid", txid, "--json"])
+            .await?;
+        let transaction: Transaction = serde_json::from_str(&output)?;
+        Ok(transaction)
+    }
 }
 
 #[async_trait::async_trait]
-impl AsyncWalletExt for AsyncGreenClient {
+impl<T: AsyncCliTransport> AsyncWalletExt for AsyncGreenClient<T> {
     async fn get_balance(&self) -> Result<Balance> {
         let output = self.run_command(&["get", "balance", "--json"]).await?;
         let balance: Balance = serde_json::from_str(&output)?;
@@ -424,11 +651,145 @@ impl AsyncWalletExt for AsyncGreenClient {
     }
 }
 
+#[async_trait::async_trait]
+impl<T: AsyncCliTransport> AsyncWalletApi for AsyncGreenClient<T> {
+    async fn create_wallet(&self, request: CreateWalletRequest) -> Result<WalletInfo> {
+        let output = self
+            .run_command_with_params(&["create", "wallet", "--json"], &request)
+            .await?;
+        let info: WalletInfo = serde_json::from_str(&output)?;
+        Ok(info)
+    }
+
+    async fn login(&self, credentials: LoginCredentials) -> Result<WalletInfo> {
+        let output = self
+            .run_command_with_params(&["login", "--json"], &credentials)
+            .await?;
+        let info: WalletInfo = serde_json::from_str(&output)?;
+        Ok(info)
+    }
+
+    async fn set_pin(&self, pin: &str) -> Result<()> {
+        let request = SetPinRequest {
+            pin: pin.to_string(),
+        };
+        self.run_command_with_params(&["set", "pin", "--json"], &request)
+            .await?;
+        Ok(())
+    }
+
+    async fn logout(&self) -> Result<()> {
+        self.run_command(&["logout", "--json"]).await?;
+        Ok(())
+    }
+
+    async fn get_wallet_info(&self) -> Result<WalletInfo> {
+        let output = self.run_command(&["get", "wallet", "--json"]).await?;
+        let info: WalletInfo = serde_json::from_str(&output)?;
+        Ok(info)
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: AsyncCliTransport> AsyncSubaccountExt for AsyncGreenClient<T> {
+    async fn get_subaccounts(&self) -> Result<Vec<Subaccount>> {
+        let output = self.run_command(&["get", "subaccounts", "--json"]).await?;
+        parse_subaccounts(&output)
+    }
+
+    async fn get_subaccount(&self, pointer: Pointer) -> Result<Subaccount> {
+        let output = self
+            .run_command(&[
+                "get",
+                "subaccount",
+                "--subaccount",
+                &pointer.to_string(),
+                "--json",
+            ])
+            .await?;
+        let subaccount: Subaccount = serde_json::from_str(&output)?;
+        Ok(subaccount)
+    }
+
+    async fn create_subaccount(&self, params: CreateSubaccountParams) -> Result<Subaccount> {
+        let output = self
+            .run_command_with_params(&["create", "subaccount", "--json"], &params)
+            .await?;
+        let subaccount: Subaccount = serde_json::from_str(&output)?;
+        Ok(subaccount)
+    }
+
+    async fn update_subaccount(
+        &self,
+        pointer: Pointer,
+        params: UpdateSubaccountParams,
+    ) -> Result<Subaccount> {
+        let output = self
+            .run_command_with_params(
+                &[
+                    "update",
+                    "subaccount",
+                    "--subaccount",
+                    &pointer.to_string(),
+                    "--json",
+                ],
+                &params,
+            )
+            .await?;
+        let subaccount: Subaccount = serde_json::from_str(&output)?;
+        Ok(subaccount)
+    }
+}
+
+/// Parse the output of `get subaccounts`
+fn parse_subaccounts(output: &str) -> Result<Vec<Subaccount>> {
+    let list: SubaccountList = serde_json::from_str(output)?;
+    Ok(list.subaccounts)
+}
+
+/// Parse the output of `get utxos`, grouped by asset ID and optionally sorted
+///
+/// Bitcoin outputs have no asset ID and are grouped under `"btc"`.
+fn parse_unspent_outputs(
+    output: &str,
+    sort_by: Option<UtxoSortBy>,
+) -> Result<HashMap<AssetId, Vec<UnspentOutput>>> {
+    let utxos: Vec<UnspentOutput> = serde_json::from_str(output)?;
+    let mut grouped: HashMap<AssetId, Vec<UnspentOutput>> = HashMap::new();
+    for utxo in utxos {
+        let asset_id = utxo.asset_id.clone().unwrap_or_else(|| "btc".to_string());
+        grouped.entry(asset_id).or_default().push(utxo);
+    }
+
+    if let Some(sort_by) = sort_by {
+        for utxos in grouped.values_mut() {
+            sort_by.sort(utxos);
+        }
+    }
+    Ok(grouped)
+}
+
+/// Build the request used by `send_to_address` for a single recipient
+fn send_to_address_request(
+    address: &str,
+    amount: Satoshis,
+    asset_id: Option<AssetId>,
+) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        addressees: Some(vec![Addressee {
+            address: address.to_string(),
+            satoshi: amount,
+            asset_id,
+        }]),
+        ..Default::default()
+    }
+}
+
 /// Helper function to run green-cli commands
 ///
-/// Invokes `green-cli` with the provided arguments, setting `-L` and `-T`
-/// environment variables by default. Captures stdout/stderr and returns
-/// stdout as a String on success, or `Error::Cli` on failure.
+/// Invokes `green-cli` from `PATH` with the provided arguments and the default
+/// [`ClientConfig`]. Captures stdout/stderr and returns stdout as a String on
+/// success, or `Error::Cli` on failure.
 ///
 /// # Arguments
 ///
@@ -450,31 +811,33 @@ impl AsyncWalletExt for AsyncGreenClient {
 ///     Ok(())
 /// }
 /// ```
-/// Execute a command using the CLI
 ///
 /// # Errors
 ///
 /// Returns an error if the command execution fails.
 pub fn run_cli(args: &[&str]) -> Result<String> {
-    let output = Command::new("green-cli")
-        .args(args)
-        .env("GREEN_CLI_L", "-L")
-        .env("GREEN_CLI_T", "-T")
-        .output()?;
-
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        Err(Error::Cli(stderr))
-    }
+    run_cli_with_config(&ClientConfig::default(), args)
+}
+
+/// Run a green-cli command using the given configuration
+///
+/// The configured executable is spawned with the configuration's global
+/// arguments followed by `args`, and with the configured environment. The
+/// process is killed if it outlives the configured timeout.
+///
+/// # Errors
+///
+/// Returns `Error::Timeout` if the command times out, or another error if it
+/// cannot be spawned or exits unsuccessfully.
+pub fn run_cli_with_config(config: &ClientConfig, args: &[&str]) -> Result<String> {
+    CliTransport::execute(&ProcessTransport, &config.command(args))?.into_result()
 }
 
 /// Asynchronous helper function to run green-cli commands
 ///
-/// Invokes `green-cli` with the provided arguments, setting `-L` and `-T`
-/// environment variables by default. Captures stdout/stderr and returns
-/// stdout as a String on success, or `Error::Cli` on failure.
+/// Invokes `green-cli` from `PATH` with the provided arguments and the default
+/// [`ClientConfig`]. Captures stdout/stderr and returns stdout as a String on
+/// success, or `Error::Cli` on failure.
 ///
 /// # Arguments
 ///
@@ -497,30 +860,35 @@ pub fn run_cli(args: &[&str]) -> Result<String> {
 ///     Ok(())
 /// }
 /// ```
-/// Asynchronously execute a command using the CLI
 ///
 /// # Errors
 ///
 /// Returns an error if the command execution fails.
 pub async fn run_cli_async(args: &[&str]) -> Result<String> {
-    let output = TokioCommand::new("green-cli")
-        .args(args)
-        .env("GREEN_CLI_L", "-L")
-        .env("GREEN_CLI_T", "-T")
-        .output()
-        .await?;
-
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        Err(Error::Cli(stderr))
-    }
+    run_cli_async_with_config(&ClientConfig::default(), args).await
+}
+
+/// Asynchronously run a green-cli command using the given configuration
+///
+/// The configured executable is spawned with the configuration's global
+/// arguments followed by `args`, and with the configured environment. The
+/// process is killed if it outlives the configured timeout or if the returned
+/// future is dropped.
+///
+/// # Errors
+///
+/// Returns `Error::Timeout` if the command times out, or another error if it
+/// cannot be spawned or exits unsuccessfully.
+pub async fn run_cli_async_with_config(config: &ClientConfig, args: &[&str]) -> Result<String> {
+    AsyncCliTransport::execute(&ProcessTransport, &config.command(args))
+        .await?
+        .into_result()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
 
     #[test]
     fn test_run_cli_with_invalid_command() {
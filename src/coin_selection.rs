@@ -0,0 +1,661 @@
+//! Local coin selection
+//!
+//! [`CoinSelector`] picks the unspent outputs to fund a transaction from the
+//! map returned by [`get_unspent_outputs`](crate::api::utxo::UtxoApi::get_unspent_outputs),
+//! so the choice can be passed to green-cli explicitly through
+//! [`CreateTransactionRequest::utxos`].
+//!
+//! Four strategies are available:
+//! - [`SelectionStrategy::BranchAndBound`] searches for a set of inputs that
+//!   matches the target closely enough to need no change output, and falls
+//!   back to knapsack when there is none
+//! - [`SelectionStrategy::LargestFirst`] spends the biggest outputs first,
+//!   using as few inputs as possible
+//! - [`SelectionStrategy::OldestFirst`] spends the most deeply confirmed
+//!   outputs first
+//! - [`SelectionStrategy::Knapsack`] approximates the subset closest to the
+//!   target, avoiding tiny change
+//!
+//! Selection works on effective values: each input's value minus the fee for
+//! spending it at the chosen fee rate, so inputs that cost more than they are
+//! worth are never picked. Frozen outputs are always skipped. Inputs and
+//! outputs are sized with a [`TxEstimator`], the same model used to estimate
+//! transactions before they are created.
+//!
+//! # Liquid Network Support
+//!
+//! Recipients of several assets are funded asset by asset. Fees are paid in
+//! the fee asset only, so inputs and change outputs of other assets add to the
+//! fee the fee asset has to cover. Pass a [`TxEstimator`] for the Liquid
+//! network so the range and surjection proofs of confidential outputs are
+//! paid for.
+//!
+//! # Example
+//!
+//! ```
+//! use green_rs::coin_selection::{CoinSelector, SelectionStrategy};
+//! use green_rs::types::transaction::Addressee;
+//! use green_rs::types::{AddressType, FeeRate, UnspentOutput};
+//! use std::collections::HashMap;
+//!
+//! let utxo = |txhash: &str, satoshi| UnspentOutput {
+//!     txhash: txhash.to_string(),
+//!     vout: 0,
+//!     satoshi,
+//!     asset_id: None,
+//!     block_height: Some(100),
+//!     confirmations: Some(6),
+//!     address: None,
+//!     address_type: Some(AddressType::P2wpkh),
+//!     script_pubkey: None,
+//!     subaccount: 0,
+//!     pointer: 0,
+//!     is_internal: false,
+//!     is_confidential: false,
+//!     is_frozen: false,
+//!     memo: None,
+//! };
+//! let utxos = HashMap::from([(
+//!     "btc".to_string(),
+//!     vec![utxo("aa", 40_000), utxo("bb", 25_000), utxo("cc", 10_000)],
+//! )]);
+//! let recipients = [Addressee {
+//!     address: "bcrt1qvewsdxxmeraett7ztsaym88jsrv85kzmev28cl".to_string(),
+//!     satoshi: 30_000,
+//!     asset_id: None,
+//! }];
+//!
+//! let selection = CoinSelector::new(SelectionStrategy::LargestFirst)
+//!     .fee_rate(FeeRate::from_sat_per_vb(2))
+//!     .select(&utxos, &recipients)
+//!     .unwrap();
+//!
+//! assert_eq!(selection.inputs[0].txhash, "aa");
+//! assert_eq!(selection.input_total("btc"), 30_000 + selection.change["btc"] + selection.fee);
+//! ```
+
+use crate::config::Network;
+use crate::error::{Error, ErrorKind, Result};
+use crate::estimation::{ScriptType, TxEstimator};
+use crate::types::common::{AssetId, Satoshis};
+use crate::types::fee::FeeRate;
+use crate::types::transaction::{Addressee, CreateTransactionRequest, UtxoRef};
+use crate::types::utxo::UnspentOutput;
+use crate::types::wallet::FeeEstimates;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+
+/// Key of the fee asset in `get_unspent_outputs` results on Bitcoin
+pub const BITCOIN_ASSET: &str = "btc";
+
+/// Default dust limit: change below this is added to the fee instead
+pub const DEFAULT_DUST_LIMIT: Satoshis = 546;
+
+/// Branch-and-bound gives up after exploring this many branches
+const BNB_MAX_TRIES: usize = 100_000;
+
+/// Rounds of random subset search in knapsack
+const KNAPSACK_ITERATIONS: usize = 1000;
+
+/// How inputs are chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SelectionStrategy {
+    /// Search for a changeless match, falling back to [`Knapsack`](Self::Knapsack)
+    #[default]
+    BranchAndBound,
+    /// Spend the largest outputs first
+    LargestFirst,
+    /// Spend the oldest (lowest block height) outputs first
+    OldestFirst,
+    /// Approximate the subset closest to the target
+    Knapsack,
+}
+
+/// Chooses inputs for a transaction
+///
+/// Built with chained setters like the other builders in this crate.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct CoinSelector {
+    strategy: SelectionStrategy,
+    fee_rate: FeeRate,
+    fee_asset: AssetId,
+    subaccount: Option<u32>,
+    dust_limit: Satoshis,
+    estimator: TxEstimator,
+}
+
+impl Default for CoinSelector {
+    fn default() -> Self {
+        Self::new(SelectionStrategy::default())
+    }
+}
+
+impl CoinSelector {
+    /// Create a selector using `strategy` at 1 sat/vB
+    pub fn new(strategy: SelectionStrategy) -> Self {
+        Self {
+            strategy,
+            fee_rate: FeeRate::BITCOIN_MIN_RELAY,
+            fee_asset: BITCOIN_ASSET.to_string(),
+            subaccount: None,
+            dust_limit: DEFAULT_DUST_LIMIT,
+            estimator: TxEstimator::new(Network::Mainnet),
+        }
+    }
+
+    /// Set the fee rate
+    pub const fn fee_rate(mut self, fee_rate: FeeRate) -> Self {
+        self.fee_rate = fee_rate;
+        self
+    }
+
+    /// Use the fee rate estimated to confirm within `target_blocks`
+    ///
+    /// See [`FeeEstimates::for_target`]. Keeps the current fee rate if there
+    /// are no estimates.
+    pub fn fee_estimates(mut self, estimates: &FeeEstimates, target_blocks: u32) -> Self {
+        if let Some(rate) = estimates.for_target(target_blocks) {
+            self.fee_rate = rate;
+        }
+        self
+    }
+
+    /// Set the key of the asset fees are paid in
+    ///
+    /// Defaults to `"btc"`. On Liquid, set it to the policy asset (L-BTC) ID.
+    pub fn fee_asset(mut self, asset_id: impl Into<AssetId>) -> Self {
+        self.fee_asset = asset_id.into();
+        self
+    }
+
+    /// Only spend outputs of `subaccount`
+    pub const fn subaccount(mut self, subaccount: u32) -> Self {
+        self.subaccount = Some(subaccount);
+        self
+    }
+
+    /// Set the smallest change worth creating; anything less goes to the fee
+    pub const fn dust_limit(mut self, dust_limit: Satoshis) -> Self {
+        self.dust_limit = dust_limit;
+        self
+    }
+
+    /// Size the transaction with `estimator`
+    ///
+    /// Its network and script types size the recipients and change; inputs
+    /// are sized by their address type. Defaults to a mainnet estimator.
+    pub fn estimator(mut self, estimator: TxEstimator) -> Self {
+        self.estimator = estimator;
+        self
+    }
+
+    /// Select inputs paying `recipients`
+    ///
+    /// Recipients without an asset ID are paid in the fee asset.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Validation`] of kind `InvalidAmount` if there are
+    /// no recipients or one is paid nothing, and of kind `InsufficientFunds`
+    /// if the spendable outputs cannot cover an asset's amount and the fee
+    pub fn select(
+        &self,
+        utxos: &HashMap<AssetId, Vec<UnspentOutput>>,
+        recipients: &[Addressee],
+    ) -> Result<CoinSelection> {
+        if recipients.is_empty() {
+            return Err(Error::validation(
+                ErrorKind::InvalidAmount,
+                "No recipients to fund",
+            ));
+        }
+        let mut targets: BTreeMap<AssetId, Satoshis> = BTreeMap::new();
+        for recipient in recipients {
+            if recipient.satoshi == 0 {
+                return Err(Error::validation(
+                    ErrorKind::InvalidAmount,
+                    format!("Amount to {} must be positive", recipient.address),
+                ));
+            }
+            let asset = recipient
+                .asset_id
+                .clone()
+                .unwrap_or_else(|| self.fee_asset.clone());
+            *targets.entry(asset).or_default() += recipient.satoshi;
+        }
+
+        let sizing = &self.estimator;
+        let mut inputs = Vec::new();
+        let mut change = HashMap::new();
+        let recipient_count = u32::try_from(recipients.len()).unwrap_or(u32::MAX);
+        let mut change_count = 0;
+        let mut input_weight = 0;
+
+        // Other assets first: their inputs and change add to the fee
+        for (asset, &amount) in targets
+            .iter()
+            .filter(|(asset, _)| **asset != self.fee_asset)
+        {
+            let candidates = self.candidates(utxos, asset, false);
+            let picked = self
+                .run(&candidates, amount, 0, 1)
+                .ok_or_else(|| insufficient(asset, amount, &candidates))?;
+            let total: Satoshis = picked.iter().map(|&i| candidates[i].utxo.satoshi).sum();
+            if total > amount {
+                change.insert(asset.clone(), total - amount);
+                change_count += 1;
+            }
+            for i in picked {
+                input_weight += candidates[i].weight;
+                inputs.push(candidates[i].utxo.clone());
+            }
+        }
+
+        // The fee asset covers its own amount and the whole fee. Liquid
+        // proofs grow with the input count, so outputs are priced for every
+        // input that may be spent.
+        let candidates = self.candidates(utxos, &self.fee_asset, true);
+        let max_inputs = u32::try_from(inputs.len() + candidates.len()).unwrap_or(u32::MAX);
+        let amount = targets.get(&self.fee_asset).copied().unwrap_or(0);
+        let fixed_weight = sizing.overhead_weight()
+            + input_weight
+            + recipient_count * sizing.recipient_weight(max_inputs)
+            + change_count * sizing.change_weight(max_inputs);
+        let target = amount + self.fee(fixed_weight);
+        let change_fee = self.fee(sizing.change_weight(max_inputs));
+        let cost_of_change = change_fee + self.fee(sizing.change_spend_weight());
+        let (picked, strategy) = self
+            .run_with_fallback(
+                &candidates,
+                target,
+                cost_of_change,
+                change_fee + self.dust_limit,
+            )
+            .ok_or_else(|| insufficient(&self.fee_asset, target, &candidates))?;
+
+        let effective: Satoshis = picked.iter().map(|&i| candidates[i].effective).sum();
+        let total: Satoshis = picked.iter().map(|&i| candidates[i].utxo.satoshi).sum();
+        for &i in &picked {
+            input_weight += candidates[i].weight;
+            inputs.push(candidates[i].utxo.clone());
+        }
+        let excess = effective - target;
+        let fee_asset_change = if excess >= change_fee + self.dust_limit {
+            change_count += 1;
+            excess - change_fee
+        } else {
+            0
+        };
+        if fee_asset_change > 0 {
+            change.insert(self.fee_asset.clone(), fee_asset_change);
+        }
+
+        let input_count = u32::try_from(inputs.len()).unwrap_or(u32::MAX);
+        let weight = sizing.overhead_weight()
+            + input_weight
+            + recipient_count * sizing.recipient_weight(input_count)
+            + change_count * sizing.change_weight(input_count);
+
+        Ok(CoinSelection {
+            inputs,
+            change,
+            fee: total - amount - fee_asset_change,
+            vsize: weight.div_ceil(4),
+            fee_rate: self.fee_rate,
+            strategy,
+        })
+    }
+
+    /// Spendable outputs of `asset`, with their effective values
+    fn candidates<'a>(
+        &self,
+        utxos: &'a HashMap<AssetId, Vec<UnspentOutput>>,
+        asset: &str,
+        pays_fee: bool,
+    ) -> Vec<Candidate<'a>> {
+        utxos
+            .get(asset)
+            .into_iter()
+            .flatten()
+            .filter(|utxo| !utxo.is_frozen)
+            .filter(|utxo| self.subaccount.is_none_or(|sub| utxo.subaccount == sub))
+            .filter_map(|utxo| {
+                let weight = self.estimator.input_weight(ScriptType::for_utxo(utxo));
+                let effective = if pays_fee {
+                    utxo.satoshi.checked_sub(self.fee(weight))?
+                } else {
+                    utxo.satoshi
+                };
+                (effective > 0).then_some(Candidate {
+                    utxo,
+                    effective,
+                    weight,
+                })
+            })
+            .collect()
+    }
+
+    /// Run the configured strategy, falling back to knapsack for branch-and-bound
+    fn run_with_fallback(
+        &self,
+        candidates: &[Candidate<'_>],
+        target: Satoshis,
+        cost_of_change: Satoshis,
+        min_change: Satoshis,
+    ) -> Option<(Vec<usize>, SelectionStrategy)> {
+        if self.strategy == SelectionStrategy::BranchAndBound {
+            if let Some(picked) = branch_and_bound(candidates, target, cost_of_change) {
+                return Some((picked, SelectionStrategy::BranchAndBound));
+            }
+            return knapsack(candidates, target, min_change)
+                .map(|picked| (picked, SelectionStrategy::Knapsack));
+        }
+        self.run(candidates, target, cost_of_change, min_change)
+            .map(|picked| (picked, self.strategy))
+    }
+
+    fn run(
+        &self,
+        candidates: &[Candidate<'_>],
+        target: Satoshis,
+        cost_of_change: Satoshis,
+        min_change: Satoshis,
+    ) -> Option<Vec<usize>> {
+        match self.strategy {
+            SelectionStrategy::BranchAndBound => {
+                branch_and_bound(candidates, target, cost_of_change)
+                    .or_else(|| knapsack(candidates, target, min_change))
+            }
+            SelectionStrategy::LargestFirst => {
+                let mut order: Vec<usize> = (0..candidates.len()).collect();
+                order.sort_by_key(|&i| Reverse(candidates[i].effective));
+                accumulate(candidates, order, target)
+            }
+            SelectionStrategy::OldestFirst => {
+                let mut order: Vec<usize> = (0..candidates.len()).collect();
+                // Unconfirmed outputs have no height and go last
+                order.sort_by_key(|&i| {
+                    let height = candidates[i].utxo.block_height;
+                    (height.is_none(), height, Reverse(candidates[i].effective))
+                });
+                accumulate(candidates, order, target)
+            }
+            SelectionStrategy::Knapsack => knapsack(candidates, target, min_change),
+        }
+    }
+
+    /// Fee for `weight` weight units, rounded up to whole vbytes
+    fn fee(&self, weight: u32) -> Satoshis {
+        self.fee_rate.fee_for_vsize(weight.div_ceil(4))
+    }
+}
+
+/// Inputs chosen by a [`CoinSelector`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoinSelection {
+    /// Outputs to spend
+    pub inputs: Vec<UnspentOutput>,
+    /// Change returned to the wallet, by asset; assets without change are absent
+    pub change: HashMap<AssetId, Satoshis>,
+    /// Fee paid in the fee asset, including any change too small to keep
+    pub fee: Satoshis,
+    /// Estimated virtual size of the transaction
+    pub vsize: u32,
+    /// Fee rate the selection was made at
+    pub fee_rate: FeeRate,
+    /// Strategy that produced the fee asset inputs
+    ///
+    /// `Knapsack` when branch-and-bound found no changeless match.
+    pub strategy: SelectionStrategy,
+}
+
+impl CoinSelection {
+    /// References to the selected outputs
+    #[must_use]
+    pub fn utxo_refs(&self) -> Vec<UtxoRef> {
+        self.inputs
+            .iter()
+            .map(|utxo| UtxoRef {
+                txid: utxo.txhash.clone(),
+                vout: utxo.vout,
+            })
+            .collect()
+    }
+
+    /// Total value of the selected outputs of `asset_id`
+    ///
+    /// Outputs without an asset ID count as `"btc"`.
+    #[must_use]
+    pub fn input_total(&self, asset_id: &str) -> Satoshis {
+        self.inputs
+            .iter()
+            .filter(|utxo| utxo.asset_id.as_deref().unwrap_or(BITCOIN_ASSET) == asset_id)
+            .map(|utxo| utxo.satoshi)
+            .sum()
+    }
+
+    /// Spend exactly the selected outputs, at the selection's fee rate
+    pub fn apply_to(&self, request: &mut CreateTransactionRequest) {
+        request.utxos = Some(self.utxo_refs());
+        request.fee_rate = Some(self.fee_rate);
+    }
+}
+
+/// An output that may be selected
+#[derive(Debug, Clone, Copy)]
+struct Candidate<'a> {
+    utxo: &'a UnspentOutput,
+    effective: Satoshis,
+    weight: u32,
+}
+
+fn insufficient(asset: &str, required: Satoshis, candidates: &[Candidate<'_>]) -> Error {
+    let available: Satoshis = candidates.iter().map(|c| c.utxo.satoshi).sum();
+    Error::validation(
+        ErrorKind::InsufficientFunds,
+        format!(
+            "Insufficient funds: {required} sat of {asset} needed with fees, \
+             {available} sat spendable"
+        ),
+    )
+}
+
+/// Take candidates in `order` until the target is reached
+fn accumulate(
+    candidates: &[Candidate<'_>],
+    order: Vec<usize>,
+    target: Satoshis,
+) -> Option<Vec<usize>> {
+    let mut total = 0;
+    let mut picked = Vec::new();
+    for i in order {
+        if total >= target {
+            break;
+        }
+        total += candidates[i].effective;
+        picked.push(i);
+    }
+    (total >= target).then_some(picked)
+}
+
+/// Depth-first search for a subset within `cost_of_change` above the target
+///
+/// Among the matches found, the one wasting least over the target wins.
+fn branch_and_bound(
+    candidates: &[Candidate<'_>],
+    target: Satoshis,
+    cost_of_change: Satoshis,
+) -> Option<Vec<usize>> {
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by_key(|&i| Reverse(candidates[i].effective));
+    let values: Vec<Satoshis> = order.iter().map(|&i| candidates[i].effective).collect();
+
+    let mut available: Satoshis = values.iter().sum();
+    if available < target {
+        return None;
+    }
+    let mut value = 0;
+    let mut selection: Vec<usize> = Vec::new();
+    let mut best: Option<(Satoshis, Vec<usize>)> = None;
+    let mut index = 0;
+
+    for _ in 0..BNB_MAX_TRIES {
+        let mut backtrack = false;
+        if value + available < target || value > target + cost_of_change {
+            backtrack = true;
+        } else if value >= target {
+            let waste = value - target;
+            if best
+                .as_ref()
+                .is_none_or(|(best_waste, _)| waste < *best_waste)
+            {
+                best = Some((waste, selection.clone()));
+            }
+            if waste == 0 {
+                break;
+            }
+            backtrack = true;
+        }
+
+        if backtrack {
+            // Undo the exclusions since the last inclusion, then exclude it
+            let Some(&last) = selection.last() else {
+                break;
+            };
+            while index > last + 1 {
+                index -= 1;
+                available += values[index];
+            }
+            selection.pop();
+            value -= values[last];
+            index = last + 1;
+        } else {
+            available -= values[index];
+            // Excluding a value and then including an equal one is redundant
+            let redundant = selection
+                .last()
+                .is_some_and(|&last| last + 1 != index && values[index] == values[index - 1]);
+            if !redundant {
+                selection.push(index);
+                value += values[index];
+            }
+            index += 1;
+        }
+    }
+
+    best.map(|(_, selection)| selection.into_iter().map(|i| order[i]).collect())
+}
+
+/// Approximate the best subset, preferring change of at least `min_change`
+///
+/// Deterministic: the random passes use a fixed-seed generator, so the same
+/// outputs always give the same selection.
+fn knapsack(
+    candidates: &[Candidate<'_>],
+    target: Satoshis,
+    min_change: Satoshis,
+) -> Option<Vec<usize>> {
+    if let Some(exact) = (0..candidates.len()).find(|&i| candidates[i].effective == target) {
+        return Some(vec![exact]);
+    }
+
+    let padded = target + min_change;
+    let mut smaller: Vec<usize> = (0..candidates.len())
+        .filter(|&i| candidates[i].effective < padded)
+        .collect();
+    smaller.sort_by_key(|&i| Reverse(candidates[i].effective));
+    let lowest_larger = (0..candidates.len())
+        .filter(|&i| candidates[i].effective >= padded)
+        .min_by_key(|&i| candidates[i].effective);
+
+    let smaller_total: Satoshis = smaller.iter().map(|&i| candidates[i].effective).sum();
+    if smaller_total == target {
+        return Some(smaller);
+    }
+    if smaller_total < target {
+        return lowest_larger.map(|i| vec![i]);
+    }
+
+    let values: Vec<Satoshis> = smaller.iter().map(|&i| candidates[i].effective).collect();
+    let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+    let (mut best_total, mut best) = approximate_best_subset(&values, target, &mut rng);
+    if best_total != target && smaller_total >= padded {
+        (best_total, best) = approximate_best_subset(&values, padded, &mut rng);
+    }
+
+    // A single larger output wins if the subset leaves too little change or costs more
+    match lowest_larger {
+        Some(larger)
+            if (best_total != target && best_total < padded)
+                || candidates[larger].effective <= best_total =>
+        {
+            Some(vec![larger])
+        }
+        _ => Some(
+            best.iter()
+                .zip(&smaller)
+                .filter(|(&included, _)| included)
+                .map(|(_, &i)| i)
+                .collect(),
+        ),
+    }
+}
+
+/// Random passes over `values` (sorted descending) for the smallest sum reaching `target`
+fn approximate_best_subset(
+    values: &[Satoshis],
+    target: Satoshis,
+    rng: &mut XorShift,
+) -> (Satoshis, Vec<bool>) {
+    let mut best = vec![true; values.len()];
+    let mut best_total: Satoshis = values.iter().sum();
+
+    for _ in 0..KNAPSACK_ITERATIONS {
+        if best_total == target {
+            break;
+        }
+        let mut included = vec![false; values.len()];
+        let mut total = 0;
+        let mut reached = false;
+        for pass in 0..2 {
+            if reached {
+                break;
+            }
+            for i in 0..values.len() {
+                // First pass includes at random, second fills in the rest
+                let include = if pass == 0 {
+                    rng.coin_flip()
+                } else {
+                    !included[i]
+                };
+                if include {
+                    total += values[i];
+                    included[i] = true;
+                    if total >= target {
+                        reached = true;
+                        if total < best_total {
+                            best_total = total;
+                            best.clone_from(&included);
+                        }
+                        total -= values[i];
+                        included[i] = false;
+                    }
+                }
+            }
+        }
+    }
+    (best_total, best)
+}
+
+/// Small deterministic pseudo-random generator for knapsack passes
+struct XorShift(u64);
+
+impl XorShift {
+    const fn coin_flip(&mut self) -> bool {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 & 1 == 1
+    }
+}
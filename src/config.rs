@@ -0,0 +1,292 @@
+//! Client configuration
+//!
+//! This module provides the configuration shared by [`GreenClient`](crate::GreenClient)
+//! and [`AsyncGreenClient`](crate::AsyncGreenClient): which `green-cli` executable to
+//! spawn, which network it talks to, where it keeps its data, and any extra
+//! environment or global arguments to pass on every invocation, and how long
+//! a command may run before it is killed.
+//!
+//! Each client owns its configuration, so a single process can drive several
+//! wallets on different networks side by side.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use green_rs::{ClientConfig, GreenClient, Network};
+//!
+//! let config = ClientConfig::builder()
+//!     .binary("/opt/green/bin/green-cli")
+//!     .network(Network::LiquidTestnet)
+//!     .config_dir("/var/lib/payments/wallet-a")
+//!     .env("RUST_LOG", "warn")
+//!     .timeout(std::time::Duration::from_secs(30))
+//!     .build();
+//!
+//! let client = GreenClient::with_config(config);
+//! ```
+
+use crate::error::{Error, Result};
+use crate::transport::{CliCommand, CliParams, Params, ParamsMode};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Default name of the green-cli executable, resolved through `PATH`
+pub const DEFAULT_BINARY: &str = "green-cli";
+
+/// Default time a single green-cli command may run before it is killed
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Network a Green wallet operates on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Network {
+    /// Bitcoin mainnet
+    #[serde(rename = "mainnet")]
+    Mainnet,
+    /// Bitcoin testnet
+    #[serde(rename = "testnet")]
+    Testnet,
+    /// Liquid mainnet
+    #[serde(rename = "liquid")]
+    Liquid,
+    /// Liquid testnet
+    #[serde(rename = "testnet-liquid", alias = "liquid-testnet")]
+    LiquidTestnet,
+    /// Local Electrum regtest environment
+    #[serde(rename = "electrum-localtest")]
+    ElectrumLocaltest,
+}
+
+impl Network {
+    /// Name of the network as understood by `green-cli --network`
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Mainnet => "mainnet",
+            Self::Testnet => "testnet",
+            Self::Liquid => "liquid",
+            Self::LiquidTestnet => "testnet-liquid",
+            Self::ElectrumLocaltest => "electrum-localtest",
+        }
+    }
+
+    /// Whether this is a Liquid network
+    #[must_use]
+    pub const fn is_liquid(self) -> bool {
+        matches!(self, Self::Liquid | Self::LiquidTestnet)
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Network {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "mainnet" => Ok(Self::Mainnet),
+            "testnet" => Ok(Self::Testnet),
+            "liquid" => Ok(Self::Liquid),
+            "testnet-liquid" | "liquid-testnet" => Ok(Self::LiquidTestnet),
+            "electrum-localtest" => Ok(Self::ElectrumLocaltest),
+            other => Err(Error::unexpected(format!("Unknown network: {other}"))),
+        }
+    }
+}
+
+/// Configuration for a Green client
+///
+/// Use [`ClientConfig::builder`] to construct a configuration, or
+/// [`ClientConfig::default`] to spawn `green-cli` from `PATH` with
+/// its own defaults.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientConfig {
+    /// Path to the green-cli executable
+    pub binary: PathBuf,
+    /// Network to operate on (green-cli default if `None`)
+    pub network: Option<Network>,
+    /// Configuration/data directory passed as `--config-dir`
+    pub config_dir: Option<PathBuf>,
+    /// Extra environment variables set on every invocation
+    pub env: Vec<(OsString, OsString)>,
+    /// Extra global arguments placed before every command
+    pub global_args: Vec<OsString>,
+    /// Default time a command may run before it is killed (`None` waits forever)
+    pub timeout: Option<Duration>,
+    /// How JSON parameters are passed to green-cli
+    pub params_mode: ParamsMode,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            binary: PathBuf::from(DEFAULT_BINARY),
+            network: None,
+            config_dir: None,
+            env: Vec::new(),
+            global_args: Vec::new(),
+            timeout: Some(DEFAULT_TIMEOUT),
+            params_mode: ParamsMode::Argv,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Create a builder for a client configuration
+    #[must_use]
+    pub fn builder() -> ClientConfigBuilder {
+        ClientConfigBuilder::new()
+    }
+
+    /// Global arguments derived from this configuration
+    ///
+    /// These precede the command arguments on every invocation, in the
+    /// order `--network`, `--config-dir`, then any extra global arguments.
+    #[must_use]
+    pub fn global_arguments(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        if let Some(network) = self.network {
+            args.push("--network".into());
+            args.push(network.as_str().into());
+        }
+        if let Some(ref config_dir) = self.config_dir {
+            args.push("--config-dir".into());
+            args.push(config_dir.clone().into_os_string());
+        }
+        args.extend(self.global_args.iter().cloned());
+        args
+    }
+
+    /// Describe a green-cli invocation of `args` under this configuration
+    ///
+    /// The command uses the configured executable, global arguments,
+    /// environment and default timeout.
+    #[must_use]
+    pub fn command(&self, args: &[&str]) -> CliCommand {
+        CliCommand {
+            program: self.binary.clone(),
+            global_args: self.global_arguments(),
+            args: args.iter().map(ToString::to_string).collect(),
+            env: self.env.clone(),
+            timeout: self.timeout,
+            params: None,
+            params_mode: self.params_mode,
+        }
+    }
+
+    /// Describe a green-cli invocation of `args` with JSON `params`
+    ///
+    /// The parameters are passed according to the configured
+    /// [`ParamsMode`]; sensitive parameters never go on the command line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `params` cannot be serialized to JSON
+    pub fn command_with_params<P: CliParams + ?Sized>(
+        &self,
+        args: &[&str],
+        params: &P,
+    ) -> Result<CliCommand> {
+        Ok(self.command(args).with_params(Params::new(params)?))
+    }
+}
+
+/// Builder for `ClientConfig`
+///
+/// # Example
+///
+/// ```no_run
+/// use green_rs::{AsyncGreenClient, ClientConfig, Network};
+///
+/// let client = AsyncGreenClient::with_config(
+///     ClientConfig::builder()
+///         .network(Network::ElectrumLocaltest)
+///         .global_arg("--electrum-url")
+///         .global_arg("127.0.0.1:50001")
+///         .build(),
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ClientConfigBuilder {
+    config: ClientConfig,
+}
+
+impl ClientConfigBuilder {
+    /// Create a new builder instance with default settings
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the path to the green-cli executable
+    #[must_use]
+    pub fn binary<P: Into<PathBuf>>(mut self, binary: P) -> Self {
+        self.config.binary = binary.into();
+        self
+    }
+
+    /// Set the network to operate on
+    #[must_use]
+    pub const fn network(mut self, network: Network) -> Self {
+        self.config.network = Some(network);
+        self
+    }
+
+    /// Set the configuration/data directory
+    #[must_use]
+    pub fn config_dir<P: Into<PathBuf>>(mut self, config_dir: P) -> Self {
+        self.config.config_dir = Some(config_dir.into());
+        self
+    }
+
+    /// Add an environment variable set on every invocation
+    #[must_use]
+    pub fn env<K: Into<OsString>, V: Into<OsString>>(mut self, key: K, value: V) -> Self {
+        self.config.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Add a global argument placed before every command
+    #[must_use]
+    pub fn global_arg<S: Into<OsString>>(mut self, arg: S) -> Self {
+        self.config.global_args.push(arg.into());
+        self
+    }
+
+    /// Set the default time a command may run before it is killed
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    /// Set how JSON parameters are passed to green-cli
+    ///
+    /// Use [`ParamsMode::Stdin`] or [`ParamsMode::File`] when the installed
+    /// green-cli supports them to keep all parameters off the command line.
+    #[must_use]
+    pub const fn params_mode(mut self, mode: ParamsMode) -> Self {
+        self.config.params_mode = mode;
+        self
+    }
+
+    /// Let commands run without a time limit
+    #[must_use]
+    pub const fn no_timeout(mut self) -> Self {
+        self.config.timeout = None;
+        self
+    }
+
+    /// Build the final configuration
+    #[must_use]
+    pub fn build(self) -> ClientConfig {
+        self.config
+    }
+}
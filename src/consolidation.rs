@@ -0,0 +1,386 @@
+//! UTXO consolidation planning
+//!
+//! A wallet that receives many small payments ends up with many small
+//! outputs, and every one of them adds an input to the transactions that
+//! later spend it. [`ConsolidationPlanner`] merges a subaccount's outputs
+//! into fewer, larger ones while fees are low, in transactions of at most a
+//! given number of inputs that pay back to the same subaccount.
+//!
+//! The resulting [`ConsolidationPlan`] reports what consolidating costs at the
+//! chosen fee rate and what it is expected to save if the same outputs would
+//! otherwise be spent at a projected future fee rate. It can be executed with
+//! [`ConsolidationPlan::execute`], which sends each transaction through the
+//! [`TransactionApi`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use green_rs::api::utxo::UtxoApi;
+//! use green_rs::api::WalletExt;
+//! use green_rs::consolidation::ConsolidationPlanner;
+//! use green_rs::types::{FeeRate, GetUnspentOutputsParams};
+//! use green_rs::GreenClient;
+//!
+//! let client = GreenClient::new();
+//! let utxos = client.get_unspent_outputs(GetUnspentOutputsParams::default())?;
+//! let plan = ConsolidationPlanner::new(0)
+//!     .fee_estimates(&client.get_fee_estimates()?, 144)
+//!     .future_fee_rate(FeeRate::from_sat_per_vb(50))
+//!     .max_inputs(50)
+//!     .plan(&utxos)?;
+//!
+//! if plan.savings() > 0 {
+//!     for tx in plan.execute(&client)? {
+//!         println!("Consolidated in {}", tx.txid);
+//!     }
+//! }
+//! # Ok::<(), green_rs::Error>(())
+//! ```
+
+use crate::api::address::{AddressApi, AsyncAddressApi};
+use crate::api::transaction::{AsyncTransactionApi, TransactionApi};
+use crate::coin_selection::{BITCOIN_ASSET, DEFAULT_DUST_LIMIT};
+use crate::config::Network;
+use crate::error::{Error, ErrorKind, Result};
+use crate::estimation::{ScriptType, TxEstimator};
+use crate::types::address::GetReceiveAddressRequest;
+use crate::types::common::{Address, AssetId, Satoshis};
+use crate::types::fee::FeeRate;
+use crate::types::transaction::{Addressee, CreateTransactionRequest, Transaction, UtxoRef};
+use crate::types::utxo::UnspentOutput;
+use crate::types::wallet::FeeEstimates;
+use std::collections::HashMap;
+
+/// Default maximum number of inputs per consolidation transaction
+pub const DEFAULT_MAX_INPUTS: usize = 100;
+
+/// Plans consolidation of a subaccount's outputs
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct ConsolidationPlanner {
+    subaccount: u32,
+    fee_rate: FeeRate,
+    future_fee_rate: Option<FeeRate>,
+    max_inputs: usize,
+    max_value: Option<Satoshis>,
+    fee_asset: AssetId,
+    dust_limit: Satoshis,
+    estimator: TxEstimator,
+}
+
+impl ConsolidationPlanner {
+    /// Create a planner for `subaccount` at 1 sat/vB
+    pub fn new(subaccount: u32) -> Self {
+        Self {
+            subaccount,
+            fee_rate: FeeRate::BITCOIN_MIN_RELAY,
+            future_fee_rate: None,
+            max_inputs: DEFAULT_MAX_INPUTS,
+            max_value: None,
+            fee_asset: BITCOIN_ASSET.to_string(),
+            dust_limit: DEFAULT_DUST_LIMIT,
+            estimator: TxEstimator::new(Network::Mainnet),
+        }
+    }
+
+    /// Set the fee rate to consolidate at
+    pub const fn fee_rate(mut self, fee_rate: FeeRate) -> Self {
+        self.fee_rate = fee_rate;
+        self
+    }
+
+    /// Consolidate at the fee rate estimated to confirm within `target_blocks`
+    ///
+    /// Consolidation is rarely urgent, so a slow target is usually cheapest.
+    /// Keeps the current fee rate if there are no estimates.
+    pub fn fee_estimates(mut self, estimates: &FeeEstimates, target_blocks: u32) -> Self {
+        if let Some(rate) = estimates.for_target(target_blocks) {
+            self.fee_rate = rate;
+        }
+        self
+    }
+
+    /// Set the fee rate the outputs are expected to be spent at later
+    ///
+    /// Defaults to the consolidation fee rate.
+    #[allow(clippy::missing_const_for_fn)] // Cannot be const due to Option::Some
+    pub fn future_fee_rate(mut self, fee_rate: FeeRate) -> Self {
+        self.future_fee_rate = Some(fee_rate);
+        self
+    }
+
+    /// Set the maximum number of inputs per transaction
+    pub const fn max_inputs(mut self, max_inputs: usize) -> Self {
+        self.max_inputs = max_inputs;
+        self
+    }
+
+    /// Only consolidate outputs worth at most `max_value`
+    #[allow(clippy::missing_const_for_fn)] // Cannot be const due to Option::Some
+    pub fn max_value(mut self, max_value: Satoshis) -> Self {
+        self.max_value = Some(max_value);
+        self
+    }
+
+    /// Set the key of the asset to consolidate and pay fees in
+    ///
+    /// Defaults to `"btc"`. On Liquid, set it to the policy asset (L-BTC) ID.
+    pub fn fee_asset(mut self, asset_id: impl Into<AssetId>) -> Self {
+        self.fee_asset = asset_id.into();
+        self
+    }
+
+    /// Set the smallest output a consolidation transaction may produce
+    pub const fn dust_limit(mut self, dust_limit: Satoshis) -> Self {
+        self.dust_limit = dust_limit;
+        self
+    }
+
+    /// Size the transactions with `estimator`
+    ///
+    /// Its network and script type size the merged output; inputs are sized
+    /// by their address type. Defaults to a mainnet estimator.
+    pub fn estimator(mut self, estimator: TxEstimator) -> Self {
+        self.estimator = estimator;
+        self
+    }
+
+    /// Plan the consolidation of the subaccount's outputs in `utxos`
+    ///
+    /// Outputs are merged smallest first. Frozen outputs are left alone, and
+    /// outputs worth less than the fee to spend them, or left over alone in
+    /// the last batch, are reported as skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Validation`] if fewer than two inputs per
+    /// transaction are allowed
+    pub fn plan(&self, utxos: &HashMap<AssetId, Vec<UnspentOutput>>) -> Result<ConsolidationPlan> {
+        if self.max_inputs < 2 {
+            return Err(Error::validation(
+                ErrorKind::Other,
+                "Consolidation needs at least two inputs per transaction",
+            ));
+        }
+        let future_fee_rate = self.future_fee_rate.unwrap_or(self.fee_rate);
+
+        let mut candidates: Vec<&UnspentOutput> = utxos
+            .get(&self.fee_asset)
+            .into_iter()
+            .flatten()
+            .filter(|utxo| utxo.subaccount == self.subaccount && !utxo.is_frozen)
+            .filter(|utxo| self.max_value.is_none_or(|max| utxo.satoshi <= max))
+            .collect();
+        candidates.sort_by_key(|utxo| utxo.satoshi);
+
+        let mut skipped = Vec::new();
+        let mut spendable = Vec::new();
+        for utxo in candidates {
+            if utxo.satoshi > self.fee_rate.fee_for_vsize(self.spend_vsize(utxo)) {
+                spendable.push(utxo);
+            } else {
+                skipped.push(utxo.clone());
+            }
+        }
+
+        let mut transactions = Vec::new();
+        for batch in spendable.chunks(self.max_inputs) {
+            let inputs = u32::try_from(batch.len()).unwrap_or(u32::MAX);
+            let vsize = (self.estimator.overhead_weight()
+                + batch.iter().map(|u| self.spend_weight(u)).sum::<u32>()
+                + self.estimator.change_weight(inputs))
+            .div_ceil(4);
+            let fee = self.fee_rate.fee_for_vsize(vsize);
+            let total: Satoshis = batch.iter().map(|utxo| utxo.satoshi).sum();
+            if batch.len() < 2 || total < fee + self.dust_limit {
+                skipped.extend(batch.iter().map(|&utxo| utxo.clone()));
+                continue;
+            }
+            transactions.push(ConsolidationTx {
+                inputs: batch.iter().map(|&utxo| utxo.clone()).collect(),
+                vsize,
+                fee,
+                output_satoshi: total - fee,
+            });
+        }
+
+        let future_cost_unconsolidated = transactions
+            .iter()
+            .flat_map(|tx| &tx.inputs)
+            .map(|utxo| future_fee_rate.fee_for_vsize(self.spend_vsize(utxo)))
+            .sum();
+        // The merged output goes to the same subaccount, so it has the type of its inputs
+        let future_cost_consolidated = transactions
+            .iter()
+            .map(|tx| future_fee_rate.fee_for_vsize(self.spend_vsize(&tx.inputs[0])))
+            .sum();
+
+        Ok(ConsolidationPlan {
+            subaccount: self.subaccount,
+            fee_rate: self.fee_rate,
+            future_fee_rate,
+            cost: transactions.iter().map(|tx| tx.fee).sum(),
+            future_cost_unconsolidated,
+            future_cost_consolidated,
+            transactions,
+            skipped,
+        })
+    }
+
+    /// Weight of an input spending `utxo`
+    fn spend_weight(&self, utxo: &UnspentOutput) -> u32 {
+        self.estimator.input_weight(ScriptType::for_utxo(utxo))
+    }
+
+    /// Virtual size of an input spending `utxo`
+    fn spend_vsize(&self, utxo: &UnspentOutput) -> u32 {
+        self.spend_weight(utxo).div_ceil(4)
+    }
+}
+
+/// One consolidation transaction in a [`ConsolidationPlan`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsolidationTx {
+    /// Outputs merged by this transaction
+    pub inputs: Vec<UnspentOutput>,
+    /// Estimated virtual size
+    pub vsize: u32,
+    /// Fee at the plan's fee rate
+    pub fee: Satoshis,
+    /// Value of the single merged output
+    pub output_satoshi: Satoshis,
+}
+
+impl ConsolidationTx {
+    /// References to the merged outputs
+    #[must_use]
+    pub fn utxo_refs(&self) -> Vec<UtxoRef> {
+        self.inputs
+            .iter()
+            .map(|utxo| UtxoRef {
+                txid: utxo.txhash.clone(),
+                vout: utxo.vout,
+            })
+            .collect()
+    }
+}
+
+/// Consolidation transactions for a subaccount, with their cost and savings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsolidationPlan {
+    /// Subaccount being consolidated
+    pub subaccount: u32,
+    /// Fee rate the transactions pay
+    pub fee_rate: FeeRate,
+    /// Fee rate the savings are estimated at
+    pub future_fee_rate: FeeRate,
+    /// Transactions to send, each merging its inputs into one output
+    pub transactions: Vec<ConsolidationTx>,
+    /// Outputs left as they are
+    pub skipped: Vec<UnspentOutput>,
+    /// Total fee of all transactions at `fee_rate`
+    pub cost: Satoshis,
+    /// Fee to spend the merged outputs at `future_fee_rate` without consolidating
+    pub future_cost_unconsolidated: Satoshis,
+    /// Fee to spend the consolidated outputs at `future_fee_rate`
+    pub future_cost_consolidated: Satoshis,
+}
+
+impl ConsolidationPlan {
+    /// Number of outputs merged across all transactions
+    #[must_use]
+    pub fn input_count(&self) -> usize {
+        self.transactions.iter().map(|tx| tx.inputs.len()).sum()
+    }
+
+    /// Whether there is nothing to consolidate
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Estimated fee saved by consolidating now rather than at `future_fee_rate`
+    ///
+    /// Negative when consolidating costs more than it saves.
+    #[must_use]
+    pub fn savings(&self) -> i64 {
+        let signed = |sat: Satoshis| i64::try_from(sat).unwrap_or(i64::MAX);
+        signed(self.future_cost_unconsolidated)
+            .saturating_sub(signed(self.future_cost_consolidated))
+            .saturating_sub(signed(self.cost))
+    }
+
+    /// Request for `tx` that pays its merged output to `address`
+    ///
+    /// The request spends exactly the transaction's inputs with `send_all`,
+    /// so green-cli deducts the fee from the merged output.
+    #[must_use]
+    pub fn request(
+        &self,
+        tx: &ConsolidationTx,
+        address: impl Into<Address>,
+    ) -> CreateTransactionRequest {
+        CreateTransactionRequest {
+            addressees: Some(vec![Addressee {
+                address: address.into(),
+                satoshi: tx.output_satoshi,
+                asset_id: tx.inputs[0].asset_id.clone(),
+            }]),
+            fee_rate: Some(self.fee_rate),
+            subaccount: Some(self.subaccount),
+            send_all: true,
+            memo: Some("Consolidation".to_string()),
+            utxos: Some(tx.utxo_refs()),
+            ..Default::default()
+        }
+    }
+
+    /// Send every transaction, each to a new address of the subaccount
+    ///
+    /// Transactions are sent in order and sending stops at the first failure;
+    /// transactions sent before it stay broadcast.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a new address cannot be generated or a
+    /// transaction cannot be sent
+    pub fn execute<C: TransactionApi + AddressApi>(&self, client: &C) -> Result<Vec<Transaction>> {
+        let mut sent = Vec::with_capacity(self.transactions.len());
+        for tx in &self.transactions {
+            let address = client.get_new_address(self.address_request())?;
+            sent.push(client.send_transaction(self.request(tx, address.address))?);
+        }
+        Ok(sent)
+    }
+
+    /// Send every transaction through an async client
+    ///
+    /// Behaves like [`ConsolidationPlan::execute`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a new address cannot be generated or a
+    /// transaction cannot be sent
+    pub async fn execute_async<C>(&self, client: &C) -> Result<Vec<Transaction>>
+    where
+        C: AsyncTransactionApi + AsyncAddressApi + Sync,
+    {
+        let mut sent = Vec::with_capacity(self.transactions.len());
+        for tx in &self.transactions {
+            let address = client.get_new_address(self.address_request()).await?;
+            sent.push(
+                client
+                    .send_transaction(self.request(tx, address.address))
+                    .await?,
+            );
+        }
+        Ok(sent)
+    }
+
+    const fn address_request(&self) -> GetReceiveAddressRequest {
+        GetReceiveAddressRequest {
+            subaccount: Some(self.subaccount),
+            address_type: None,
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! Child-pays-for-parent acceleration
+//!
+//! A transaction stuck in the mempool that cannot be replaced, such as an
+//! incoming payment or one that does not signal RBF, can still be sped up
+//! by spending one of its outputs in a child paying a high fee. Miners
+//! evaluate the two as a package, so the child's fee counts towards the
+//! parent.
+//!
+//! [`CpfpPlanner`] picks an unconfirmed output the wallet controls (an output
+//! with `is_relevant` and a `subaccount`), and computes the child fee that
+//! brings the package to a target fee rate. The [`CpfpPlan`] reports the
+//! package fee rate before and after, and [`CpfpPlan::execute`] sends the
+//! child, paying the output back to a new address of the same subaccount.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use green_rs::api::{TransactionApi, WalletExt};
+//! use green_rs::cpfp::CpfpPlanner;
+//! use green_rs::GreenClient;
+//!
+//! let client = GreenClient::new();
+//! let parent = client.get_transaction_details("a1b2c3...")?;
+//! let plan = CpfpPlanner::new()
+//!     .fee_estimates(&client.get_fee_estimates()?, 2)
+//!     .plan(&parent)?;
+//!
+//! println!(
+//!     "package {} -> {} for {} sat",
+//!     plan.package_fee_rate_before, plan.package_fee_rate_after, plan.child_fee
+//! );
+//! let child = plan.execute(&client)?;
+//! println!("Child {}", child.txid);
+//! # Ok::<(), green_rs::Error>(())
+//! ```
+
+use crate::api::address::{AddressApi, AsyncAddressApi};
+use crate::api::fee_bump::fee_and_vsize;
+use crate::api::transaction::{AsyncTransactionApi, TransactionApi};
+use crate::coin_selection::{BITCOIN_ASSET, DEFAULT_DUST_LIMIT};
+use crate::config::Network;
+use crate::error::{Error, ErrorKind, Result};
+use crate::estimation::{ScriptType, TxEstimator};
+use crate::types::address::{AddressType, GetReceiveAddressRequest};
+use crate::types::common::{Address, AssetId, Satoshis, TxId};
+use crate::types::fee::FeeRate;
+use crate::types::transaction::{
+    Addressee, CreateTransactionRequest, Transaction, TxOutput, UtxoRef,
+};
+use crate::types::wallet::FeeEstimates;
+
+/// Plans a child transaction that accelerates its parent
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct CpfpPlanner {
+    fee_rate: FeeRate,
+    vout: Option<u32>,
+    address_type: Option<AddressType>,
+    fee_asset: AssetId,
+    dust_limit: Satoshis,
+    estimator: TxEstimator,
+}
+
+impl Default for CpfpPlanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CpfpPlanner {
+    /// Create a planner targeting 1 sat/vB
+    pub fn new() -> Self {
+        Self {
+            fee_rate: FeeRate::BITCOIN_MIN_RELAY,
+            vout: None,
+            address_type: None,
+            fee_asset: BITCOIN_ASSET.to_string(),
+            dust_limit: DEFAULT_DUST_LIMIT,
+            estimator: TxEstimator::new(Network::Mainnet),
+        }
+    }
+
+    /// Set the package fee rate to reach
+    pub const fn fee_rate(mut self, fee_rate: FeeRate) -> Self {
+        self.fee_rate = fee_rate;
+        self
+    }
+
+    /// Target the fee rate estimated to confirm within `target_blocks`
+    ///
+    /// Keeps the current fee rate if there are no estimates.
+    pub fn fee_estimates(mut self, estimates: &FeeEstimates, target_blocks: u32) -> Self {
+        if let Some(rate) = estimates.for_target(target_blocks) {
+            self.fee_rate = rate;
+        }
+        self
+    }
+
+    /// Spend output `vout` of the parent
+    ///
+    /// By default the largest output the wallet controls is spent.
+    #[allow(clippy::missing_const_for_fn)] // Cannot be const due to Option::Some
+    pub fn vout(mut self, vout: u32) -> Self {
+        self.vout = Some(vout);
+        self
+    }
+
+    /// Set the address type of the spent output
+    ///
+    /// Used to size the child's input. Without it the input is sized
+    /// conservatively.
+    pub fn address_type(mut self, address_type: AddressType) -> Self {
+        self.address_type = Some(address_type);
+        self
+    }
+
+    /// Set the key of the asset fees are paid in
+    ///
+    /// Defaults to `"btc"`. On Liquid, set it to the policy asset (L-BTC) ID
+    /// and pass a Liquid [`estimator`](Self::estimator).
+    pub fn fee_asset(mut self, asset_id: impl Into<AssetId>) -> Self {
+        self.fee_asset = asset_id.into();
+        self
+    }
+
+    /// Set the smallest output the child may produce
+    pub const fn dust_limit(mut self, dust_limit: Satoshis) -> Self {
+        self.dust_limit = dust_limit;
+        self
+    }
+
+    /// Size the child with `estimator`
+    ///
+    /// Its network and script type size the child's output. Defaults to a
+    /// mainnet estimator.
+    pub fn estimator(mut self, estimator: TxEstimator) -> Self {
+        self.estimator = estimator;
+        self
+    }
+
+    /// Plan a child of `parent` that brings the package to the target rate
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Validation`] if the parent is confirmed, has no
+    /// spendable output of the fee asset the wallet controls, already pays
+    /// the target rate, or the output cannot cover the child fee; returns
+    /// [`Error::InvalidResponse`] if the parent's fee or size is unknown
+    pub fn plan(&self, parent: &Transaction) -> Result<CpfpPlan> {
+        let txid = &parent.txid;
+        if parent.block_height.is_some() || parent.confirmations > 0 {
+            return Err(Error::validation(
+                ErrorKind::Other,
+                format!("transaction {txid} is already confirmed"),
+            ));
+        }
+        let (vout, output) = self.spent_output(parent)?;
+        let output_satoshi = output.satoshi;
+        let (parent_fee, parent_vsize) = fee_and_vsize(parent)?;
+
+        let package_fee_rate_before = FeeRate::from_fee_and_vsize(parent_fee, parent_vsize);
+        if parent_fee >= self.fee_rate.fee_for_vsize(parent_vsize) {
+            return Err(Error::validation(
+                ErrorKind::InvalidFee,
+                format!(
+                    "transaction {txid} already pays {package_fee_rate_before}, at least the \
+                     target {}",
+                    self.fee_rate
+                ),
+            ));
+        }
+
+        let sizing = &self.estimator;
+        let input = ScriptType::from_types(None, self.address_type.as_ref());
+        let child_vsize =
+            (sizing.overhead_weight() + sizing.input_weight(input) + sizing.change_weight(1))
+                .div_ceil(4);
+        let package_fee = self.fee_rate.fee_for_vsize(parent_vsize + child_vsize);
+        let needed = package_fee.saturating_sub(parent_fee);
+        // The child can only set a fee rate, so round it up to cover `needed`
+        let child_fee_rate =
+            FeeRate::from_sat_per_kvb((needed * 1_000).div_ceil(u64::from(child_vsize)));
+        let child_fee = child_fee_rate.fee_for_vsize(child_vsize);
+        if output_satoshi < child_fee + self.dust_limit {
+            return Err(Error::validation(
+                ErrorKind::InsufficientFunds,
+                format!(
+                    "output {txid}:{vout} of {output_satoshi} sat cannot pay a child fee of \
+                     {child_fee} sat"
+                ),
+            ));
+        }
+
+        Ok(CpfpPlan {
+            parent_txid: txid.clone(),
+            outpoint: UtxoRef {
+                txid: txid.clone(),
+                vout,
+            },
+            subaccount: output.subaccount.unwrap_or_default(),
+            asset_id: output.asset_id.clone(),
+            input_satoshi: output_satoshi,
+            parent_fee,
+            parent_vsize,
+            child_vsize,
+            child_fee,
+            child_fee_rate,
+            target_fee_rate: self.fee_rate,
+            package_fee_rate_before,
+            package_fee_rate_after: FeeRate::from_fee_and_vsize(
+                parent_fee + child_fee,
+                parent_vsize + child_vsize,
+            ),
+        })
+    }
+
+    /// Index and details of the parent output to spend
+    fn spent_output<'a>(&self, parent: &'a Transaction) -> Result<(u32, &'a TxOutput)> {
+        parent
+            .outputs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, output)| Some((u32::try_from(index).ok()?, output)))
+            .filter(|(index, _)| self.vout.is_none_or(|vout| vout == *index))
+            .filter(|(_, output)| output.is_relevant && output.subaccount.is_some())
+            .filter(|(_, output)| {
+                output.asset_id.as_deref().unwrap_or(BITCOIN_ASSET) == self.fee_asset
+            })
+            .max_by_key(|(_, output)| output.satoshi)
+            .ok_or_else(|| {
+                let which = self
+                    .vout
+                    .map_or_else(|| "no output".to_string(), |vout| format!("output {vout}"));
+                Error::validation(
+                    ErrorKind::Other,
+                    format!(
+                        "transaction {} has {which} of the fee asset the wallet controls",
+                        parent.txid
+                    ),
+                )
+            })
+    }
+}
+
+/// A child transaction accelerating its parent, with the package fee rates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpfpPlan {
+    /// Transaction being accelerated
+    pub parent_txid: TxId,
+    /// Parent output the child spends
+    pub outpoint: UtxoRef,
+    /// Subaccount owning the spent output
+    pub subaccount: u32,
+    /// Asset of the spent output (`None` for bitcoin)
+    pub asset_id: Option<AssetId>,
+    /// Value of the spent output
+    pub input_satoshi: Satoshis,
+    /// Fee paid by the parent
+    pub parent_fee: Satoshis,
+    /// Virtual size of the parent
+    pub parent_vsize: u32,
+    /// Estimated virtual size of the child
+    pub child_vsize: u32,
+    /// Fee the child pays
+    pub child_fee: Satoshis,
+    /// Fee rate the child is sent at
+    pub child_fee_rate: FeeRate,
+    /// Package fee rate aimed for
+    pub target_fee_rate: FeeRate,
+    /// Fee rate of the parent alone
+    pub package_fee_rate_before: FeeRate,
+    /// Fee rate of parent and child together
+    pub package_fee_rate_after: FeeRate,
+}
+
+impl CpfpPlan {
+    /// Value of the child's single output
+    #[must_use]
+    pub const fn output_satoshi(&self) -> Satoshis {
+        self.input_satoshi - self.child_fee
+    }
+
+    /// Request for the child, paying the spent output to `address`
+    ///
+    /// The request spends exactly the parent output with `send_all`, so
+    /// green-cli deducts the fee from it.
+    #[must_use]
+    pub fn request(&self, address: impl Into<Address>) -> CreateTransactionRequest {
+        CreateTransactionRequest {
+            addressees: Some(vec![Addressee {
+                address: address.into(),
+                satoshi: self.output_satoshi(),
+                asset_id: self.asset_id.clone(),
+            }]),
+            fee_rate: Some(self.child_fee_rate),
+            subaccount: Some(self.subaccount),
+            send_all: true,
+            memo: Some(format!("CPFP for {}", self.parent_txid)),
+            utxos: Some(vec![self.outpoint.clone()]),
+            ..Default::default()
+        }
+    }
+
+    /// Send the child to a new address of the output's subaccount
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a new address cannot be generated or the child
+    /// cannot be sent
+    pub fn execute<C: TransactionApi + AddressApi>(&self, client: &C) -> Result<Transaction> {
+        let address = client.get_new_address(self.address_request())?;
+        client.send_transaction(self.request(address.address))
+    }
+
+    /// Send the child through an async client
+    ///
+    /// Behaves like [`CpfpPlan::execute`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a new address cannot be generated or the child
+    /// cannot be sent
+    pub async fn execute_async<C>(&self, client: &C) -> Result<Transaction>
+    where
+        C: AsyncTransactionApi + AsyncAddressApi + Sync,
+    {
+        let address = client.get_new_address(self.address_request()).await?;
+        client.send_transaction(self.request(address.address)).await
+    }
+
+    const fn address_request(&self) -> GetReceiveAddressRequest {
+        GetReceiveAddressRequest {
+            subaccount: Some(self.subaccount),
+            address_type: None,
+        }
+    }
+}
+
+/// Fee rate of a parent and child sent together
+///
+/// Returns `None` if either transaction does not report its fee and size.
+#[must_use]
+pub fn package_fee_rate(parent: &Transaction, child: &Transaction) -> Option<FeeRate> {
+    let fee = parent.fee? + child.fee?;
+    let vsize = parent.vsize? + child.vsize?;
+    (vsize > 0).then(|| FeeRate::from_fee_and_vsize(fee, vsize))
+}
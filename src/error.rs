@@ -2,7 +2,14 @@
 //!
 //! This module provides a comprehensive error type that covers various failure modes
 //! including IO errors, JSON parsing errors, CLI execution errors, and timeouts.
+//!
+//! Failed green-cli invocations are classified into an [`ErrorKind`] from their
+//! stderr or JSON error payload, so callers can react to "insufficient funds"
+//! or "login required" without matching on strings. Connection failures are
+//! reported as [`Error::Network`], everything else as [`Error::Cli`].
 
+use serde_json::Value;
+use std::fmt;
 use thiserror::Error;
 
 /// Result type alias for Green API operations
@@ -32,22 +39,35 @@ pub enum Error {
 
     /// CLI error with non-zero exit code and stderr capture
     ///
-    /// This variant should contain the stderr output when a CLI command fails
+    /// Carries the classified [`ErrorKind`], the exit code and the raw output
+    /// of the failed command
     #[error("CLI error: {0}")]
-    Cli(String),
+    Cli(CliError),
 
     /// Timeout error for operations that exceed time limits
     #[error("Operation timed out")]
     Timeout,
 
-    /// Network-related errors
+    /// Network-related errors, such as green-cli failing to reach its backend
     #[error("Network error: {0}")]
-    Network(String),
+    Network(CliError),
 
     /// Invalid response received from the API
     #[error("Invalid response received")]
     InvalidResponse,
 
+    /// A request was rejected locally, before reaching green-cli
+    ///
+    /// Carries the same [`ErrorKind`] green-cli would report for the problem,
+    /// such as `InsufficientFunds` when coin selection cannot fund a send
+    #[error("{message}")]
+    Validation {
+        /// Category of the problem
+        kind: ErrorKind,
+        /// Human-readable description
+        message: String,
+    },
+
     /// Catch-all for unexpected errors
     #[error("Unexpected error: {0}")]
     Unexpected(String),
@@ -63,16 +83,310 @@ impl Error {
     /// let error = Error::cli_error("Command failed: permission denied");
     /// ```
     pub fn cli_error<S: Into<String>>(stderr: S) -> Self {
-        Self::Cli(stderr.into())
+        Self::Cli(CliError::from_output(None, "", &stderr.into()))
     }
 
     /// Create a new network error
     pub fn network<S: Into<String>>(msg: S) -> Self {
-        Self::Network(msg.into())
+        let msg = msg.into();
+        Self::Network(CliError {
+            kind: ErrorKind::Network,
+            exit_code: None,
+            code: None,
+            message: msg.clone(),
+            stderr: msg,
+        })
+    }
+
+    /// Create a new validation error of the given kind
+    ///
+    /// # Example
+    /// ```
+    /// use green_rs::{Error, ErrorKind};
+    ///
+    /// let error = Error::validation(ErrorKind::InvalidAmount, "Amount must be positive");
+    /// assert_eq!(error.kind(), Some(ErrorKind::InvalidAmount));
+    /// ```
+    pub fn validation<S: Into<String>>(kind: ErrorKind, message: S) -> Self {
+        Self::Validation {
+            kind,
+            message: message.into(),
+        }
     }
 
     /// Create a new unexpected error
     pub fn unexpected<S: Into<String>>(msg: S) -> Self {
         Self::Unexpected(msg.into())
     }
+
+    /// Classification of a failed green-cli command or a local validation
+    /// error, if this is one
+    ///
+    /// # Example
+    /// ```
+    /// use green_rs::{Error, ErrorKind};
+    ///
+    /// let error = Error::cli_error("Error: Insufficient funds");
+    /// assert_eq!(error.kind(), Some(ErrorKind::InsufficientFunds));
+    /// ```
+    #[must_use]
+    pub const fn kind(&self) -> Option<ErrorKind> {
+        match self {
+            Self::Cli(err) | Self::Network(err) => Some(err.kind),
+            Self::Validation { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+}
+
+/// Category of a green-cli failure or local validation error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The wallet cannot fund the requested amount and fee
+    InsufficientFunds,
+    /// An address is malformed or belongs to another network
+    InvalidAddress,
+    /// An amount is missing, zero, negative or below the dust limit
+    InvalidAmount,
+    /// The fee rate is below the network minimum or otherwise invalid
+    InvalidFee,
+    /// The command needs a logged-in, unlocked wallet
+    LoginRequired,
+    /// A PIN, mnemonic or password was rejected
+    InvalidCredentials,
+    /// The operation needs two-factor authorization
+    TwoFactorRequired,
+    /// green-cli could not reach its backend
+    Network,
+    /// A referenced transaction, UTXO, subaccount or address does not exist
+    NotFound,
+    /// Any failure that could not be classified
+    Other,
+}
+
+impl ErrorKind {
+    /// Patterns identifying each kind, checked in order against lowercased text
+    const PATTERNS: &'static [(Self, &'static [&'static str])] = &[
+        (
+            Self::Network,
+            &[
+                "id_connection_failed",
+                "id_you_are_not_connected",
+                "network unreachable",
+                "network is unreachable",
+                "connection refused",
+                "connection failed",
+                "connection reset",
+                "not connected",
+                "failed to connect",
+                "unable to connect",
+                "could not resolve",
+                "name resolution",
+            ],
+        ),
+        (
+            Self::TwoFactorRequired,
+            &[
+                "2fa",
+                "two factor",
+                "two-factor",
+                "twofactor",
+                "id_twofactor",
+            ],
+        ),
+        (
+            Self::InsufficientFunds,
+            &[
+                "id_insufficient_funds",
+                "insufficient funds",
+                "insufficient_funds",
+                "not enough funds",
+            ],
+        ),
+        (
+            Self::InvalidAddress,
+            &["id_invalid_address", "invalid address", "invalid_address"],
+        ),
+        (
+            Self::InvalidFee,
+            &[
+                "id_fee_rate_is_below_minimum",
+                "fee rate is below minimum",
+                "invalid fee",
+                "fee too low",
+                "min relay fee not met",
+            ],
+        ),
+        (
+            Self::InvalidAmount,
+            &[
+                "id_invalid_amount",
+                "id_no_amount_specified",
+                "invalid amount",
+                "amount below",
+                "dust",
+            ],
+        ),
+        (
+            Self::InvalidCredentials,
+            &[
+                "id_invalid_pin",
+                "id_login_failed",
+                "invalid pin",
+                "invalid mnemonic",
+                "invalid password",
+                "wrong pin",
+                "login failed",
+            ],
+        ),
+        (
+            Self::LoginRequired,
+            &[
+                "login required",
+                "not logged in",
+                "wallet locked",
+                "wallet is locked",
+                "session not logged in",
+            ],
+        ),
+        (
+            Self::NotFound,
+            &[
+                "not found",
+                "unknown subaccount",
+                "unknown utxo",
+                "unknown transaction",
+                "no such",
+            ],
+        ),
+    ];
+
+    /// Classify free-form green-cli error text
+    ///
+    /// # Example
+    /// ```
+    /// use green_rs::ErrorKind;
+    ///
+    /// assert_eq!(ErrorKind::classify("id_invalid_address"), ErrorKind::InvalidAddress);
+    /// assert_eq!(ErrorKind::classify("something odd"), ErrorKind::Other);
+    /// ```
+    #[must_use]
+    pub fn classify(text: &str) -> Self {
+        let text = text.to_lowercase();
+        Self::PATTERNS
+            .iter()
+            .find(|(_, patterns)| patterns.iter().any(|pattern| text.contains(pattern)))
+            .map_or(Self::Other, |(kind, _)| *kind)
+    }
+}
+
+/// Details of a failed green-cli command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliError {
+    /// Classified category of the failure
+    pub kind: ErrorKind,
+    /// Exit code (`None` if unknown or the process was killed by a signal)
+    pub exit_code: Option<i32>,
+    /// Error code from a JSON error payload (e.g. `id_insufficient_funds`)
+    pub code: Option<String>,
+    /// Human-readable message extracted from the output
+    pub message: String,
+    /// Raw stderr of the command
+    pub stderr: String,
+}
+
+impl CliError {
+    /// Parse and classify the output of a failed command
+    ///
+    /// A JSON error payload on stderr or stdout, such as
+    /// `{"error": "id_insufficient_funds"}` or
+    /// `{"error": {"code": "...", "message": "..."}}`, takes precedence.
+    /// Otherwise the last non-empty line of stderr is used as the message,
+    /// without any leading `Error:`.
+    #[must_use]
+    pub fn from_output(exit_code: Option<i32>, stdout: &str, stderr: &str) -> Self {
+        let payload = [stderr, stdout]
+            .into_iter()
+            .find_map(|text| json_error(text.trim()));
+        let (code, message) = payload.unwrap_or_else(|| (None, text_message(stderr, stdout)));
+        let message = message.unwrap_or_else(|| {
+            exit_code.map_or_else(
+                || "green-cli was terminated".to_string(),
+                |code| format!("green-cli exited with code {code}"),
+            )
+        });
+
+        let kind = match ErrorKind::classify(&format!(
+            "{} {message}",
+            code.as_deref().unwrap_or_default()
+        )) {
+            ErrorKind::Other => ErrorKind::classify(stderr),
+            kind => kind,
+        };
+
+        Self {
+            kind,
+            exit_code,
+            code,
+            message,
+            stderr: stderr.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<CliError> for Error {
+    fn from(err: CliError) -> Self {
+        if err.kind == ErrorKind::Network {
+            Self::Network(err)
+        } else {
+            Self::Cli(err)
+        }
+    }
+}
+
+/// Code and message of a JSON error payload
+fn json_error(text: &str) -> Option<(Option<String>, Option<String>)> {
+    let Value::Object(map) = serde_json::from_str::<Value>(text).ok()? else {
+        return None;
+    };
+    let as_string = |value: Option<&Value>| value.and_then(Value::as_str).map(str::to_string);
+
+    match map.get("error") {
+        Some(Value::String(error)) => Some((
+            Some(error.clone()).filter(|error| error.starts_with("id_")),
+            as_string(map.get("message")).or_else(|| Some(error.clone())),
+        )),
+        Some(Value::Object(error)) => Some((
+            as_string(error.get("code")),
+            as_string(error.get("message")).or_else(|| as_string(error.get("code"))),
+        )),
+        _ if map.contains_key("message") || map.contains_key("code") => Some((
+            as_string(map.get("code")),
+            as_string(map.get("message")).or_else(|| as_string(map.get("code"))),
+        )),
+        _ => None,
+    }
+}
+
+/// Last non-empty line of stderr (or stdout), without a leading `Error:`
+fn text_message(stderr: &str, stdout: &str) -> Option<String> {
+    [stderr, stdout].into_iter().find_map(|text| {
+        text.lines()
+            .rev()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(|line| {
+                line.strip_prefix("Error:")
+                    .or_else(|| line.strip_prefix("error:"))
+                    .or_else(|| line.strip_prefix("ERROR:"))
+                    .map_or(line, str::trim)
+                    .to_string()
+            })
+    })
 }
@@ -0,0 +1,492 @@
+//! Transaction size and fee estimation
+//!
+//! [`TxEstimator`] answers "what would this cost?" for a
+//! [`CreateTransactionRequest`] or a [`TxBuilder`] before anything is created,
+//! signed or reserved. It sizes the transaction from the script type of the
+//! spending subaccount and the recipients, adds the confidential output
+//! overhead on Liquid, and prices the result at one or several fee rates.
+//!
+//! The estimate is computed locally, so it can differ from the size green-cli
+//! reports once the transaction is created; [`TxEstimate::reconcile`] compares
+//! the two against [`CreateTransactionResult::estimated_vsize`].
+//!
+//! # Example
+//!
+//! ```
+//! use green_rs::estimation::{ScriptType, TxEstimator};
+//! use green_rs::types::transaction::{Addressee, CreateTransactionRequest};
+//! use green_rs::types::FeeRate;
+//! use green_rs::Network;
+//!
+//! let request = CreateTransactionRequest {
+//!     addressees: Some(vec![Addressee {
+//!         address: "bcrt1qvewsdxxmeraett7ztsaym88jsrv85kzmev28cl".to_string(),
+//!         satoshi: 50_000,
+//!         asset_id: None,
+//!     }]),
+//!     ..Default::default()
+//! };
+//!
+//! let estimate = TxEstimator::new(Network::Mainnet)
+//!     .script_type(ScriptType::P2wpkh)
+//!     .recipient_script_type(ScriptType::P2wpkh)
+//!     .fee_rates([FeeRate::from_sat_per_vb(2), FeeRate::from_sat_per_vb(20)])
+//!     .estimate(&request)
+//!     .unwrap();
+//!
+//! // One input, the payment and change
+//! assert_eq!(estimate.vsize, 11 + 68 + 2 * 31);
+//! assert_eq!(estimate.fee_at(FeeRate::from_sat_per_vb(20)), 20 * 141);
+//! ```
+
+use crate::api::transaction::TxBuilder;
+use crate::config::Network;
+use crate::error::{Error, ErrorKind, Result};
+use crate::transport::CliTransport;
+use crate::types::address::AddressType;
+use crate::types::common::{AssetId, Satoshis};
+use crate::types::fee::FeeRate;
+use crate::types::subaccount::{Subaccount, SubaccountType};
+use crate::types::transaction::{CreateTransactionRequest, CreateTransactionResult};
+use crate::types::utxo::UnspentOutput;
+use std::collections::BTreeSet;
+
+/// Version, input and output counts and locktime, plus the segwit marker
+const BITCOIN_OVERHEAD_WEIGHT: u32 = 42;
+
+/// Version, the Elements flag byte, input and output counts and locktime
+const LIQUID_OVERHEAD_WEIGHT: u32 = 44;
+
+/// Empty issuance proofs and peg-in witness of a Liquid input
+const LIQUID_INPUT_WITNESS_WEIGHT: u32 = 3;
+
+/// Explicit asset, value and empty nonce of a Liquid output
+const EXPLICIT_COMMITMENTS_SIZE: u32 = 33 + 9 + 1;
+
+/// Asset, value and nonce commitments of a confidential output
+const CONFIDENTIAL_COMMITMENTS_SIZE: u32 = 33 + 33 + 33;
+
+/// Empty surjection and range proofs of an explicit Liquid output
+const EMPTY_PROOFS_WEIGHT: u32 = 2;
+
+/// Range proof of a confidential output, with its length prefix
+const RANGEPROOF_SIZE: u32 = 3 + 4174;
+
+/// Explicit fee output of a Liquid transaction, with an empty script
+const LIQUID_FEE_OUTPUT_WEIGHT: u32 = (EXPLICIT_COMMITMENTS_SIZE + 1) * 4 + EMPTY_PROOFS_WEIGHT;
+
+/// Script an input spends or an output pays to
+///
+/// Decides the size of inputs and outputs. Green multisig subaccounts are
+/// sized as native segwit scripts with two 72-byte signatures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScriptType {
+    /// Green 2of2 with a CSV recovery path (address type `csv`)
+    Csv,
+    /// Green 2of2 multisig (address type `p2wsh`)
+    P2wsh2of2,
+    /// 2of3 multisig with a recovery key (address type `p2wsh`)
+    P2wsh2of3,
+    /// Legacy 2of2 multisig (address type `p2sh`)
+    P2sh2of2,
+    /// Legacy 2of3 multisig (address type `p2sh`)
+    P2sh2of3,
+    /// Native segwit single-sig
+    P2wpkh,
+    /// Wrapped segwit single-sig
+    P2shP2wpkh,
+    /// Legacy single-sig
+    P2pkh,
+    /// Taproot key path
+    P2tr,
+}
+
+impl ScriptType {
+    /// Script type of a green-cli address type
+    ///
+    /// `p2wsh` and `p2sh` are used by both 2of2 and 2of3 subaccounts, so
+    /// `subaccount_type` picks between them; they are sized as 2of3 when it
+    /// is unknown. Without an address type the subaccount type's
+    /// [default addresses](SubaccountType::default_address_type) are
+    /// assumed, and anything unrecognized is sized as the conservative
+    /// [`ScriptType::P2wsh2of3`].
+    #[must_use]
+    pub fn from_types(
+        subaccount_type: Option<&SubaccountType>,
+        address_type: Option<&AddressType>,
+    ) -> Self {
+        let two_of_two = subaccount_type.is_some_and(SubaccountType::is_two_of_two);
+        let default = subaccount_type.and_then(SubaccountType::default_address_type);
+        match address_type.or(default.as_ref()) {
+            Some(AddressType::Csv) => Self::Csv,
+            Some(AddressType::P2wsh) if two_of_two => Self::P2wsh2of2,
+            Some(AddressType::P2sh) if two_of_two => Self::P2sh2of2,
+            Some(AddressType::P2sh) => Self::P2sh2of3,
+            Some(AddressType::P2wpkh) => Self::P2wpkh,
+            Some(AddressType::P2shP2wpkh) => Self::P2shP2wpkh,
+            Some(AddressType::P2pkh) => Self::P2pkh,
+            Some(AddressType::P2tr) => Self::P2tr,
+            Some(_) if two_of_two => Self::Csv,
+            _ => Self::P2wsh2of3,
+        }
+    }
+
+    /// Script type spent by `utxo`, from its address type
+    #[must_use]
+    pub fn for_utxo(utxo: &UnspentOutput) -> Self {
+        Self::from_types(None, utxo.address_type.as_ref())
+    }
+
+    /// Script type of the addresses of `subaccount`
+    #[must_use]
+    pub fn for_subaccount(subaccount: &Subaccount) -> Self {
+        Self::from_types(Some(&subaccount.subaccount_type), None)
+    }
+
+    /// Weight of a Bitcoin input spending this script
+    #[must_use]
+    pub const fn input_weight(self) -> u32 {
+        match self {
+            Self::P2tr => 230,
+            Self::P2wpkh => 272,
+            Self::P2shP2wpkh => 364,
+            Self::P2wsh2of2 => 384,
+            Self::Csv => 388,
+            Self::P2wsh2of3 => 418,
+            Self::P2pkh => 592,
+            Self::P2sh2of2 => 1040,
+            Self::P2sh2of3 => 1188,
+        }
+    }
+
+    /// Virtual size of a Bitcoin input spending this script, rounded up
+    #[must_use]
+    pub const fn input_vsize(self) -> u32 {
+        self.input_weight().div_ceil(4)
+    }
+
+    /// Length of the script pubkey paying to this script
+    #[must_use]
+    pub const fn script_pubkey_len(self) -> u32 {
+        match self {
+            Self::P2wpkh => 22,
+            Self::P2shP2wpkh | Self::P2sh2of2 | Self::P2sh2of3 => 23,
+            Self::P2pkh => 25,
+            Self::Csv | Self::P2wsh2of2 | Self::P2wsh2of3 | Self::P2tr => 34,
+        }
+    }
+
+    /// Weight of a Bitcoin output paying to this script
+    #[must_use]
+    pub const fn output_weight(self) -> u32 {
+        (8 + 1 + self.script_pubkey_len()) * 4
+    }
+}
+
+/// Estimates the size and fee of a transaction before it is created
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct TxEstimator {
+    network: Network,
+    script_type: ScriptType,
+    recipient_script_type: ScriptType,
+    input_count: u32,
+    confidential: bool,
+    fee_rates: Vec<FeeRate>,
+}
+
+impl TxEstimator {
+    /// Create an estimator for a 2of2 CSV subaccount on `network`
+    ///
+    /// Recipients are sized as 34-byte scripts (`p2wsh` or `p2tr`), the
+    /// largest common output, and are confidential on Liquid.
+    pub const fn new(network: Network) -> Self {
+        Self {
+            network,
+            script_type: ScriptType::Csv,
+            recipient_script_type: ScriptType::P2tr,
+            input_count: 1,
+            confidential: network.is_liquid(),
+            fee_rates: Vec::new(),
+        }
+    }
+
+    /// Set the script type of the spending subaccount
+    ///
+    /// Sizes the inputs and the change outputs.
+    pub const fn script_type(mut self, script_type: ScriptType) -> Self {
+        self.script_type = script_type;
+        self
+    }
+
+    /// Use the script type of `subaccount`
+    pub fn subaccount(self, subaccount: &Subaccount) -> Self {
+        self.script_type(ScriptType::for_subaccount(subaccount))
+    }
+
+    /// Set the script type recipients are sized as
+    pub const fn recipient_script_type(mut self, script_type: ScriptType) -> Self {
+        self.recipient_script_type = script_type;
+        self
+    }
+
+    /// Set the number of inputs assumed when the request lists none
+    ///
+    /// Defaults to one input per asset spent.
+    pub const fn input_count(mut self, input_count: u32) -> Self {
+        self.input_count = input_count;
+        self
+    }
+
+    /// Set whether recipients on Liquid are confidential
+    ///
+    /// Change is always confidential and the fee output never is. Ignored
+    /// on Bitcoin.
+    pub const fn confidential(mut self, confidential: bool) -> Self {
+        self.confidential = confidential;
+        self
+    }
+
+    /// Also estimate the fee at `fee_rate`
+    pub fn fee_rate(mut self, fee_rate: FeeRate) -> Self {
+        self.fee_rates.push(fee_rate);
+        self
+    }
+
+    /// Estimate the fee at each of `fee_rates`, replacing any set before
+    ///
+    /// Without fee rates the request's own rate is used, and the network's
+    /// minimum relay fee if it has none.
+    pub fn fee_rates(mut self, fee_rates: impl IntoIterator<Item = FeeRate>) -> Self {
+        self.fee_rates = fee_rates.into_iter().collect();
+        self
+    }
+
+    /// Estimate the transaction `request` would create
+    ///
+    /// Inputs are the request's `utxos` if it lists any, otherwise the
+    /// configured input count. Unless `send_all` is set, a change output is
+    /// assumed for every asset paid, and on Liquid for the fee asset too.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Validation`] of kind `InvalidAmount` if the
+    /// request has no recipients
+    pub fn estimate(&self, request: &CreateTransactionRequest) -> Result<TxEstimate> {
+        let addressees = request
+            .addressees
+            .as_deref()
+            .filter(|addressees| !addressees.is_empty())
+            .ok_or_else(|| {
+                Error::validation(ErrorKind::InvalidAmount, "No recipients to estimate")
+            })?;
+        let liquid = self.network.is_liquid();
+
+        let mut assets: BTreeSet<Option<&AssetId>> = addressees
+            .iter()
+            .map(|addressee| addressee.asset_id.as_ref())
+            .collect();
+        if liquid {
+            // The fee is paid in the policy asset, which may not be sent
+            assets.insert(None);
+        }
+        let asset_count = u32::try_from(assets.len()).unwrap_or(u32::MAX);
+        let input_count = match request.utxos.as_deref() {
+            Some(utxos) if !utxos.is_empty() => u32::try_from(utxos.len()).unwrap_or(u32::MAX),
+            _ => self.input_count.max(asset_count),
+        };
+        let recipient_count = u32::try_from(addressees.len()).unwrap_or(u32::MAX);
+        let change_count = if request.send_all { 0 } else { asset_count };
+
+        let weight = self.overhead_weight()
+            + input_count * self.input_weight(self.script_type)
+            + recipient_count * self.recipient_weight(input_count)
+            + change_count * self.change_weight(input_count);
+        let vsize = weight.div_ceil(4);
+
+        let fee_rates = if !self.fee_rates.is_empty() {
+            self.fee_rates.clone()
+        } else if let Some(fee_rate) = request.fee_rate {
+            vec![fee_rate]
+        } else {
+            vec![FeeRate::min_relay(self.network)]
+        };
+
+        Ok(TxEstimate {
+            weight,
+            vsize,
+            input_count,
+            output_count: recipient_count + change_count + u32::from(liquid),
+            fees: fee_rates
+                .into_iter()
+                .map(|fee_rate| EstimatedFee {
+                    fee_rate,
+                    fee: fee_rate.fee_for_vsize(vsize),
+                })
+                .collect(),
+        })
+    }
+
+    /// Network transactions are sized for
+    #[must_use]
+    pub const fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Weight of the parts of every transaction: version, counts and
+    /// locktime, plus the explicit fee output on Liquid
+    #[must_use]
+    pub const fn overhead_weight(&self) -> u32 {
+        if self.network.is_liquid() {
+            LIQUID_OVERHEAD_WEIGHT + LIQUID_FEE_OUTPUT_WEIGHT
+        } else {
+            BITCOIN_OVERHEAD_WEIGHT
+        }
+    }
+
+    /// Weight of an input spending `script_type`
+    #[must_use]
+    pub const fn input_weight(&self, script_type: ScriptType) -> u32 {
+        if self.network.is_liquid() {
+            script_type.input_weight() + LIQUID_INPUT_WITNESS_WEIGHT
+        } else {
+            script_type.input_weight()
+        }
+    }
+
+    /// Weight of an input later spending a change output
+    #[must_use]
+    pub const fn change_spend_weight(&self) -> u32 {
+        self.input_weight(self.script_type)
+    }
+
+    /// Weight of a recipient output in a transaction with `input_count` inputs
+    ///
+    /// Liquid surjection proofs grow with the number of inputs.
+    #[must_use]
+    pub const fn recipient_weight(&self, input_count: u32) -> u32 {
+        self.output_weight(self.recipient_script_type, self.confidential, input_count)
+    }
+
+    /// Weight of a change output in a transaction with `input_count` inputs
+    ///
+    /// Change is confidential on Liquid.
+    #[must_use]
+    pub const fn change_weight(&self, input_count: u32) -> u32 {
+        self.output_weight(self.script_type, true, input_count)
+    }
+
+    const fn output_weight(&self, script_type: ScriptType, confidential: bool, inputs: u32) -> u32 {
+        if self.network.is_liquid() {
+            liquid_output_weight(script_type, confidential, inputs)
+        } else {
+            script_type.output_weight()
+        }
+    }
+
+    /// Estimate the transaction `builder` would create
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Validation`] if the builder has no outputs or an
+    /// input is not of the form `txid:vout`
+    pub fn estimate_builder<T: CliTransport>(&self, builder: &TxBuilder<T>) -> Result<TxEstimate> {
+        self.estimate(&builder.to_request()?)
+    }
+}
+
+/// Weight of a Liquid output paying to `script_type` in a transaction with
+/// `inputs` inputs
+const fn liquid_output_weight(script_type: ScriptType, confidential: bool, inputs: u32) -> u32 {
+    let script = 1 + script_type.script_pubkey_len();
+    if confidential {
+        (CONFIDENTIAL_COMMITMENTS_SIZE + script) * 4
+            + surjection_proof_size(inputs)
+            + RANGEPROOF_SIZE
+    } else {
+        (EXPLICIT_COMMITMENTS_SIZE + script) * 4 + EMPTY_PROOFS_WEIGHT
+    }
+}
+
+/// Size of a surjection proof over `inputs` inputs, with its length prefix
+///
+/// Elements proves against at most three of the inputs.
+const fn surjection_proof_size(inputs: u32) -> u32 {
+    let used = if inputs < 3 { inputs } else { 3 };
+    1 + 2 + inputs.div_ceil(8) + 32 * (1 + used)
+}
+
+/// Estimated size of a transaction and its fee at each requested rate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxEstimate {
+    /// Estimated weight in weight units
+    pub weight: u32,
+    /// Estimated virtual size
+    pub vsize: u32,
+    /// Number of inputs assumed
+    pub input_count: u32,
+    /// Number of outputs assumed, including change and any fee output
+    pub output_count: u32,
+    /// Fee at each requested rate, in the order requested
+    pub fees: Vec<EstimatedFee>,
+}
+
+/// Fee of an estimated transaction at one fee rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EstimatedFee {
+    /// Fee rate the fee was computed at
+    pub fee_rate: FeeRate,
+    /// Fee paid at that rate
+    pub fee: Satoshis,
+}
+
+impl TxEstimate {
+    /// Fee of the estimated transaction at `fee_rate`
+    #[must_use]
+    pub fn fee_at(&self, fee_rate: FeeRate) -> Satoshis {
+        fee_rate.fee_for_vsize(self.vsize)
+    }
+
+    /// Compare the estimate with the size green-cli reports for the request
+    #[must_use]
+    pub const fn reconcile(&self, result: &CreateTransactionResult) -> SizeReconciliation {
+        SizeReconciliation {
+            estimated_vsize: self.vsize,
+            reported_vsize: result.estimated_vsize,
+            reported_fee: result.estimated_fee,
+        }
+    }
+}
+
+/// Local size estimate next to green-cli's
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeReconciliation {
+    /// Virtual size estimated locally
+    pub estimated_vsize: u32,
+    /// Virtual size green-cli estimated when creating the transaction
+    pub reported_vsize: u32,
+    /// Fee green-cli estimated
+    pub reported_fee: Satoshis,
+}
+
+impl SizeReconciliation {
+    /// Reported minus estimated virtual size
+    ///
+    /// Positive when the local estimate is too small.
+    #[must_use]
+    pub fn difference(&self) -> i64 {
+        i64::from(self.reported_vsize) - i64::from(self.estimated_vsize)
+    }
+
+    /// Whether the estimates are at most `tolerance` vbytes apart
+    #[must_use]
+    pub const fn is_within(&self, tolerance: u32) -> bool {
+        self.estimated_vsize.abs_diff(self.reported_vsize) <= tolerance
+    }
+
+    /// Fee rate green-cli's fee and size correspond to
+    #[must_use]
+    pub fn reported_fee_rate(&self) -> FeeRate {
+        FeeRate::from_fee_and_vsize(self.reported_fee, self.reported_vsize)
+    }
+}
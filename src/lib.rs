@@ -16,6 +16,48 @@
 //! - Blinding factors are needed to reveal transaction details
 //! - Fee calculations differ from Bitcoin mainnet
 //!
+//! # Configuration
+//!
+//! Clients spawn `green-cli` from `PATH` by default. Use [`ClientConfig`] to
+//! select the executable, [`Network`], data directory, environment and extra
+//! global arguments for each client:
+//!
+//! ```no_run
+//! use green_rs::{ClientConfig, GreenClient, Network};
+//!
+//! let testnet = GreenClient::with_config(
+//!     ClientConfig::builder()
+//!         .network(Network::Testnet)
+//!         .config_dir("/var/lib/wallets/testnet")
+//!         .build(),
+//! );
+//! ```
+//!
+//! # Testing Without green-cli
+//!
+//! Both clients are generic over a [`transport`] that executes commands. The
+//! [`ScriptedTransport`](transport::ScriptedTransport) answers commands from
+//! canned outputs, so code built on green-rs can be unit-tested in memory:
+//!
+//! ```
+//! use green_rs::api::WalletExt;
+//! use green_rs::transport::{CliOutput, ScriptedTransport};
+//! use green_rs::{ClientConfig, GreenClient};
+//!
+//! let client = GreenClient::with_transport(
+//!     ClientConfig::default(),
+//!     ScriptedTransport::new().on(
+//!         ["get", "balance", "--json"],
+//!         CliOutput::failure(1, "Error: Wallet locked"),
+//!     ),
+//! );
+//! assert!(client.get_balance().is_err());
+//! ```
+//!
+//! With the `testkit` feature, [`FakeGreen`](testkit::FakeGreen) goes further
+//! and answers every command from a stateful in-memory wallet, so whole
+//! create → sign → send flows can be exercised end to end.
+//!
 //! # Examples
 //!
 //! ## Synchronous Client
@@ -42,8 +84,11 @@
 // Re-export main client types
 pub use client::{AsyncGreenClient, GreenClient};
 
+// Re-export client configuration
+pub use config::{ClientConfig, Network};
+
 // Re-export error types
-pub use error::{Error, Result};
+pub use error::{CliError, Error, ErrorKind, Result};
 
 // Re-export types module
 pub mod types;
@@ -52,7 +97,18 @@ pub mod types;
 pub mod api;
 
 // Public modules
+pub mod address;
 pub mod client;
+pub mod coin_selection;
+pub mod config;
+pub mod consolidation;
+pub mod cpfp;
+pub mod estimation;
+pub mod transport;
+
+// Test support, enabled with the `testkit` feature
+#[cfg(feature = "testkit")]
+pub mod testkit;
 
 // Internal modules
 mod error;
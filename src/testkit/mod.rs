@@ -0,0 +1,623 @@
+//! In-process fake Green wallet for integration testing
+//!
+//! [`FakeGreen`] is a [transport](crate::transport) that answers green-cli
+//! commands from a stateful, in-memory wallet instead of canned outputs. It
+//! keeps subaccounts, addresses with pointers, unspent outputs, transactions
+//! and block confirmations, so a create → sign → send flow through either
+//! client updates balances, UTXOs and history exactly as a real wallet would.
+//!
+//! This module is only available with the `testkit` feature:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! green-rs = { version = "0.1", features = ["testkit"] }
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use green_rs::api::{TxBuilder, WalletExt};
+//! use green_rs::testkit::FakeGreen;
+//! use green_rs::types::FeeRate;
+//!
+//! let wallet = FakeGreen::new();
+//! wallet.fund(0, 100_000);
+//! wallet.mine(1);
+//!
+//! let txid = TxBuilder::with_client(wallet.client())
+//!     .add_output("bcrt1qvewsdxxmeraett7ztsaym88jsrv85kzmev28cl".to_string(), 40_000)
+//!     .set_fee_rate(FeeRate::from_sat_per_vb(2))
+//!     .dump()
+//!     .and_then(TxBuilder::sign)
+//!     .and_then(TxBuilder::send)
+//!     .unwrap();
+//!
+//! let balance = wallet.client().get_balance().unwrap();
+//! assert!(balance.get("btc").unwrap() < 60_000);
+//! assert_eq!(wallet.transaction(&txid).unwrap().confirmations, 0);
+//! ```
+//!
+//! # Commands
+//!
+//! The fake understands every command issued by the clients and
+//! [`TxBuilder`](crate::api::TxBuilder). Anything else fails with
+//! `Error: Unknown command`, like an unsupported green-cli subcommand.
+//!
+//! Wallet commands need a logged-in session, and signing, sending and
+//! creating subaccounts need a full (not watch-only) one. [`FakeGreen::new`]
+//! starts logged in to a wallet; [`FakeGreen::without_wallet`] starts with
+//! none, for exercising the session lifecycle.
+//!
+//! Fees are computed from a simple segwit size estimate at the requested fee
+//! rate in sat/vB, falling back to the 6-block estimate. Change below the dust
+//! limit is added to the fee.
+
+mod session;
+mod state;
+
+use crate::client::{AsyncGreenClient, GreenClient};
+use crate::config::{ClientConfig, Network};
+use crate::error::Result;
+use crate::transport::{AsyncCliTransport, CliCommand, CliOutput, CliTransport};
+use crate::types::address::{GetPreviousAddressesRequest, GetReceiveAddressRequest};
+use crate::types::common::{AssetId, BlockHeight, Satoshis, TxId};
+use crate::types::fee::FeeRate;
+use crate::types::subaccount::{
+    CreateSubaccountParams, Subaccount, SubaccountList, UpdateSubaccountParams,
+};
+use crate::types::transaction::{
+    CreateTransactionRequest, CreateTransactionResult, GetTransactionsParams, InputToSign,
+    Transaction, TransactionList, TxOutput,
+};
+use crate::types::utxo::{GetUnspentOutputsParams, UnspentOutput};
+use crate::types::wallet::{FeeEstimates, SetPinRequest, WatchOnlyData};
+use crate::types::Balance;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use session::Session;
+use state::{Recipient, SpendRequest, StateResult, WalletState};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Number of transactions returned by `get transactions` when no count is given
+const DEFAULT_PAGE_COUNT: u32 = 30;
+
+/// Stateful in-memory Green wallet
+///
+/// Clones share the same wallet, so a test can keep a handle for funding,
+/// mining and inspection while clients built from it issue commands.
+#[derive(Debug, Clone)]
+pub struct FakeGreen {
+    state: Arc<Mutex<WalletState>>,
+    calls: Arc<Mutex<Vec<CliCommand>>>,
+}
+
+impl Default for FakeGreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeGreen {
+    /// Create an empty wallet on the local regtest network
+    ///
+    /// The wallet starts at block height 100 with a single "Main Account"
+    /// 2of2 subaccount and no funds.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_network(Network::ElectrumLocaltest)
+    }
+
+    /// Create an empty wallet on `network`
+    #[must_use]
+    pub fn with_network(network: Network) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(WalletState::new(network))),
+            calls: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Create a fake green-cli on the local regtest network with no wallet
+    ///
+    /// Every wallet command fails until one is created with
+    /// [`WalletApi::create_wallet`](crate::api::WalletApi::create_wallet).
+    #[must_use]
+    pub fn without_wallet() -> Self {
+        let wallet = Self::new();
+        wallet.lock().session = Session::default();
+        wallet
+    }
+
+    /// Mnemonic of the wallet, if one exists
+    ///
+    /// Wallets from [`FakeGreen::new`] are created from the
+    /// `abandon ... about` test mnemonic and start logged in.
+    #[must_use]
+    pub fn mnemonic(&self) -> Option<String> {
+        self.lock()
+            .session
+            .wallet
+            .as_ref()
+            .map(|wallet| wallet.mnemonic.clone())
+    }
+
+    /// Watch-only credentials accepted by the wallet, if one exists
+    #[must_use]
+    pub fn watch_only_data(&self) -> Option<WatchOnlyData> {
+        self.lock()
+            .session
+            .wallet
+            .as_ref()
+            .map(|wallet| WatchOnlyData {
+                xpub: wallet.xpub(),
+                core_descriptors: None,
+            })
+    }
+
+    /// Network this wallet operates on
+    #[must_use]
+    pub fn network(&self) -> Network {
+        self.lock().network
+    }
+
+    /// A synchronous client backed by this wallet
+    #[must_use]
+    pub fn client(&self) -> GreenClient<Self> {
+        GreenClient::with_transport(self.config(), self.clone())
+    }
+
+    /// An asynchronous client backed by this wallet
+    #[must_use]
+    pub fn async_client(&self) -> AsyncGreenClient<Self> {
+        AsyncGreenClient::with_transport(self.config(), self.clone())
+    }
+
+    fn config(&self) -> ClientConfig {
+        ClientConfig::builder().network(self.network()).build()
+    }
+
+    /// Receive `satoshi` of the network's native asset into `subaccount`
+    ///
+    /// Creates an unconfirmed incoming transaction paying a fresh receive
+    /// address and returns its ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `subaccount` does not exist.
+    #[allow(clippy::must_use_candidate)] // Funding is the point, the txid is optional
+    pub fn fund(&self, subaccount: u32, satoshi: Satoshis) -> TxId {
+        self.fund_asset(subaccount, satoshi, None)
+    }
+
+    /// Receive `satoshi` of `asset_id` into `subaccount`
+    ///
+    /// `None` funds the network's native asset, like [`FakeGreen::fund`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `subaccount` does not exist.
+    #[allow(clippy::must_use_candidate)] // Funding is the point, the txid is optional
+    pub fn fund_asset(
+        &self,
+        subaccount: u32,
+        satoshi: Satoshis,
+        asset_id: Option<AssetId>,
+    ) -> TxId {
+        self.lock()
+            .fund(subaccount, satoshi, asset_id)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Mine `blocks` blocks, confirming every pending transaction in the first
+    pub fn mine(&self, blocks: u32) {
+        self.lock().mine(blocks);
+    }
+
+    /// Current block height
+    #[must_use]
+    pub fn block_height(&self) -> BlockHeight {
+        self.lock().block_height
+    }
+
+    /// Replace the fee estimates, by confirmation target in blocks
+    pub fn set_fee_estimates(&self, fees: HashMap<u32, FeeRate>) {
+        self.lock().fee_estimates = fees;
+    }
+
+    /// Balance of all subaccounts, or of a single one
+    #[must_use]
+    pub fn balance(&self, subaccount: Option<u32>) -> Balance {
+        self.lock().balance(subaccount)
+    }
+
+    /// All unspent outputs of the wallet
+    #[must_use]
+    pub fn utxos(&self) -> Vec<UnspentOutput> {
+        self.lock().unspent_outputs()
+    }
+
+    /// All wallet transactions, oldest first
+    #[must_use]
+    pub fn transactions(&self) -> Vec<Transaction> {
+        self.lock().transaction_history()
+    }
+
+    /// A wallet transaction by ID
+    #[must_use]
+    pub fn transaction(&self, txid: &str) -> Option<Transaction> {
+        self.transactions().into_iter().find(|tx| tx.txid == txid)
+    }
+
+    /// All subaccounts of the wallet
+    #[must_use]
+    pub fn subaccounts(&self) -> Vec<Subaccount> {
+        self.lock()
+            .subaccounts
+            .iter()
+            .map(|sub| sub.info.clone())
+            .collect()
+    }
+
+    /// Commands executed so far, in order
+    #[must_use]
+    pub fn calls(&self) -> Vec<CliCommand> {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, WalletState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Answer a single command
+    fn respond(&self, command: &CliCommand) -> CliOutput {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(command.clone());
+
+        // Parameters arrive out of band; read them like `--params` arguments
+        let mut args = command.args.clone();
+        if let Some(ref params) = command.params {
+            args.extend(["--params".to_string(), params.json().to_string()]);
+        }
+        let result = dispatch(&mut self.lock(), &args);
+        match result {
+            Ok(stdout) => CliOutput::success(stdout),
+            Err(stderr) => CliOutput::failure(1, stderr),
+        }
+    }
+}
+
+/// Route a command to the wallet
+fn dispatch(state: &mut WalletState, args: &[String]) -> StateResult<String> {
+    let words: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .take_while(|arg| !arg.starts_with("--"))
+        .collect();
+
+    match words.as_slice() {
+        ["create" | "get", "wallet"] | ["login" | "logout"] | ["set", "pin"] => {
+            return session_command(state, &words, args);
+        }
+        ["send", "transaction"] | ["tx", "sign" | "send"] | ["create", "subaccount"] => {
+            state.session.require_full()?;
+        }
+        _ => state.session.require_login()?,
+    }
+
+    match words.as_slice() {
+        [_, "transaction" | "transactions"] | ["tx", _] => transaction_command(state, &words, args),
+        _ => wallet_command(state, &words, args),
+    }
+}
+
+/// Wallet creation, login, logout and PIN commands
+fn session_command(
+    state: &mut WalletState,
+    words: &[&str],
+    args: &[String],
+) -> StateResult<String> {
+    let session = &mut state.session;
+    match words {
+        ["create", "wallet"] => session.create(params(args)?)?,
+        ["login"] => session.login(&params(args)?)?,
+        ["set", "pin"] => {
+            let request: SetPinRequest = params(args)?;
+            session.set_pin(request.pin)?;
+            return to_json(&json!({}));
+        }
+        ["logout"] => {
+            session.logout();
+            return to_json(&json!({}));
+        }
+        _ => {}
+    }
+    to_json(&session.info(state.network)?)
+}
+
+/// Balance, fee, UTXO, address and subaccount commands
+fn wallet_command(state: &mut WalletState, words: &[&str], args: &[String]) -> StateResult<String> {
+    match words {
+        ["get", "balance"] => to_json(&state.balance(None)),
+        ["get", "fee-estimates"] => to_json(&FeeEstimates {
+            fees: state.fee_estimates.clone(),
+        }),
+        ["get", "utxos"] => {
+            let params: GetUnspentOutputsParams = params(args)?;
+            to_json(&filter_utxos(state.unspent_outputs(), &params))
+        }
+        ["update", "utxo"] => to_json(&state.update_utxo(params(args)?)?),
+        ["get", "address"] => {
+            let request: GetReceiveAddressRequest = params(args)?;
+            to_json(&state.current_address(request.subaccount.unwrap_or(0))?)
+        }
+        ["get", "new-address"] => {
+            let request: GetReceiveAddressRequest = params(args)?;
+            to_json(&state.new_address(request.subaccount.unwrap_or(0), 0)?)
+        }
+        ["update", "address"] => to_json(&state.update_address(params(args)?)?),
+        ["get", "previous-addresses"] => {
+            let request: GetPreviousAddressesRequest = params(args)?;
+            to_json(&state.previous_addresses(
+                request.subaccount.unwrap_or(0),
+                request.last_pointer,
+                request.unused_only.unwrap_or(false),
+            )?)
+        }
+        ["get", "subaccounts"] => to_json(&SubaccountList {
+            subaccounts: state.subaccounts.iter().map(|s| s.info.clone()).collect(),
+        }),
+        ["get", "subaccount"] => {
+            let pointer = subaccount_flag(args)?;
+            to_json(&state.subaccount(pointer)?.info)
+        }
+        ["create", "subaccount"] => {
+            let params: CreateSubaccountParams = params(args)?;
+            to_json(&state.create_subaccount(params.name, params.subaccount_type))
+        }
+        ["update", "subaccount"] => {
+            let pointer = subaccount_flag(args)?;
+            let params: UpdateSubaccountParams = params(args)?;
+            let sub = state.subaccount_mut(pointer)?;
+            if let Some(name) = params.name {
+                sub.info.name = name;
+            }
+            if let Some(hidden) = params.hidden {
+                sub.info.hidden = hidden;
+            }
+            to_json(&sub.info)
+        }
+        _ => Err(unknown_command(words)),
+    }
+}
+
+/// Transaction history, creation, signing and broadcast commands
+fn transaction_command(
+    state: &mut WalletState,
+    words: &[&str],
+    args: &[String],
+) -> StateResult<String> {
+    match words {
+        ["get", "transactions"] => {
+            let params: GetTransactionsParams = params(args)?;
+            to_json(&transaction_page(state.transaction_history(), &params))
+        }
+        ["get", "transaction"] => {
+            let txid = flag(args, "--txid")?;
+            let tx = state
+                .transaction_history()
+                .into_iter()
+                .find(|tx| tx.txid == txid)
+                .ok_or_else(|| format!("Error: Transaction {txid} not found"))?;
+            to_json(&tx)
+        }
+        ["create", "transaction"] => {
+            let request: CreateTransactionRequest = params(args)?;
+            let plan = state.plan_spend(&spend_request(request))?;
+            let txid = format!("{:064x}", 0);
+            let tx = state.preview(&plan, txid);
+            to_json(&CreateTransactionResult {
+                unsigned_hex: hex::encode(to_json(&tx)?),
+                inputs_to_sign: (0..tx.inputs.len())
+                    .map(|index| InputToSign {
+                        index: u32::try_from(index).unwrap_or(u32::MAX),
+                        required_signatures: 1,
+                        pubkeys: Vec::new(),
+                        paths: Vec::new(),
+                        script: String::new(),
+                        sighash: 1,
+                    })
+                    .collect(),
+                estimated_vsize: plan.vsize,
+                estimated_fee: plan.fee,
+                transaction: tx,
+            })
+        }
+        ["send", "transaction"] => {
+            let mut request: CreateTransactionRequest = params(args)?;
+            if let Some(previous) = request.previous_transaction.take() {
+                return to_json(&state.replace(&previous.txid, &spend_request(request))?);
+            }
+            let plan = state.plan_spend(&spend_request(request))?;
+            to_json(&state.apply(plan)?)
+        }
+        ["tx", "sign"] => {
+            let mut payload = read_payload(args)?;
+            state.plan_spend(&builder_request(&payload)?)?;
+            payload["is_signed"] = Value::Bool(true);
+            to_json(&payload)
+        }
+        ["tx", "send"] => {
+            let payload = read_payload(args)?;
+            if payload.get("is_signed").and_then(Value::as_bool) != Some(true) {
+                return Err("Error: Transaction is not signed".to_string());
+            }
+            let plan = state.plan_spend(&builder_request(&payload)?)?;
+            let tx = state.apply(plan)?;
+            to_json(&json!({ "txhash": tx.txid }))
+        }
+        _ => Err(unknown_command(words)),
+    }
+}
+
+fn unknown_command(words: &[&str]) -> String {
+    format!("Error: Unknown command `{}`", words.join(" "))
+}
+
+impl CliTransport for FakeGreen {
+    fn execute(&self, command: &CliCommand) -> Result<CliOutput> {
+        Ok(self.respond(command))
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncCliTransport for FakeGreen {
+    async fn execute(&self, command: &CliCommand) -> Result<CliOutput> {
+        Ok(self.respond(command))
+    }
+}
+
+fn to_json<V: Serialize>(value: &V) -> StateResult<String> {
+    serde_json::to_string(value).map_err(|err| format!("Error: {err}"))
+}
+
+/// Value following `name` in the command arguments
+fn flag<'a>(args: &'a [String], name: &str) -> StateResult<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|index| args.get(index + 1))
+        .map(String::as_str)
+        .ok_or_else(|| format!("Error: Missing {name}"))
+}
+
+/// Parsed `--params` JSON
+fn params<P: DeserializeOwned>(args: &[String]) -> StateResult<P> {
+    serde_json::from_str(flag(args, "--params")?)
+        .map_err(|err| format!("Error: Invalid --params: {err}"))
+}
+
+fn subaccount_flag(args: &[String]) -> StateResult<u32> {
+    flag(args, "--subaccount")?
+        .parse()
+        .map_err(|_| "Error: Invalid --subaccount".to_string())
+}
+
+/// JSON contents of the file named by `--file`
+fn read_payload(args: &[String]) -> StateResult<Value> {
+    let path = flag(args, "--file")?;
+    let contents =
+        std::fs::read_to_string(path).map_err(|err| format!("Error: Cannot read {path}: {err}"))?;
+    serde_json::from_str(&contents).map_err(|err| format!("Error: Invalid transaction file: {err}"))
+}
+
+fn spend_request(request: CreateTransactionRequest) -> SpendRequest {
+    SpendRequest {
+        subaccount: request.subaccount.unwrap_or(0),
+        recipients: request
+            .addressees
+            .unwrap_or_default()
+            .into_iter()
+            .map(|addressee| Recipient {
+                address: addressee.address,
+                satoshi: addressee.satoshi,
+                asset_id: addressee.asset_id,
+            })
+            .collect(),
+        fee_rate: request.fee_rate,
+        utxos: request.utxos.map(|utxos| {
+            utxos
+                .into_iter()
+                .map(|utxo| (utxo.txid, utxo.vout))
+                .collect()
+        }),
+        send_all: request.send_all,
+        memo: request.memo,
+    }
+}
+
+/// Spend described by a file written by `TxBuilder::dump`
+fn builder_request(payload: &Value) -> StateResult<SpendRequest> {
+    let invalid = |err: serde_json::Error| format!("Error: Invalid transaction file: {err}");
+    let outputs: Vec<TxOutput> =
+        serde_json::from_value(payload["outputs"].clone()).map_err(invalid)?;
+    let inputs: Vec<String> = serde_json::from_value(payload["inputs"].clone()).map_err(invalid)?;
+
+    let utxos = inputs
+        .iter()
+        .map(|input| {
+            input
+                .split_once(':')
+                .and_then(|(txid, vout)| Some((txid.to_string(), vout.parse().ok()?)))
+                .ok_or_else(|| format!("Error: Invalid input {input}, expected txid:vout"))
+        })
+        .collect::<StateResult<Vec<_>>>()?;
+
+    Ok(SpendRequest {
+        subaccount: payload["subaccount"]
+            .as_u64()
+            .and_then(|sub| u32::try_from(sub).ok())
+            .unwrap_or(0),
+        recipients: outputs
+            .into_iter()
+            .map(|output| Recipient {
+                address: output.address.unwrap_or_default(),
+                satoshi: output.satoshi,
+                asset_id: output.asset_id,
+            })
+            .collect(),
+        fee_rate: serde_json::from_value(payload["fee_rate"].clone()).map_err(invalid)?,
+        utxos: (!utxos.is_empty()).then_some(utxos),
+        send_all: payload["send_all"].as_bool().unwrap_or(false),
+        memo: None,
+    })
+}
+
+/// Apply `get utxos` filters
+fn filter_utxos(utxos: Vec<UnspentOutput>, params: &GetUnspentOutputsParams) -> Vec<UnspentOutput> {
+    utxos
+        .into_iter()
+        .filter(|utxo| params.subaccount.is_none_or(|sub| utxo.subaccount == sub))
+        .filter(|utxo| params.include_frozen.unwrap_or(false) || !utxo.is_frozen)
+        .filter(|utxo| !params.confidential_only.unwrap_or(false) || utxo.is_confidential)
+        .filter(|utxo| {
+            let confirmations = utxo.confirmations.unwrap_or(0);
+            params.min_confs.is_none_or(|min| confirmations >= min)
+                && params.max_confs.is_none_or(|max| confirmations <= max)
+        })
+        .filter(|utxo| {
+            params
+                .asset_id
+                .as_ref()
+                .is_none_or(|asset| state::asset_key(utxo.asset_id.as_ref()) == *asset)
+        })
+        .filter(|utxo| params.min_value.is_none_or(|min| utxo.satoshi >= min))
+        .filter(|utxo| params.max_value.is_none_or(|max| utxo.satoshi <= max))
+        .collect()
+}
+
+/// One page of history, newest first
+fn transaction_page(history: Vec<Transaction>, params: &GetTransactionsParams) -> TransactionList {
+    let first = params.first.unwrap_or(0) as usize;
+    let count = params.count.unwrap_or(DEFAULT_PAGE_COUNT) as usize;
+    let matching: Vec<Transaction> = history
+        .into_iter()
+        .rev()
+        .filter(|tx| {
+            params
+                .subaccount
+                .is_none_or(|sub| tx.subaccounts.contains(&sub))
+        })
+        .collect();
+
+    let more = matching.len() > first + count;
+    TransactionList {
+        transactions: matching.into_iter().skip(first).take(count).collect(),
+        more,
+        next_page: more.then(|| (first + count).to_string()),
+    }
+}
@@ -0,0 +1,179 @@
+//! Wallet and login session behind the fake green-cli
+//!
+//! Tracks whether a wallet exists, the credentials it was created with, its
+//! PIN and the kind of session currently logged in.
+
+use super::state::StateResult;
+use crate::config::Network;
+use crate::types::wallet::{CreateWalletRequest, LoginCredentials, WalletInfo};
+
+/// Mnemonic of the wallet every [`FakeGreen::new`](super::FakeGreen::new) starts logged in to
+pub const DEFAULT_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon \
+                                    abandon abandon abandon abandon abandon about";
+
+/// Name given to wallets created without one
+const DEFAULT_WALLET_NAME: &str = "Green Wallet";
+
+/// Word counts of valid BIP39 mnemonics
+const MNEMONIC_LENGTHS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// Secrets of the wallet known to the fake
+#[derive(Debug, Clone)]
+pub struct FakeWallet {
+    pub name: String,
+    pub mnemonic: String,
+    pub password: Option<String>,
+    pub pin: Option<String>,
+}
+
+impl FakeWallet {
+    /// Extended public key handed out for watch-only logins
+    ///
+    /// Derived deterministically from the mnemonic, so every wallet created
+    /// from the same mnemonic shares it.
+    pub fn xpub(&self) -> String {
+        // FNV-1a, enough to tell wallets apart
+        let hash = self
+            .mnemonic
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        format!("tpubfake{hash:016x}")
+    }
+}
+
+/// Kind of session currently logged in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Login {
+    Full,
+    WatchOnly,
+}
+
+/// The wallet, if any, and the current session
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    pub wallet: Option<FakeWallet>,
+    pub login: Option<Login>,
+}
+
+impl Session {
+    /// A session logged in to a wallet created from [`DEFAULT_MNEMONIC`]
+    pub fn logged_in() -> Self {
+        Self {
+            wallet: Some(FakeWallet {
+                name: DEFAULT_WALLET_NAME.to_string(),
+                mnemonic: DEFAULT_MNEMONIC.to_string(),
+                password: None,
+                pin: None,
+            }),
+            login: Some(Login::Full),
+        }
+    }
+
+    pub fn create(&mut self, request: CreateWalletRequest) -> StateResult<()> {
+        if self.wallet.is_some() {
+            return Err("Error: A wallet already exists in this data directory".to_string());
+        }
+        let mnemonic = normalize_mnemonic(&request.mnemonic)?;
+        self.wallet = Some(FakeWallet {
+            name: request
+                .name
+                .unwrap_or_else(|| DEFAULT_WALLET_NAME.to_string()),
+            mnemonic,
+            password: request.password,
+            pin: None,
+        });
+        self.login = Some(Login::Full);
+        Ok(())
+    }
+
+    pub fn login(&mut self, credentials: &LoginCredentials) -> StateResult<()> {
+        let wallet = self.wallet()?;
+        let login = if let Some(ref mnemonic) = credentials.mnemonic {
+            let mnemonic = normalize_mnemonic(mnemonic)?;
+            if mnemonic != wallet.mnemonic || credentials.password != wallet.password {
+                return Err("Error: id_login_failed".to_string());
+            }
+            Login::Full
+        } else if let Some(ref pin) = credentials.pin {
+            match wallet.pin {
+                Some(ref expected) if expected == pin => Login::Full,
+                Some(_) => return Err("Error: id_invalid_pin".to_string()),
+                None => return Err("Error: id_login_failed: no PIN is set up".to_string()),
+            }
+        } else if let Some(ref watch_only) = credentials.watch_only {
+            if watch_only.xpub != wallet.xpub() {
+                return Err("Error: id_login_failed".to_string());
+            }
+            Login::WatchOnly
+        } else {
+            return Err("Error: id_login_failed: no credentials given".to_string());
+        };
+        self.login = Some(login);
+        Ok(())
+    }
+
+    pub fn set_pin(&mut self, pin: String) -> StateResult<()> {
+        self.require_full()?;
+        if pin.is_empty() || !pin.chars().all(|c| c.is_ascii_digit()) {
+            return Err("Error: Invalid PIN, expected digits only".to_string());
+        }
+        if let Some(ref mut wallet) = self.wallet {
+            wallet.pin = Some(pin);
+        }
+        Ok(())
+    }
+
+    pub const fn logout(&mut self) {
+        self.login = None;
+    }
+
+    pub fn info(&self, network: Network) -> StateResult<WalletInfo> {
+        let wallet = self.wallet()?;
+        Ok(WalletInfo {
+            name: wallet.name.clone(),
+            network: network.as_str().to_string(),
+            watch_only: self.login == Some(Login::WatchOnly),
+            is_locked: self.login.is_none(),
+            version: Some(1),
+        })
+    }
+
+    fn wallet(&self) -> StateResult<&FakeWallet> {
+        self.wallet
+            .as_ref()
+            .ok_or_else(|| "Error: Wallet not found, create one first".to_string())
+    }
+
+    /// Fail unless some session is logged in
+    pub fn require_login(&self) -> StateResult<()> {
+        self.wallet()?;
+        self.login
+            .map(drop)
+            .ok_or_else(|| "Error: Login required".to_string())
+    }
+
+    /// Fail unless a session able to sign is logged in
+    pub fn require_full(&self) -> StateResult<()> {
+        self.require_login()?;
+        if self.login == Some(Login::WatchOnly) {
+            return Err("Error: Watch-only sessions cannot sign".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Collapse whitespace in a mnemonic and check its shape
+fn normalize_mnemonic(mnemonic: &str) -> StateResult<String> {
+    let words: Vec<&str> = mnemonic.split_whitespace().collect();
+    let well_formed = MNEMONIC_LENGTHS.contains(&words.len())
+        && words
+            .iter()
+            .all(|word| word.chars().all(|c| c.is_ascii_lowercase()));
+    if well_formed {
+        Ok(words.join(" "))
+    } else {
+        Err("Error: Invalid mnemonic".to_string())
+    }
+}
@@ -0,0 +1,919 @@
+//! Wallet state behind the fake green-cli
+//!
+//! Keeps subaccounts, addresses, unspent outputs and transactions, and applies
+//! spends so that balances, UTXOs and history stay consistent.
+
+use super::session::Session;
+use crate::address::{validate_address, AddressEncoding, AddressScriptType, ParsedAddress};
+use crate::config::Network;
+use crate::types::address::{AddressDetails, AddressType, ReceiveAddress, UpdateAddressRequest};
+use crate::types::common::{Address, AssetId, BlockHeight, Pointer, Satoshis, TxId};
+use crate::types::fee::FeeRate;
+use crate::types::subaccount::{Subaccount, SubaccountType};
+use crate::types::transaction::{Transaction, TxInput, TxOutput};
+use crate::types::utxo::{UnspentOutput, UpdateUtxoRequest};
+use crate::types::Balance;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Outputs below this value are folded into the fee instead of creating change
+pub const DUST_LIMIT: Satoshis = 546;
+
+/// Number of addresses returned per `get previous-addresses` page
+pub const ADDRESS_PAGE_SIZE: usize = 10;
+
+/// Key used for the fee asset in balances and UTXO groupings
+const FEE_ASSET: &str = "btc";
+
+/// A wallet-side failure, reported as green-cli stderr
+pub type StateResult<T> = std::result::Result<T, String>;
+
+/// Address bookkeeping for one subaccount
+#[derive(Debug, Clone)]
+pub struct FakeSubaccount {
+    pub info: Subaccount,
+    pub receive: Vec<AddressDetails>,
+    pub change: Vec<AddressDetails>,
+}
+
+/// A recipient of a spend
+#[derive(Debug, Clone)]
+pub struct Recipient {
+    pub address: Address,
+    pub satoshi: Satoshis,
+    pub asset_id: Option<AssetId>,
+}
+
+/// Everything needed to build a spend from the wallet
+#[derive(Debug, Clone, Default)]
+pub struct SpendRequest {
+    pub subaccount: u32,
+    pub recipients: Vec<Recipient>,
+    pub fee_rate: Option<FeeRate>,
+    pub utxos: Option<Vec<(TxId, u32)>>,
+    pub send_all: bool,
+    pub memo: Option<String>,
+}
+
+/// A fully funded spend that has not been applied yet
+#[derive(Debug, Clone)]
+pub struct SpendPlan {
+    pub subaccount: u32,
+    pub inputs: Vec<UnspentOutput>,
+    pub outputs: Vec<TxOutput>,
+    pub fee: Satoshis,
+    pub vsize: u32,
+    pub memo: Option<String>,
+}
+
+/// Complete state of the fake wallet
+#[derive(Debug, Clone)]
+pub struct WalletState {
+    pub network: Network,
+    pub block_height: BlockHeight,
+    pub fee_estimates: HashMap<u32, FeeRate>,
+    pub subaccounts: Vec<FakeSubaccount>,
+    pub utxos: Vec<UnspentOutput>,
+    pub transactions: Vec<Transaction>,
+    pub session: Session,
+    next_txid: u64,
+}
+
+impl WalletState {
+    pub fn new(network: Network) -> Self {
+        let mut state = Self {
+            network,
+            block_height: 100,
+            fee_estimates: [(1, 20), (3, 10), (6, 5), (12, 2), (144, 1)]
+                .into_iter()
+                .map(|(blocks, rate)| (blocks, FeeRate::from_sat_per_vb(rate)))
+                .chain([(0, FeeRate::min_relay(network))])
+                .collect(),
+            subaccounts: Vec::new(),
+            utxos: Vec::new(),
+            transactions: Vec::new(),
+            session: Session::logged_in(),
+            next_txid: 1,
+        };
+        state.create_subaccount("Main Account".to_string(), SubaccountType::TwoOfTwo);
+        state
+    }
+
+    /// Allocate a fresh, deterministic transaction ID
+    pub fn new_txid(&mut self) -> TxId {
+        let txid = format!("{:064x}", self.next_txid);
+        self.next_txid += 1;
+        txid
+    }
+
+    pub fn create_subaccount(
+        &mut self,
+        name: String,
+        subaccount_type: SubaccountType,
+    ) -> Subaccount {
+        let pointer = u32::try_from(self.subaccounts.len()).unwrap_or(u32::MAX);
+        let info = Subaccount {
+            pointer,
+            name,
+            subaccount_type,
+            recovery_mnemonic: None,
+            recovery_xpub: None,
+            required_ca: 0,
+            available_ca: 0,
+            hidden: false,
+            bip44_discovered: None,
+        };
+        self.subaccounts.push(FakeSubaccount {
+            info: info.clone(),
+            receive: Vec::new(),
+            change: Vec::new(),
+        });
+        info
+    }
+
+    pub fn subaccount(&self, pointer: u32) -> StateResult<&FakeSubaccount> {
+        self.subaccounts
+            .get(pointer as usize)
+            .ok_or_else(|| format!("Error: Unknown subaccount {pointer}"))
+    }
+
+    pub fn subaccount_mut(&mut self, pointer: u32) -> StateResult<&mut FakeSubaccount> {
+        self.subaccounts
+            .get_mut(pointer as usize)
+            .ok_or_else(|| format!("Error: Unknown subaccount {pointer}"))
+    }
+
+    /// Address type produced by a subaccount type
+    fn address_type_for(subaccount_type: &SubaccountType) -> AddressType {
+        subaccount_type
+            .default_address_type()
+            .unwrap_or(AddressType::Csv)
+    }
+
+    /// Deterministic address for a subaccount/branch/pointer
+    ///
+    /// The address is well formed for the network, with a program hashed
+    /// from its derivation path; confidential on Liquid.
+    fn derive_address(
+        &self,
+        subaccount: u32,
+        branch: u32,
+        pointer: Pointer,
+        address_type: &AddressType,
+    ) -> Address {
+        let hash = |tag: &str| -> Vec<u8> {
+            Sha256::digest(format!("{tag}/{subaccount}/{branch}/{pointer}")).to_vec()
+        };
+        let (script_type, witness_version, program_len) = match address_type {
+            AddressType::P2pkh => (AddressScriptType::P2pkh, None, 20),
+            AddressType::P2sh | AddressType::P2shP2wpkh => (AddressScriptType::P2sh, None, 20),
+            AddressType::P2wpkh => (AddressScriptType::P2wpkh, Some(0), 20),
+            AddressType::P2tr => (AddressScriptType::P2tr, Some(1), 32),
+            _ => (AddressScriptType::P2wsh, Some(0), 32),
+        };
+        let encoding = match (witness_version, self.network.is_liquid()) {
+            (None, _) => AddressEncoding::Base58,
+            (Some(0), false) => AddressEncoding::Bech32,
+            (Some(0), true) => AddressEncoding::Blech32,
+            (_, false) => AddressEncoding::Bech32m,
+            (_, true) => AddressEncoding::Blech32m,
+        };
+        let blinding_pubkey = self.network.is_liquid().then(|| {
+            let mut pubkey = vec![0x02];
+            pubkey.extend(hash("blinding"));
+            pubkey
+        });
+        let mut program = hash("script");
+        program.truncate(program_len);
+
+        ParsedAddress {
+            network: self.network,
+            encoding,
+            script_type,
+            witness_version,
+            program,
+            blinding_pubkey,
+        }
+        .to_string()
+    }
+
+    /// Generate the next address on a branch (0 external, 1 internal)
+    pub fn new_address(&mut self, subaccount: u32, branch: u32) -> StateResult<ReceiveAddress> {
+        let sub = self.subaccount(subaccount)?;
+        let list = if branch == 0 {
+            &sub.receive
+        } else {
+            &sub.change
+        };
+        let pointer = u32::try_from(list.len()).unwrap_or(u32::MAX) + 1;
+        let address_type = Self::address_type_for(&sub.info.subaccount_type);
+        let address = self.derive_address(subaccount, branch, pointer, &address_type);
+
+        let details = AddressDetails {
+            address: address.clone(),
+            address_type: address_type.clone(),
+            subaccount,
+            pointer,
+            label: None,
+            tx_count: 0,
+            is_used: false,
+        };
+        let sub = self.subaccount_mut(subaccount)?;
+        if branch == 0 {
+            sub.receive.push(details);
+        } else {
+            sub.change.push(details);
+        }
+
+        Ok(ReceiveAddress {
+            address,
+            pointer,
+            address_type,
+            branch,
+            subaccount,
+            script_pubkey: None,
+            is_confidential: None,
+            unconfidential_address: None,
+        })
+    }
+
+    /// Latest unused receive address, generating one if needed
+    pub fn current_address(&mut self, subaccount: u32) -> StateResult<ReceiveAddress> {
+        let sub = self.subaccount(subaccount)?;
+        if let Some(details) = sub.receive.last().filter(|details| !details.is_used) {
+            return Ok(ReceiveAddress {
+                address: details.address.clone(),
+                pointer: details.pointer,
+                address_type: details.address_type.clone(),
+                branch: 0,
+                subaccount,
+                script_pubkey: None,
+                is_confidential: None,
+                unconfidential_address: None,
+            });
+        }
+        self.new_address(subaccount, 0)
+    }
+
+    /// Receive addresses below `last_pointer`, newest first, one page at a time
+    pub fn previous_addresses(
+        &self,
+        subaccount: u32,
+        last_pointer: Option<Pointer>,
+        unused_only: bool,
+    ) -> StateResult<Vec<AddressDetails>> {
+        let sub = self.subaccount(subaccount)?;
+        Ok(sub
+            .receive
+            .iter()
+            .rev()
+            .filter(|details| last_pointer.is_none_or(|last| details.pointer < last))
+            .take(ADDRESS_PAGE_SIZE)
+            .filter(|details| !unused_only || !details.is_used)
+            .cloned()
+            .collect())
+    }
+
+    /// Update the label of a receive address
+    pub fn update_address(&mut self, request: UpdateAddressRequest) -> StateResult<AddressDetails> {
+        let details = self
+            .subaccount_mut(request.subaccount)?
+            .receive
+            .iter_mut()
+            .find(|details| details.pointer == request.pointer)
+            .ok_or_else(|| {
+                format!(
+                    "Error: Address {} not found in subaccount {}",
+                    request.pointer, request.subaccount
+                )
+            })?;
+        if let Some(label) = request.label {
+            details.label = (!label.is_empty()).then_some(label);
+        }
+        Ok(details.clone())
+    }
+
+    /// Find a wallet address, returning (subaccount, branch, pointer)
+    fn owner_of(&self, address: &str) -> Option<(u32, u32, Pointer)> {
+        self.subaccounts.iter().find_map(|sub| {
+            let lookup = |list: &[AddressDetails], branch| {
+                list.iter()
+                    .find(|details| details.address == address)
+                    .map(|details| (sub.info.pointer, branch, details.pointer))
+            };
+            lookup(&sub.receive, 0).or_else(|| lookup(&sub.change, 1))
+        })
+    }
+
+    fn mark_used(&mut self, address: &str) {
+        for sub in &mut self.subaccounts {
+            for details in sub.receive.iter_mut().chain(sub.change.iter_mut()) {
+                if details.address == address {
+                    details.tx_count += 1;
+                    details.is_used = true;
+                }
+            }
+        }
+    }
+
+    /// Confirmations for something mined at `height`
+    pub const fn confirmations(&self, height: Option<BlockHeight>) -> u32 {
+        match height {
+            Some(height) if height <= self.block_height => self.block_height - height + 1,
+            _ => 0,
+        }
+    }
+
+    /// Unspent outputs with confirmations filled in
+    pub fn unspent_outputs(&self) -> Vec<UnspentOutput> {
+        self.utxos
+            .iter()
+            .cloned()
+            .map(|mut utxo| {
+                utxo.confirmations = Some(self.confirmations(utxo.block_height));
+                utxo
+            })
+            .collect()
+    }
+
+    /// Freeze, thaw or relabel an unspent output
+    pub fn update_utxo(&mut self, request: UpdateUtxoRequest) -> StateResult<UnspentOutput> {
+        let utxo = self
+            .utxos
+            .iter_mut()
+            .find(|utxo| utxo.txhash == request.txhash && utxo.vout == request.vout)
+            .ok_or_else(|| format!("Error: UTXO {}:{} not found", request.txhash, request.vout))?;
+        if let Some(is_frozen) = request.is_frozen {
+            utxo.is_frozen = is_frozen;
+        }
+        if let Some(memo) = request.memo {
+            utxo.memo = (!memo.is_empty()).then_some(memo);
+        }
+        let mut utxo = utxo.clone();
+        utxo.confirmations = Some(self.confirmations(utxo.block_height));
+        Ok(utxo)
+    }
+
+    /// Transactions with confirmations filled in
+    pub fn transaction_history(&self) -> Vec<Transaction> {
+        self.transactions
+            .iter()
+            .cloned()
+            .map(|mut tx| {
+                tx.confirmations = self.confirmations(tx.block_height);
+                tx
+            })
+            .collect()
+    }
+
+    /// Balance across all subaccounts or a single one
+    pub fn balance(&self, subaccount: Option<u32>) -> Balance {
+        let mut balance = Balance::new();
+        for utxo in &self.utxos {
+            if subaccount.is_some_and(|sub| sub != utxo.subaccount) {
+                continue;
+            }
+            let asset = asset_key(utxo.asset_id.as_ref());
+            let total = balance.get(&asset).unwrap_or(0) + utxo.satoshi;
+            balance.set(asset, total);
+        }
+        balance
+    }
+
+    /// Receive an external payment into a fresh address of `subaccount`
+    pub fn fund(
+        &mut self,
+        subaccount: u32,
+        satoshi: Satoshis,
+        asset_id: Option<AssetId>,
+    ) -> StateResult<TxId> {
+        let address = self.new_address(subaccount, 0)?;
+        let funding_txid = self.new_txid();
+        let txid = self.new_txid();
+
+        let output = TxOutput {
+            satoshi,
+            script_pubkey: String::new(),
+            address: Some(address.address.clone()),
+            asset_id: asset_id.clone(),
+            is_relevant: true,
+            subaccount: Some(subaccount),
+            pointer: Some(address.pointer),
+            is_change: false,
+        };
+        let input = TxInput {
+            txid: funding_txid,
+            vout: 0,
+            script_sig: None,
+            witness: None,
+            sequence: 0xffff_fffd,
+            prevout: None,
+            is_relevant: false,
+            address: None,
+            subaccount: None,
+            pointer: None,
+        };
+
+        self.mark_used(&address.address);
+        self.utxos.push(UnspentOutput {
+            txhash: txid.clone(),
+            vout: 0,
+            satoshi,
+            asset_id,
+            block_height: None,
+            confirmations: None,
+            address: Some(address.address),
+            address_type: Some(address.address_type),
+            script_pubkey: None,
+            subaccount,
+            pointer: address.pointer,
+            is_internal: false,
+            is_confidential: self.network.is_liquid(),
+            is_frozen: false,
+            memo: None,
+        });
+        self.transactions.push(Transaction {
+            txid: txid.clone(),
+            version: 2,
+            locktime: 0,
+            inputs: vec![input],
+            outputs: vec![output],
+            weight: Some(440),
+            size: Some(110),
+            vsize: Some(110),
+            fee: Some(0),
+            fee_rate: None,
+            block_hash: None,
+            block_height: None,
+            confirmations: 0,
+            timestamp: Some(self.timestamp()),
+            memo: None,
+            tx_type: Some("incoming".to_string()),
+            subaccounts: vec![subaccount],
+            can_rbf: false,
+            has_been_replaced: false,
+            hex: None,
+        });
+        Ok(txid)
+    }
+
+    /// Mine `blocks` blocks, confirming everything in the mempool
+    pub fn mine(&mut self, blocks: u32) {
+        if blocks == 0 {
+            return;
+        }
+        let height = self.block_height + 1;
+        for tx in &mut self.transactions {
+            if tx.block_height.is_none() && !tx.has_been_replaced {
+                tx.block_height = Some(height);
+                tx.block_hash = Some(format!("{height:064x}"));
+            }
+        }
+        for utxo in &mut self.utxos {
+            if utxo.block_height.is_none() {
+                utxo.block_height = Some(height);
+            }
+        }
+        self.block_height += blocks;
+    }
+
+    fn timestamp(&self) -> u64 {
+        1_700_000_000 + u64::from(self.block_height) * 600
+    }
+
+    /// Fee rate for a request, falling back to the 6-block estimate and
+    /// never below the network's minimum relay fee rate
+    fn effective_fee_rate(&self, requested: Option<FeeRate>) -> FeeRate {
+        let floor = FeeRate::min_relay(self.network);
+        requested
+            .or_else(|| self.fee_estimates.get(&6).copied())
+            .map_or(floor, |rate| rate.max(floor))
+    }
+
+    /// Rough vsize of a segwit spend with the given input and output counts
+    pub fn estimate_vsize(inputs: usize, outputs: usize) -> u32 {
+        u32::try_from(11 + 68 * inputs + 31 * outputs).unwrap_or(u32::MAX)
+    }
+
+    /// Select inputs and compute outputs and fee for a spend
+    ///
+    /// Inputs are chosen largest first, skipping frozen outputs. Assets other
+    /// than the fee asset are funded first, since their change adds outputs
+    /// the fee has to pay for.
+    pub fn plan_spend(&self, request: &SpendRequest) -> StateResult<SpendPlan> {
+        self.validate(request)?;
+        let fee_rate = self.effective_fee_rate(request.fee_rate);
+        let spendable = self.spendable(request)?;
+        let mut recipients = request.recipients.clone();
+        let mut inputs: Vec<UnspentOutput> = Vec::new();
+        let mut change: Vec<(String, Satoshis)> = Vec::new();
+
+        for (asset, amount) in asset_totals(&recipients) {
+            if asset == FEE_ASSET {
+                continue;
+            }
+            let candidates = by_asset(&spendable, &asset);
+            if request.send_all {
+                recipients[0].satoshi = candidates.iter().map(|utxo| utxo.satoshi).sum();
+                inputs.extend(candidates);
+                continue;
+            }
+            let (selected, total) = select_largest_first(candidates, |_| amount)?;
+            if total > amount {
+                change.push((asset, total - amount));
+            }
+            inputs.extend(selected);
+        }
+
+        let candidates = by_asset(&spendable, FEE_ASSET);
+        let sweep_fee_asset =
+            request.send_all && asset_key(recipients[0].asset_id.as_ref()) == FEE_ASSET;
+        let fee = if sweep_fee_asset {
+            let total: Satoshis = candidates.iter().map(|utxo| utxo.satoshi).sum();
+            inputs.extend(candidates);
+            let fee = fee_rate.fee_for_vsize(Self::estimate_vsize(inputs.len(), 1));
+            recipients[0].satoshi = total
+                .checked_sub(fee)
+                .filter(|amount| *amount >= DUST_LIMIT)
+                .ok_or_else(insufficient_funds)?;
+            fee
+        } else {
+            let amount: Satoshis = asset_totals(&recipients)
+                .into_iter()
+                .filter(|(asset, _)| asset == FEE_ASSET)
+                .map(|(_, amount)| amount)
+                .sum();
+            let base_inputs = inputs.len();
+            let outputs = recipients.len() + change.len() + 1;
+            let fee_for =
+                |count: usize| fee_rate.fee_for_vsize(Self::estimate_vsize(count, outputs));
+            let (selected, total) =
+                select_largest_first(candidates, |count| amount + fee_for(base_inputs + count))?;
+            inputs.extend(selected);
+            let fee = fee_for(inputs.len());
+            let leftover = total - amount - fee;
+            if leftover >= DUST_LIMIT {
+                change.push((FEE_ASSET.to_string(), leftover));
+                fee
+            } else {
+                fee + leftover
+            }
+        };
+        if inputs.is_empty() {
+            return Err(insufficient_funds());
+        }
+
+        let mut outputs: Vec<TxOutput> = recipients
+            .into_iter()
+            .map(|recipient| {
+                let owner = self.owner_of(&recipient.address);
+                TxOutput {
+                    satoshi: recipient.satoshi,
+                    script_pubkey: String::new(),
+                    address: Some(recipient.address),
+                    asset_id: recipient.asset_id,
+                    is_relevant: owner.is_some(),
+                    subaccount: owner.map(|(sub, _, _)| sub),
+                    pointer: owner.map(|(_, _, pointer)| pointer),
+                    is_change: false,
+                }
+            })
+            .collect();
+        outputs.extend(change.into_iter().map(|(asset, amount)| TxOutput {
+            satoshi: amount,
+            script_pubkey: String::new(),
+            address: None,
+            asset_id: asset_id_for(&asset),
+            is_relevant: true,
+            subaccount: Some(request.subaccount),
+            pointer: None,
+            is_change: true,
+        }));
+
+        Ok(SpendPlan {
+            subaccount: request.subaccount,
+            vsize: Self::estimate_vsize(inputs.len(), outputs.len()),
+            inputs,
+            outputs,
+            fee,
+            memo: request.memo.clone(),
+        })
+    }
+
+    /// Reject malformed spends before selecting inputs
+    fn validate(&self, request: &SpendRequest) -> StateResult<()> {
+        self.subaccount(request.subaccount)?;
+        if request.recipients.is_empty() {
+            return Err("Error: No addressees given".to_string());
+        }
+        if request.send_all && request.recipients.len() != 1 {
+            return Err("Error: send_all requires exactly one addressee".to_string());
+        }
+        for recipient in &request.recipients {
+            if validate_address(&recipient.address, Some(self.network)).is_err() {
+                return Err(format!("Error: Invalid address {}", recipient.address));
+            }
+            if !request.send_all && recipient.satoshi < DUST_LIMIT {
+                return Err("Error: Amount below the dust threshold".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Outputs a request may spend
+    fn spendable(&self, request: &SpendRequest) -> StateResult<Vec<UnspentOutput>> {
+        let Some(ref refs) = request.utxos else {
+            return Ok(self
+                .utxos
+                .iter()
+                .filter(|utxo| utxo.subaccount == request.subaccount && !utxo.is_frozen)
+                .cloned()
+                .collect());
+        };
+        refs.iter()
+            .map(|(txid, vout)| {
+                let utxo = self
+                    .utxos
+                    .iter()
+                    .find(|utxo| utxo.txhash == *txid && utxo.vout == *vout)
+                    .ok_or_else(|| format!("Error: Unknown UTXO {txid}:{vout}"))?;
+                if utxo.is_frozen {
+                    return Err(format!("Error: UTXO {txid}:{vout} is frozen"));
+                }
+                Ok(utxo.clone())
+            })
+            .collect()
+    }
+
+    /// Build the transaction a plan would produce, without touching state
+    pub fn preview(&self, plan: &SpendPlan, txid: TxId) -> Transaction {
+        let inputs = plan
+            .inputs
+            .iter()
+            .map(|utxo| TxInput {
+                txid: utxo.txhash.clone(),
+                vout: utxo.vout,
+                script_sig: None,
+                witness: None,
+                sequence: 0xffff_fffd,
+                prevout: Some(TxOutput {
+                    satoshi: utxo.satoshi,
+                    script_pubkey: String::new(),
+                    address: utxo.address.clone(),
+                    asset_id: utxo.asset_id.clone(),
+                    is_relevant: true,
+                    subaccount: Some(utxo.subaccount),
+                    pointer: Some(utxo.pointer),
+                    is_change: utxo.is_internal,
+                }),
+                is_relevant: true,
+                address: utxo.address.clone(),
+                subaccount: Some(utxo.subaccount),
+                pointer: Some(utxo.pointer),
+            })
+            .collect();
+
+        let all_ours = plan
+            .outputs
+            .iter()
+            .all(|output| output.is_relevant || output.is_change);
+        let mut subaccounts = vec![plan.subaccount];
+        for output in &plan.outputs {
+            if let Some(sub) = output.subaccount {
+                if !subaccounts.contains(&sub) {
+                    subaccounts.push(sub);
+                }
+            }
+        }
+
+        Transaction {
+            txid,
+            version: 2,
+            locktime: self.block_height,
+            inputs,
+            outputs: plan.outputs.clone(),
+            weight: Some(plan.vsize * 4),
+            size: Some(plan.vsize),
+            vsize: Some(plan.vsize),
+            fee: Some(plan.fee),
+            fee_rate: Some(FeeRate::from_fee_and_vsize(plan.fee, plan.vsize)),
+            block_hash: None,
+            block_height: None,
+            confirmations: 0,
+            timestamp: Some(self.timestamp()),
+            memo: plan.memo.clone(),
+            tx_type: Some(if all_ours { "redeposit" } else { "outgoing" }.to_string()),
+            subaccounts,
+            can_rbf: true,
+            has_been_replaced: false,
+            hex: None,
+        }
+    }
+
+    /// Apply a plan: spend its inputs, create wallet outputs and record history
+    pub fn apply(&mut self, mut plan: SpendPlan) -> StateResult<Transaction> {
+        for input in &plan.inputs {
+            if !self
+                .utxos
+                .iter()
+                .any(|utxo| utxo.txhash == input.txhash && utxo.vout == input.vout)
+            {
+                return Err(format!(
+                    "Error: UTXO {}:{} already spent",
+                    input.txhash, input.vout
+                ));
+            }
+        }
+
+        // Give change outputs real internal addresses
+        for output in &mut plan.outputs {
+            if output.is_change {
+                let change = self.new_address(plan.subaccount, 1)?;
+                output.address = Some(change.address);
+                output.pointer = Some(change.pointer);
+            }
+        }
+
+        let txid = self.new_txid();
+        let tx = self.preview(&plan, txid.clone());
+
+        self.utxos.retain(|utxo| {
+            !plan
+                .inputs
+                .iter()
+                .any(|input| input.txhash == utxo.txhash && input.vout == utxo.vout)
+        });
+
+        for (vout, output) in tx.outputs.iter().enumerate() {
+            let Some(ref address) = output.address else {
+                continue;
+            };
+            let Some((subaccount, branch, pointer)) = self.owner_of(address) else {
+                continue;
+            };
+            self.mark_used(address);
+            let address_type =
+                Self::address_type_for(&self.subaccount(subaccount)?.info.subaccount_type);
+            self.utxos.push(UnspentOutput {
+                txhash: txid.clone(),
+                vout: u32::try_from(vout).unwrap_or(u32::MAX),
+                satoshi: output.satoshi,
+                asset_id: output.asset_id.clone(),
+                block_height: None,
+                confirmations: None,
+                address: Some(address.clone()),
+                address_type: Some(address_type),
+                script_pubkey: None,
+                subaccount,
+                pointer,
+                is_internal: branch == 1,
+                is_confidential: self.network.is_liquid(),
+                is_frozen: false,
+                memo: None,
+            });
+        }
+
+        self.transactions.push(tx.clone());
+        Ok(tx)
+    }
+
+    /// Replace the unconfirmed transaction `txid` with a spend paying more
+    ///
+    /// The original's inputs become spendable again and its outputs are
+    /// dropped. The replacement must pay the original fee plus 1 sat/vB of
+    /// its own size, as BIP125 requires. Nothing changes if it fails.
+    pub fn replace(&mut self, txid: &str, request: &SpendRequest) -> StateResult<Transaction> {
+        let original = self
+            .transactions
+            .iter()
+            .find(|tx| tx.txid == txid)
+            .cloned()
+            .ok_or_else(|| format!("Error: Transaction {txid} not found"))?;
+        if original.block_height.is_some() {
+            return Err(format!("Error: Transaction {txid} is already confirmed"));
+        }
+        if original.has_been_replaced {
+            return Err(format!(
+                "Error: Transaction {txid} has already been replaced"
+            ));
+        }
+        if !original.signals_rbf() {
+            return Err(format!("Error: Transaction {txid} is not replaceable"));
+        }
+        let has_descendants = self
+            .transactions
+            .iter()
+            .any(|tx| !tx.has_been_replaced && tx.inputs.iter().any(|input| input.txid == txid));
+        if has_descendants {
+            return Err(format!(
+                "Error: Transaction {txid} has unconfirmed descendants"
+            ));
+        }
+
+        let mut next = self.clone();
+        next.utxos.retain(|utxo| utxo.txhash != txid);
+        for input in original.inputs.iter().filter(|input| input.is_relevant) {
+            let restored = next.restore_input(input)?;
+            next.utxos.push(restored);
+        }
+        let plan = next.plan_spend(request)?;
+        let old_fee = original.fee.unwrap_or_default();
+        if plan.fee < old_fee + u64::from(plan.vsize) {
+            return Err("Error: Replacement fee too low".to_string());
+        }
+        let tx = next.apply(plan)?;
+        for replaced in &mut next.transactions {
+            if replaced.txid == txid {
+                replaced.has_been_replaced = true;
+            }
+        }
+        *self = next;
+        Ok(tx)
+    }
+
+    /// Unspent output for a wallet input of an unconfirmed transaction
+    fn restore_input(&self, input: &TxInput) -> StateResult<UnspentOutput> {
+        let prevout = input
+            .prevout
+            .as_ref()
+            .ok_or_else(|| format!("Error: Unknown UTXO {}:{}", input.txid, input.vout))?;
+        let subaccount = input.subaccount.unwrap_or(0);
+        let address_type =
+            Self::address_type_for(&self.subaccount(subaccount)?.info.subaccount_type);
+        let block_height = self
+            .transactions
+            .iter()
+            .find(|tx| tx.txid == input.txid)
+            .and_then(|tx| tx.block_height);
+        Ok(UnspentOutput {
+            txhash: input.txid.clone(),
+            vout: input.vout,
+            satoshi: prevout.satoshi,
+            asset_id: prevout.asset_id.clone(),
+            block_height,
+            confirmations: None,
+            address: input.address.clone(),
+            address_type: Some(address_type),
+            script_pubkey: None,
+            subaccount,
+            pointer: input.pointer.unwrap_or(0),
+            is_internal: prevout.is_change,
+            is_confidential: self.network.is_liquid(),
+            is_frozen: false,
+            memo: None,
+        })
+    }
+}
+
+fn insufficient_funds() -> String {
+    "Error: Insufficient funds".to_string()
+}
+
+/// Amounts per asset, in order of first appearance
+fn asset_totals(recipients: &[Recipient]) -> Vec<(String, Satoshis)> {
+    let mut totals: Vec<(String, Satoshis)> = Vec::new();
+    for recipient in recipients {
+        let asset = asset_key(recipient.asset_id.as_ref());
+        match totals.iter_mut().find(|(key, _)| *key == asset) {
+            Some((_, amount)) => *amount += recipient.satoshi,
+            None => totals.push((asset, recipient.satoshi)),
+        }
+    }
+    totals
+}
+
+/// Spendable outputs of one asset
+fn by_asset(utxos: &[UnspentOutput], asset: &str) -> Vec<UnspentOutput> {
+    utxos
+        .iter()
+        .filter(|utxo| asset_key(utxo.asset_id.as_ref()) == asset)
+        .cloned()
+        .collect()
+}
+
+/// Pick the largest outputs until they cover `target(selected_count)`
+fn select_largest_first(
+    mut candidates: Vec<UnspentOutput>,
+    target: impl Fn(usize) -> Satoshis,
+) -> StateResult<(Vec<UnspentOutput>, Satoshis)> {
+    candidates.sort_by_key(|utxo| std::cmp::Reverse(utxo.satoshi));
+    let mut selected = Vec::new();
+    let mut total: Satoshis = 0;
+    let mut remaining = candidates.into_iter();
+    while total < target(selected.len()) {
+        let utxo = remaining.next().ok_or_else(insufficient_funds)?;
+        total += utxo.satoshi;
+        selected.push(utxo);
+    }
+    Ok((selected, total))
+}
+
+/// Balance/grouping key for an optional asset ID
+pub fn asset_key(asset_id: Option<&AssetId>) -> String {
+    asset_id.map_or_else(|| FEE_ASSET.to_string(), Clone::clone)
+}
+
+/// Inverse of `asset_key`
+fn asset_id_for(key: &str) -> Option<AssetId> {
+    (key != FEE_ASSET).then(|| key.to_string())
+}
@@ -0,0 +1,787 @@
+//! Command transports for green-cli
+//!
+//! The clients describe every green-cli invocation as a [`CliCommand`] and hand
+//! it to a transport, which runs it and reports a [`CliOutput`]. This keeps the
+//! clients independent of how commands are actually executed.
+//!
+//! Commands that take JSON parameters carry them as [`Params`] rather than as
+//! arguments. [`ProcessTransport`] passes them according to the command's
+//! [`ParamsMode`]: on the command line, over stdin, or through a temporary
+//! file readable only by the current user. Parameters marked sensitive through
+//! [`CliParams::is_sensitive`], such as login credentials, never appear on
+//! the command line, in `Debug` output, or in error messages.
+//!
+//! Two transports are provided:
+//! - [`ProcessTransport`] spawns the configured green-cli executable and is the
+//!   default for both clients
+//! - [`ScriptedTransport`] answers commands from an in-memory script of canned
+//!   outputs, so code built on green-rs can be unit-tested without green-cli
+//!
+//! # Example
+//!
+//! ```
+//! use green_rs::api::WalletExt;
+//! use green_rs::transport::{CliOutput, ScriptedTransport};
+//! use green_rs::{ClientConfig, GreenClient};
+//!
+//! let transport = ScriptedTransport::new()
+//!     .on(["get", "balance", "--json"], CliOutput::success(r#"{"btc": 1500}"#));
+//! let client = GreenClient::with_transport(ClientConfig::default(), transport);
+//!
+//! let balance = client.get_balance().unwrap();
+//! assert_eq!(balance.get("btc"), Some(1500));
+//! ```
+
+use crate::error::{CliError, Error, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::ffi::OsString;
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tempfile::NamedTempFile;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command as TokioCommand;
+
+/// How often a synchronous command is polled while waiting on its timeout
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Placeholder substituted for secrets in error output
+const REDACTED: &str = "<redacted>";
+
+/// Request types that can be passed to green-cli as JSON parameters
+///
+/// Implementations that may carry secrets (mnemonics, PINs, passwords)
+/// override [`CliParams::is_sensitive`] so their JSON is kept off the command
+/// line and out of error messages.
+pub trait CliParams: Serialize {
+    /// Whether these parameters contain secrets
+    fn is_sensitive(&self) -> bool {
+        false
+    }
+}
+
+/// How JSON parameters are handed to green-cli
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ParamsMode {
+    /// `--params <json>` on the command line
+    ///
+    /// Sensitive parameters are passed as with [`ParamsMode::File`] instead.
+    #[default]
+    Argv,
+    /// `--params -`, with the JSON written to green-cli's stdin
+    Stdin,
+    /// `--params-file <path>`, naming a temporary file with `0600`
+    /// permissions that is removed once the command finishes
+    File,
+}
+
+/// Serialized JSON parameters of a command
+///
+/// The `Debug` output of sensitive parameters is redacted.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Params {
+    json: String,
+    sensitive: bool,
+}
+
+impl Params {
+    /// Serialize `params`, keeping their sensitivity
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `params` cannot be serialized to JSON
+    pub fn new<P: CliParams + ?Sized>(params: &P) -> Result<Self> {
+        Ok(Self {
+            json: serde_json::to_string(params)?,
+            sensitive: params.is_sensitive(),
+        })
+    }
+
+    /// The JSON text of the parameters
+    #[must_use]
+    pub fn json(&self) -> &str {
+        &self.json
+    }
+
+    /// Whether the parameters contain secrets
+    #[must_use]
+    pub const fn is_sensitive(&self) -> bool {
+        self.sensitive
+    }
+
+    /// Replace every string value of sensitive parameters found in `text`
+    ///
+    /// Non-sensitive parameters leave `text` unchanged.
+    #[must_use]
+    pub fn redact(&self, text: &str) -> String {
+        if !self.sensitive {
+            return text.to_string();
+        }
+        let mut values = Vec::new();
+        if let Ok(value) = serde_json::from_str::<Value>(&self.json) {
+            collect_strings(&value, &mut values);
+        }
+        // Longest first, so a secret containing another is fully replaced
+        values.sort_by_key(|value| std::cmp::Reverse(value.len()));
+        let mut redacted = text.replace(&self.json, REDACTED);
+        for value in values {
+            redacted = redacted.replace(value.as_str(), REDACTED);
+        }
+        redacted
+    }
+}
+
+impl fmt::Debug for Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.sensitive {
+            f.write_str("Params(<redacted>)")
+        } else {
+            f.debug_tuple("Params").field(&self.json).finish()
+        }
+    }
+}
+
+/// Every non-empty string inside a JSON value
+fn collect_strings(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(text) if !text.is_empty() => out.push(text.clone()),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, out)),
+        Value::Object(map) => map.values().for_each(|item| collect_strings(item, out)),
+        _ => {}
+    }
+}
+
+/// A single green-cli invocation
+///
+/// Built by the clients from their [`ClientConfig`](crate::ClientConfig) and
+/// the command being run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliCommand {
+    /// Path to the green-cli executable
+    pub program: PathBuf,
+    /// Global arguments placed before the command
+    pub global_args: Vec<OsString>,
+    /// Command arguments, e.g. `["get", "balance", "--json"]`
+    pub args: Vec<String>,
+    /// Extra environment variables
+    pub env: Vec<(OsString, OsString)>,
+    /// Time the command may run before it is killed (`None` waits forever)
+    pub timeout: Option<Duration>,
+    /// JSON parameters, passed as described by `params_mode`
+    pub params: Option<Params>,
+    /// How `params` reach green-cli
+    pub params_mode: ParamsMode,
+}
+
+impl CliCommand {
+    /// Create a command for `program` with the given arguments and no extras
+    #[must_use]
+    pub fn new<P: Into<PathBuf>>(program: P, args: &[&str]) -> Self {
+        Self {
+            program: program.into(),
+            global_args: Vec::new(),
+            args: args.iter().map(ToString::to_string).collect(),
+            env: Vec::new(),
+            timeout: None,
+            params: None,
+            params_mode: ParamsMode::default(),
+        }
+    }
+
+    /// Attach JSON parameters to the command
+    #[must_use]
+    pub fn with_params(mut self, params: Params) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// The mode actually used for this command's parameters
+    ///
+    /// Sensitive parameters are never passed on the command line.
+    #[must_use]
+    pub const fn effective_params_mode(&self) -> ParamsMode {
+        match (&self.params, self.params_mode) {
+            (Some(params), ParamsMode::Argv) if params.is_sensitive() => ParamsMode::File,
+            (_, mode) => mode,
+        }
+    }
+
+    /// Convert the output of this command into stdout or an error
+    ///
+    /// Secrets from sensitive parameters are redacted from a failed
+    /// command's output before it becomes an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns the classified error if the command failed, as
+    /// [`CliOutput::into_result`] does
+    pub fn finish(&self, output: CliOutput) -> Result<String> {
+        match self.params {
+            Some(ref params) if params.is_sensitive() && !output.is_success() => CliOutput {
+                exit_code: output.exit_code,
+                stdout: params.redact(&output.stdout),
+                stderr: params.redact(&output.stderr),
+            }
+            .into_result(),
+            _ => output.into_result(),
+        }
+    }
+}
+
+/// Raw result of a finished green-cli invocation
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CliOutput {
+    /// Exit code (`None` if the process was terminated by a signal)
+    pub exit_code: Option<i32>,
+    /// Captured stdout
+    pub stdout: String,
+    /// Captured stderr
+    pub stderr: String,
+}
+
+impl CliOutput {
+    /// A successful invocation printing `stdout`
+    #[must_use]
+    pub fn success<S: Into<String>>(stdout: S) -> Self {
+        Self {
+            exit_code: Some(0),
+            stdout: stdout.into(),
+            stderr: String::new(),
+        }
+    }
+
+    /// A failed invocation exiting with `exit_code` and printing `stderr`
+    #[must_use]
+    pub fn failure<S: Into<String>>(exit_code: i32, stderr: S) -> Self {
+        Self {
+            exit_code: Some(exit_code),
+            stdout: String::new(),
+            stderr: stderr.into(),
+        }
+    }
+
+    /// Whether the invocation exited successfully
+    #[must_use]
+    pub const fn is_success(&self) -> bool {
+        matches!(self.exit_code, Some(0))
+    }
+
+    /// Convert the output into stdout on success or an error on failure
+    ///
+    /// # Errors
+    ///
+    /// Returns the classified [`CliError`] if the invocation failed: as
+    /// `Error::Network` when green-cli could not reach its backend, and as
+    /// `Error::Cli` otherwise
+    pub fn into_result(self) -> Result<String> {
+        if self.is_success() {
+            Ok(self.stdout)
+        } else {
+            Err(CliError::from_output(self.exit_code, &self.stdout, &self.stderr).into())
+        }
+    }
+}
+
+impl From<Output> for CliOutput {
+    fn from(output: Output) -> Self {
+        Self {
+            exit_code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        }
+    }
+}
+
+/// Synchronous transport used by [`GreenClient`](crate::GreenClient)
+pub trait CliTransport {
+    /// Run `command` to completion
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command could not be run at all, or
+    /// `Error::Timeout` if it outlived its timeout. A command that runs and
+    /// exits unsuccessfully is reported through [`CliOutput::exit_code`].
+    fn execute(&self, command: &CliCommand) -> Result<CliOutput>;
+}
+
+/// Asynchronous transport used by [`AsyncGreenClient`](crate::AsyncGreenClient)
+#[async_trait::async_trait]
+pub trait AsyncCliTransport: Send + Sync {
+    /// Run `command` to completion
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command could not be run at all, or
+    /// `Error::Timeout` if it outlived its timeout. A command that runs and
+    /// exits unsuccessfully is reported through [`CliOutput::exit_code`].
+    async fn execute(&self, command: &CliCommand) -> Result<CliOutput>;
+}
+
+/// Transport that spawns the green-cli executable
+///
+/// Commands are killed once their timeout expires. The asynchronous
+/// implementation also kills the child process if its future is dropped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessTransport;
+
+impl ProcessTransport {
+    /// Create a new process transport
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Build the process command line for `command`
+    ///
+    /// Parameters are passed according to the command's effective
+    /// [`ParamsMode`]; anything that must outlive the process, such as the
+    /// parameters file, is kept in the returned [`PreparedCommand`].
+    fn build_command(command: &CliCommand) -> Result<PreparedCommand> {
+        let mut process = Command::new(&command.program);
+        process
+            .args(&command.global_args)
+            .args(&command.args)
+            .envs(command.env.iter().map(|(key, value)| (key, value)));
+
+        let mut prepared = PreparedCommand {
+            process,
+            stdin: None,
+            params_file: None,
+        };
+        if let Some(ref params) = command.params {
+            match command.effective_params_mode() {
+                ParamsMode::Argv => {
+                    prepared.process.arg("--params").arg(params.json());
+                }
+                ParamsMode::Stdin => {
+                    prepared.process.arg("--params").arg("-");
+                    prepared.stdin = Some(params.json().as_bytes().to_vec());
+                }
+                ParamsMode::File => {
+                    let file = private_file(params.json())?;
+                    prepared.process.arg("--params-file").arg(file.path());
+                    prepared.params_file = Some(file);
+                }
+            }
+        }
+        Ok(prepared)
+    }
+}
+
+/// A process ready to spawn, with its stdin payload and parameters file
+struct PreparedCommand {
+    process: Command,
+    stdin: Option<Vec<u8>>,
+    // Removed from disk when dropped, after the process has finished
+    params_file: Option<NamedTempFile>,
+}
+
+impl PreparedCommand {
+    fn stdin_config(&self) -> Stdio {
+        if self.stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        }
+    }
+}
+
+/// Write `contents` to a new temporary file only the current user can read
+fn private_file(contents: &str) -> Result<NamedTempFile> {
+    let mut builder = tempfile::Builder::new();
+    builder.prefix("green-params-");
+    #[cfg(unix)]
+    builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600));
+    let mut file = builder.tempfile()?;
+    file.write_all(contents.as_bytes())?;
+    file.flush()?;
+    Ok(file)
+}
+
+impl CliTransport for ProcessTransport {
+    fn execute(&self, command: &CliCommand) -> Result<CliOutput> {
+        let mut prepared = Self::build_command(command)?;
+        let output = output_with_timeout(&mut prepared, command.timeout)?;
+        drop(prepared.params_file);
+        Ok(output.into())
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncCliTransport for ProcessTransport {
+    async fn execute(&self, command: &CliCommand) -> Result<CliOutput> {
+        let prepared = Self::build_command(command)?;
+        let stdin = prepared.stdin_config();
+        let mut child = TokioCommand::from(prepared.process)
+            .stdin(stdin)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        // Write the params while waiting, so a child that never reads them
+        // can't block past the timeout
+        let pipe = child.stdin.take();
+        let data = prepared.stdin;
+        let write = async move {
+            match (pipe, data) {
+                // Dropping the pipe afterwards closes the child's stdin
+                (Some(mut pipe), Some(data)) => ignore_broken_pipe(pipe.write_all(&data).await),
+                _ => Ok(()),
+            }
+        };
+        let run = async {
+            let (written, output) = tokio::join!(write, child.wait_with_output());
+            written?;
+            Ok::<_, Error>(output?)
+        };
+
+        // Dropping `wait_with_output` drops the child, which kills it
+        let output = match command.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, run)
+                .await
+                .map_err(|_| Error::Timeout)??,
+            None => run.await?,
+        };
+        drop(prepared.params_file);
+        Ok(output.into())
+    }
+}
+
+/// Run `command` to completion, killing it if it outlives `timeout`
+fn output_with_timeout(command: &mut PreparedCommand, timeout: Option<Duration>) -> Result<Output> {
+    let stdin = command.stdin_config();
+    let mut child = command
+        .process
+        .stdin(stdin)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain the pipes on separate threads so a chatty child can't block on a full pipe
+    let stdout = child.stdout.take().map(spawn_reader);
+    let stderr = child.stderr.take().map(spawn_reader);
+
+    // Write the params on a thread too, so a child that never reads them
+    // can't block past the timeout; dropping the pipe closes its stdin
+    let writer = match (child.stdin.take(), command.stdin.take()) {
+        (Some(mut pipe), Some(data)) => Some(thread::spawn(move || {
+            ignore_broken_pipe(pipe.write_all(&data))
+        })),
+        _ => None,
+    };
+
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let status: ExitStatus = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            // The reader and writer threads are left to finish on their own; a
+            // grandchild may still hold the pipes open.
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(Error::Timeout);
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+    if let Some(writer) = writer {
+        writer.join().unwrap_or(Ok(()))?;
+    }
+
+    Ok(Output {
+        status,
+        stdout: join_reader(stdout),
+        stderr: join_reader(stderr),
+    })
+}
+
+/// Treat a child that exits without reading all of its stdin as success
+fn ignore_broken_pipe(result: std::io::Result<()>) -> std::io::Result<()> {
+    match result {
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+/// Read a child pipe to the end on a background thread
+fn spawn_reader<R: Read + Send + 'static>(mut pipe: R) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+/// Collect the bytes read by a pipe reader thread
+fn join_reader(reader: Option<JoinHandle<Vec<u8>>>) -> Vec<u8> {
+    reader
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default()
+}
+
+/// How a scripted response is matched against command arguments
+#[derive(Debug, Clone)]
+enum Matcher {
+    Exact(Vec<String>),
+    Prefix(Vec<String>),
+}
+
+/// In-memory transport answering commands from canned outputs
+///
+/// Responses are matched against [`CliCommand::args`]; global arguments and
+/// environment are ignored. An exact match wins over a prefix match, and
+/// among prefix matches the longest prefix wins. Commands without a scripted
+/// response fail with `Error::Unexpected`.
+///
+/// Every executed command is recorded and can be inspected with
+/// [`ScriptedTransport::calls`]. Clones share the same call log.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedTransport {
+    responses: Vec<(Matcher, CliOutput)>,
+    calls: Arc<Mutex<Vec<CliCommand>>>,
+}
+
+impl ScriptedTransport {
+    /// Create an empty script
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Respond with `output` when the command arguments equal `args`
+    #[must_use]
+    pub fn on<I, S>(mut self, args: I, output: CliOutput) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let args = args.into_iter().map(Into::into).collect();
+        self.responses.push((Matcher::Exact(args), output));
+        self
+    }
+
+    /// Respond with `output` when the command arguments start with `prefix`
+    ///
+    /// Useful for commands carrying a `--params` JSON payload.
+    #[must_use]
+    pub fn on_prefix<I, S>(mut self, prefix: I, output: CliOutput) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let prefix = prefix.into_iter().map(Into::into).collect();
+        self.responses.push((Matcher::Prefix(prefix), output));
+        self
+    }
+
+    /// Commands executed so far, in order
+    #[must_use]
+    pub fn calls(&self) -> Vec<CliCommand> {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Find the scripted output for `args`
+    fn lookup(&self, args: &[String]) -> Option<&CliOutput> {
+        let exact = self
+            .responses
+            .iter()
+            .find_map(|(matcher, output)| match matcher {
+                Matcher::Exact(expected) if expected.as_slice() == args => Some(output),
+                _ => None,
+            });
+        exact.or_else(|| {
+            self.responses
+                .iter()
+                .filter_map(|(matcher, output)| match matcher {
+                    Matcher::Prefix(prefix) if args.starts_with(prefix) => {
+                        Some((prefix.len(), output))
+                    }
+                    _ => None,
+                })
+                .max_by_key(|(len, _)| *len)
+                .map(|(_, output)| output)
+        })
+    }
+}
+
+impl CliTransport for ScriptedTransport {
+    fn execute(&self, command: &CliCommand) -> Result<CliOutput> {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(command.clone());
+
+        self.lookup(&command.args).cloned().ok_or_else(|| {
+            Error::unexpected(format!(
+                "No scripted response for `{}`",
+                command.args.join(" ")
+            ))
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncCliTransport for ScriptedTransport {
+    async fn execute(&self, command: &CliCommand) -> Result<CliOutput> {
+        CliTransport::execute(self, command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ClientConfig, Network};
+    use crate::types::transaction::GetTransactionsParams;
+    use crate::types::wallet::LoginCredentials;
+
+    #[test]
+    fn test_build_command_applies_config() {
+        let config = ClientConfig::builder()
+            .binary("/opt/green/green-cli")
+            .network(Network::LiquidTestnet)
+            .config_dir("/tmp/wallet-a")
+            .env("GREEN_TEST_VAR", "1")
+            .global_arg("--tor")
+            .build();
+
+        let prepared =
+            ProcessTransport::build_command(&config.command(&["get", "balance", "--json"]))
+                .unwrap();
+        let command = prepared.process;
+
+        assert_eq!(command.get_program(), "/opt/green/green-cli");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(
+            args,
+            [
+                "--network",
+                "testnet-liquid",
+                "--config-dir",
+                "/tmp/wallet-a",
+                "--tor",
+                "get",
+                "balance",
+                "--json"
+            ]
+        );
+        let envs: Vec<_> = command.get_envs().collect();
+        assert_eq!(
+            envs,
+            [(
+                std::ffi::OsStr::new("GREEN_TEST_VAR"),
+                Some(std::ffi::OsStr::new("1"))
+            )]
+        );
+    }
+
+    #[test]
+    fn test_build_command_default_config() {
+        let command =
+            ProcessTransport::build_command(&ClientConfig::default().command(&["--help"]))
+                .unwrap()
+                .process;
+
+        assert_eq!(command.get_program(), "green-cli");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), ["--help"]);
+        assert_eq!(command.get_envs().count(), 0);
+    }
+
+    #[test]
+    fn test_build_command_params_modes() {
+        let params = GetTransactionsParams {
+            count: Some(5),
+            ..Default::default()
+        };
+        let command = |mode| {
+            let config = ClientConfig::builder().params_mode(mode).build();
+            let command = config
+                .command_with_params(&["get", "transactions"], &params)
+                .unwrap();
+            ProcessTransport::build_command(&command).unwrap()
+        };
+
+        let argv = command(ParamsMode::Argv);
+        let args: Vec<_> = argv.process.get_args().collect();
+        assert_eq!(args, ["get", "transactions", "--params", r#"{"count":5}"#]);
+        assert!(argv.stdin.is_none() && argv.params_file.is_none());
+
+        let stdin = command(ParamsMode::Stdin);
+        assert_eq!(stdin.process.get_args().last().unwrap(), "-");
+        assert_eq!(stdin.stdin.as_deref(), Some(br#"{"count":5}"#.as_slice()));
+
+        let file = command(ParamsMode::File);
+        let path = file.params_file.as_ref().unwrap().path();
+        assert_eq!(file.process.get_args().last().unwrap(), path.as_os_str());
+        assert_eq!(std::fs::read_to_string(path).unwrap(), r#"{"count":5}"#);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_sensitive_params_are_redacted() {
+        let credentials = LoginCredentials {
+            mnemonic: None,
+            pin: Some("8642".to_string()),
+            password: None,
+            watch_only: None,
+        };
+        let command = ClientConfig::default()
+            .command_with_params(&["login"], &credentials)
+            .unwrap();
+
+        assert_eq!(command.effective_params_mode(), ParamsMode::File);
+        assert!(!format!("{command:?}").contains("8642"));
+
+        let result = command.finish(CliOutput::failure(1, "Error: invalid PIN 8642"));
+        match result {
+            Err(Error::Cli(err)) => {
+                assert_eq!(err.message, "invalid PIN <redacted>");
+                assert_eq!(err.kind, crate::ErrorKind::InvalidCredentials);
+            }
+            other => panic!("Expected CLI error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_scripted_lookup_prefers_exact_then_longest_prefix() {
+        let transport = ScriptedTransport::new()
+            .on_prefix(["get"], CliOutput::success("get"))
+            .on_prefix(["get", "utxos"], CliOutput::success("utxos"))
+            .on(["get", "utxos", "--json"], CliOutput::success("exact"));
+
+        let run = |args: &[&str]| {
+            CliTransport::execute(&transport, &CliCommand::new("green-cli", args))
+                .unwrap()
+                .stdout
+        };
+
+        assert_eq!(run(&["get", "utxos", "--json"]), "exact");
+        assert_eq!(run(&["get", "utxos", "--params", "{}"]), "utxos");
+        assert_eq!(run(&["get", "balance"]), "get");
+        assert_eq!(transport.calls().len(), 3);
+    }
+
+    #[test]
+    fn test_scripted_unknown_command() {
+        let transport = ScriptedTransport::new();
+        let result = CliTransport::execute(&transport, &CliCommand::new("green-cli", &["status"]));
+
+        match result {
+            Err(Error::Unexpected(msg)) => assert!(msg.contains("status")),
+            other => panic!("Expected unexpected error, got {:?}", other),
+        }
+    }
+}
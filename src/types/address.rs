@@ -1,7 +1,96 @@
 //! Address-related types for the Green API
 
 use super::common::{Address, Pointer, Script};
-use serde::{Deserialize, Serialize};
+use crate::address::PaymentUri;
+use crate::error::Result;
+use crate::transport::CliParams;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Type of a wallet address, spelled as green-cli does
+///
+/// Types this crate does not know yet are kept as [`AddressType::Unknown`]
+/// so they round-trip unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AddressType {
+    /// Green 2of2 multisig with a CSV recovery path (`csv`)
+    Csv,
+    /// Segwit multisig without a CSV path (`p2wsh`)
+    P2wsh,
+    /// Legacy multisig (`p2sh`)
+    P2sh,
+    /// Wrapped segwit single-sig (`p2sh-p2wpkh`)
+    P2shP2wpkh,
+    /// Native segwit single-sig (`p2wpkh`)
+    P2wpkh,
+    /// Legacy single-sig (`p2pkh`)
+    P2pkh,
+    /// Taproot single-sig (`p2tr`)
+    P2tr,
+    /// Any other type reported by green-cli
+    Unknown(String),
+}
+
+impl AddressType {
+    /// Name of the type as understood by green-cli
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Csv => "csv",
+            Self::P2wsh => "p2wsh",
+            Self::P2sh => "p2sh",
+            Self::P2shP2wpkh => "p2sh-p2wpkh",
+            Self::P2wpkh => "p2wpkh",
+            Self::P2pkh => "p2pkh",
+            Self::P2tr => "p2tr",
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+impl fmt::Display for AddressType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for AddressType {
+    fn from(s: &str) -> Self {
+        match s {
+            "csv" => Self::Csv,
+            "p2wsh" => Self::P2wsh,
+            "p2sh" => Self::P2sh,
+            "p2sh-p2wpkh" => Self::P2shP2wpkh,
+            "p2wpkh" => Self::P2wpkh,
+            "p2pkh" => Self::P2pkh,
+            "p2tr" => Self::P2tr,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl FromStr for AddressType {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+impl Serialize for AddressType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AddressType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Self::from(name.as_str()))
+    }
+}
 
 /// Receive address response from the API
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -10,9 +99,9 @@ pub struct ReceiveAddress {
     pub address: Address,
     /// Subaccount pointer
     pub pointer: Pointer,
-    /// Address type (e.g., "p2sh-p2wpkh", "p2wpkh", etc.)
+    /// Address type
     #[serde(rename = "address_type")]
-    pub address_type: String,
+    pub address_type: AddressType,
     /// Branch (0 for external, 1 for internal/change)
     pub branch: u32,
     /// Subaccount index
@@ -28,6 +117,18 @@ pub struct ReceiveAddress {
     pub unconfidential_address: Option<Address>,
 }
 
+impl ReceiveAddress {
+    /// Payment URI for the address, to add an amount, label or message to
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidAddress`](crate::ErrorKind::InvalidAddress)
+    /// validation error if the address is malformed
+    pub fn payment_uri(&self) -> Result<PaymentUri> {
+        PaymentUri::new(&self.address)
+    }
+}
+
 /// Request to generate a new receive address
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GetReceiveAddressRequest {
@@ -36,9 +137,11 @@ pub struct GetReceiveAddressRequest {
     pub subaccount: Option<u32>,
     /// Address type to generate
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub address_type: Option<String>,
+    pub address_type: Option<AddressType>,
 }
 
+impl CliParams for GetReceiveAddressRequest {}
+
 /// Previous addresses query parameters
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GetPreviousAddressesRequest {
@@ -53,6 +156,36 @@ pub struct GetPreviousAddressesRequest {
     pub unused_only: Option<bool>,
 }
 
+impl CliParams for GetPreviousAddressesRequest {}
+
+/// Request to update one of the wallet's receive addresses
+///
+/// Fields left as `None` are not changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateAddressRequest {
+    /// Subaccount of the address
+    pub subaccount: u32,
+    /// Pointer of the address
+    pub pointer: Pointer,
+    /// User-defined label; empty removes it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl UpdateAddressRequest {
+    /// Request to set the label of an address
+    #[must_use]
+    pub fn label(subaccount: u32, pointer: Pointer, label: impl Into<String>) -> Self {
+        Self {
+            subaccount,
+            pointer,
+            label: Some(label.into()),
+        }
+    }
+}
+
+impl CliParams for UpdateAddressRequest {}
+
 /// Address details
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AddressDetails {
@@ -60,7 +193,7 @@ pub struct AddressDetails {
     pub address: Address,
     /// Address type
     #[serde(rename = "address_type")]
-    pub address_type: String,
+    pub address_type: AddressType,
     /// Subaccount
     pub subaccount: u32,
     /// Pointer
@@ -75,3 +208,158 @@ pub struct AddressDetails {
     #[serde(default)]
     pub is_used: bool,
 }
+
+/// Consecutive unused addresses a wallet scans before recovery stops looking
+pub const DEFAULT_GAP_LIMIT: u32 = 20;
+
+/// Gap limit an address usage report is checked against
+///
+/// Wallet recovery stops at the first run of `limit` unused addresses, so
+/// funds sent to an address past such a run are not found on restore.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GapLimitPolicy {
+    /// Gap limit of the wallets used for recovery
+    pub limit: u32,
+    /// Gap from which a warning is raised
+    pub warn_at: u32,
+}
+
+impl GapLimitPolicy {
+    /// Create a policy warning once the gap is three quarters of `limit`
+    #[must_use]
+    pub const fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            warn_at: limit - limit / 4,
+        }
+    }
+
+    /// Warn once the gap reaches `warn_at`
+    #[must_use]
+    pub const fn warn_at(mut self, warn_at: u32) -> Self {
+        self.warn_at = warn_at;
+        self
+    }
+}
+
+impl Default for GapLimitPolicy {
+    fn default() -> Self {
+        Self::new(DEFAULT_GAP_LIMIT)
+    }
+}
+
+/// Warning that the unused address gap endangers wallet recovery
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GapWarning {
+    /// The gap is close to the limit
+    Approaching {
+        /// Unused addresses after the last used one
+        gap: u32,
+        /// Gap limit
+        limit: u32,
+    },
+    /// The gap reached the limit; addresses past it are missed on recovery
+    Reached {
+        /// Unused addresses after the last used one
+        gap: u32,
+        /// Gap limit
+        limit: u32,
+    },
+}
+
+impl fmt::Display for GapWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Approaching { gap, limit } => write!(
+                f,
+                "{gap} unused addresses after the last used one, approaching the gap limit of {limit}"
+            ),
+            Self::Reached { gap, limit } => write!(
+                f,
+                "{gap} unused addresses after the last used one reach the gap limit of {limit}; \
+                 funds sent to newer addresses are not found on recovery"
+            ),
+        }
+    }
+}
+
+/// Usage of a subaccount's receive addresses
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AddressUsageReport {
+    /// Subaccount the addresses belong to
+    pub subaccount: u32,
+    /// Policy the gap was checked against
+    pub policy: GapLimitPolicy,
+    /// Addresses generated so far
+    pub address_count: u32,
+    /// Addresses that received a transaction
+    pub used_count: u32,
+    /// Addresses that never received a transaction
+    pub unused_count: u32,
+    /// Highest pointer generated
+    pub last_pointer: Option<Pointer>,
+    /// Highest pointer that received a transaction
+    pub last_used_pointer: Option<Pointer>,
+    /// Addresses generated after the last used one
+    pub gap: u32,
+    /// Addresses that received more than one transaction, newest first
+    pub reused: Vec<AddressDetails>,
+    /// Warning if the gap approaches or reached the limit
+    pub gap_warning: Option<GapWarning>,
+}
+
+impl AddressUsageReport {
+    /// Summarize the addresses of `subaccount`, as returned by
+    /// `get_previous_addresses`
+    #[must_use]
+    pub fn new(subaccount: u32, addresses: &[AddressDetails], policy: GapLimitPolicy) -> Self {
+        let count = |filter: &dyn Fn(&AddressDetails) -> bool| {
+            u32::try_from(addresses.iter().filter(|details| filter(details)).count())
+                .unwrap_or(u32::MAX)
+        };
+        let is_used = |details: &AddressDetails| details.is_used || details.tx_count > 0;
+
+        let last_used_pointer = addresses
+            .iter()
+            .filter(|details| is_used(details))
+            .map(|details| details.pointer)
+            .max();
+        let gap = count(&|details| last_used_pointer.is_none_or(|last| details.pointer > last));
+        let gap_warning = if gap >= policy.limit {
+            Some(GapWarning::Reached {
+                gap,
+                limit: policy.limit,
+            })
+        } else if gap >= policy.warn_at {
+            Some(GapWarning::Approaching {
+                gap,
+                limit: policy.limit,
+            })
+        } else {
+            None
+        };
+
+        let mut reused: Vec<AddressDetails> = addresses
+            .iter()
+            .filter(|details| details.tx_count > 1)
+            .cloned()
+            .collect();
+        reused.sort_by_key(|details| std::cmp::Reverse(details.pointer));
+
+        let address_count = u32::try_from(addresses.len()).unwrap_or(u32::MAX);
+        let used_count = count(&is_used);
+        Self {
+            subaccount,
+            policy,
+            address_count,
+            used_count,
+            unused_count: address_count - used_count,
+            last_pointer: addresses.iter().map(|details| details.pointer).max(),
+            last_used_pointer,
+            gap,
+            reused,
+            gap_warning,
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Fee rate types for the Green API
+
+use super::common::Satoshis;
+use crate::config::Network;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// A fee rate, stored in satoshis per 1000 virtual bytes
+///
+/// Bitcoin rates are usually quoted in sat/vB and Liquid rates are often a
+/// fraction of one, so the rate is kept in sat/kvB to represent both
+/// exactly. It serializes as a number of sat/vB, which is what green-cli
+/// expects: a whole number where possible, with up to three decimals
+/// otherwise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FeeRate(u64);
+
+impl FeeRate {
+    /// No fee at all
+    pub const ZERO: Self = Self(0);
+
+    /// Default minimum relay fee rate of Bitcoin nodes, 1 sat/vB
+    pub const BITCOIN_MIN_RELAY: Self = Self(1_000);
+
+    /// Default minimum relay fee rate of Liquid nodes, 0.1 sat/vB
+    pub const LIQUID_MIN_RELAY: Self = Self(100);
+
+    /// Fee rate of `sat_per_vb` satoshis per virtual byte
+    #[must_use]
+    pub const fn from_sat_per_vb(sat_per_vb: u64) -> Self {
+        Self(sat_per_vb.saturating_mul(1_000))
+    }
+
+    /// Fee rate of `sat_per_kvb` satoshis per 1000 virtual bytes
+    #[must_use]
+    pub const fn from_sat_per_kvb(sat_per_kvb: u64) -> Self {
+        Self(sat_per_kvb)
+    }
+
+    /// Fee rate of `fee` paid for `vsize` virtual bytes, rounded down
+    ///
+    /// Returns [`FeeRate::ZERO`] for a zero `vsize`.
+    #[must_use]
+    pub fn from_fee_and_vsize(fee: Satoshis, vsize: u32) -> Self {
+        if vsize == 0 {
+            return Self::ZERO;
+        }
+        Self(fee.saturating_mul(1_000) / u64::from(vsize))
+    }
+
+    /// Minimum relay fee rate nodes of `network` accept by default
+    #[must_use]
+    pub const fn min_relay(network: Network) -> Self {
+        if network.is_liquid() {
+            Self::LIQUID_MIN_RELAY
+        } else {
+            Self::BITCOIN_MIN_RELAY
+        }
+    }
+
+    /// Rate in satoshis per 1000 virtual bytes
+    #[must_use]
+    pub const fn to_sat_per_kvb(self) -> u64 {
+        self.0
+    }
+
+    /// Rate in whole satoshis per virtual byte, rounded down
+    #[must_use]
+    pub const fn to_sat_per_vb_floor(self) -> u64 {
+        self.0 / 1_000
+    }
+
+    /// Rate in whole satoshis per virtual byte, rounded up
+    #[must_use]
+    pub const fn to_sat_per_vb_ceil(self) -> u64 {
+        self.0.div_ceil(1_000)
+    }
+
+    /// Rate in satoshis per virtual byte
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn as_sat_per_vb(self) -> f64 {
+        self.0 as f64 / 1_000.0
+    }
+
+    /// Fee for `vsize` virtual bytes at this rate, rounded up
+    #[must_use]
+    pub fn fee_for_vsize(self, vsize: u32) -> Satoshis {
+        self.0.saturating_mul(u64::from(vsize)).div_ceil(1_000)
+    }
+
+    /// Sum of two rates
+    #[must_use]
+    pub const fn saturating_add(self, other: Self) -> Self {
+        Self(self.0.saturating_add(other.0))
+    }
+}
+
+impl fmt::Display for FeeRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (whole, thousandths) = (self.0 / 1_000, self.0 % 1_000);
+        if thousandths == 0 {
+            return write!(f, "{whole} sat/vB");
+        }
+        let decimals = format!("{thousandths:03}");
+        write!(f, "{whole}.{} sat/vB", decimals.trim_end_matches('0'))
+    }
+}
+
+impl Serialize for FeeRate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0.is_multiple_of(1_000) {
+            serializer.serialize_u64(self.to_sat_per_vb_floor())
+        } else {
+            serializer.serialize_f64(self.as_sat_per_vb())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FeeRate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let sat_per_vb = f64::deserialize(deserializer)?;
+        if !sat_per_vb.is_finite() || sat_per_vb < 0.0 {
+            return Err(serde::de::Error::custom(format!(
+                "invalid fee rate {sat_per_vb} sat/vB"
+            )));
+        }
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let kvb = (sat_per_vb * 1_000.0).round() as u64;
+        Ok(Self(kvb))
+    }
+}
+
+/// How urgently a transaction should confirm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FeePriority {
+    /// Next block
+    Fast,
+    /// Within about an hour (6 blocks)
+    #[default]
+    Normal,
+    /// Within about a day (144 blocks)
+    Slow,
+    /// Lowest rate nodes will relay
+    Minimum,
+}
+
+impl FeePriority {
+    /// Confirmation target in blocks, or `None` for [`FeePriority::Minimum`]
+    #[must_use]
+    pub const fn target_blocks(self) -> Option<u32> {
+        match self {
+            Self::Fast => Some(1),
+            Self::Normal => Some(6),
+            Self::Slow => Some(144),
+            Self::Minimum => None,
+        }
+    }
+}
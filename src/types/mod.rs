@@ -6,16 +6,18 @@ pub mod common;
 // API-specific types
 pub mod address;
 pub mod balance;
+pub mod fee;
 pub mod subaccount;
 pub mod transaction;
 pub mod utxo;
 pub mod wallet;
 
 // Re-export commonly used types
-pub use address::{AddressDetails, ReceiveAddress};
+pub use address::{AddressDetails, AddressType, ReceiveAddress};
 pub use balance::Balance;
 pub use common::*;
-pub use subaccount::Subaccount;
-pub use transaction::{Transaction, TxInput, TxOutput};
+pub use fee::{FeePriority, FeeRate};
+pub use subaccount::{Subaccount, SubaccountType};
+pub use transaction::{FeeBump, Transaction, TransactionFilter, TxDirection, TxInput, TxOutput};
 pub use utxo::{GetUnspentOutputsParams, UnspentOutput, UnspentOutputs, UtxoDetails, UtxoSortBy};
-pub use wallet::{FeeEstimates, NetworkInfo, WalletInfo};
+pub use wallet::{CreateWalletRequest, FeeEstimates, LoginCredentials, NetworkInfo, WalletInfo};
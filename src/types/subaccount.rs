@@ -1,8 +1,127 @@
 //! Subaccount-related types for the Green API
 
+use super::address::AddressType;
 use super::common::{AssetId, Pointer, Satoshis};
-use serde::{Deserialize, Serialize};
+use crate::transport::CliParams;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Type of a subaccount, spelled as green-cli does
+///
+/// Types this crate does not know yet are kept as [`SubaccountType::Unknown`]
+/// so they round-trip unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SubaccountType {
+    /// Green 2of2 multisig with a CSV recovery path (`2of2`)
+    TwoOfTwo,
+    /// Green 2of2 multisig without a recovery path (`2of2_no_recovery`)
+    TwoOfTwoNoRecovery,
+    /// 2of3 multisig with a recovery key (`2of3`)
+    TwoOfThree,
+    /// Wrapped segwit single-sig (`p2sh-p2wpkh`)
+    P2shP2wpkh,
+    /// Native segwit single-sig (`p2wpkh`)
+    P2wpkh,
+    /// Legacy single-sig (`p2pkh`)
+    P2pkh,
+    /// Taproot single-sig (`p2tr`)
+    P2tr,
+    /// Any other type reported by green-cli
+    Unknown(String),
+}
+
+impl SubaccountType {
+    /// Name of the type as understood by green-cli
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::TwoOfTwo => "2of2",
+            Self::TwoOfTwoNoRecovery => "2of2_no_recovery",
+            Self::TwoOfThree => "2of3",
+            Self::P2shP2wpkh => "p2sh-p2wpkh",
+            Self::P2wpkh => "p2wpkh",
+            Self::P2pkh => "p2pkh",
+            Self::P2tr => "p2tr",
+            Self::Unknown(other) => other,
+        }
+    }
+
+    /// Whether this is a 2of2 subaccount, with or without recovery
+    #[must_use]
+    pub const fn is_two_of_two(&self) -> bool {
+        matches!(self, Self::TwoOfTwo | Self::TwoOfTwoNoRecovery)
+    }
+
+    /// Whether this is a multisig subaccount
+    #[must_use]
+    pub const fn is_multisig(&self) -> bool {
+        matches!(
+            self,
+            Self::TwoOfTwo | Self::TwoOfTwoNoRecovery | Self::TwoOfThree
+        )
+    }
+
+    /// Type of the addresses the subaccount generates by default
+    ///
+    /// `None` for an unknown subaccount type.
+    #[must_use]
+    pub const fn default_address_type(&self) -> Option<AddressType> {
+        match self {
+            Self::TwoOfTwo => Some(AddressType::Csv),
+            Self::TwoOfTwoNoRecovery | Self::TwoOfThree => Some(AddressType::P2wsh),
+            Self::P2shP2wpkh => Some(AddressType::P2shP2wpkh),
+            Self::P2wpkh => Some(AddressType::P2wpkh),
+            Self::P2pkh => Some(AddressType::P2pkh),
+            Self::P2tr => Some(AddressType::P2tr),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for SubaccountType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for SubaccountType {
+    fn from(s: &str) -> Self {
+        match s {
+            "2of2" => Self::TwoOfTwo,
+            "2of2_no_recovery" => Self::TwoOfTwoNoRecovery,
+            "2of3" => Self::TwoOfThree,
+            "p2sh-p2wpkh" => Self::P2shP2wpkh,
+            "p2wpkh" => Self::P2wpkh,
+            "p2pkh" => Self::P2pkh,
+            "p2tr" => Self::P2tr,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl FromStr for SubaccountType {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+impl Serialize for SubaccountType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SubaccountType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Self::from(name.as_str()))
+    }
+}
 
 /// Subaccount information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,9 +130,9 @@ pub struct Subaccount {
     pub pointer: Pointer,
     /// Subaccount name
     pub name: String,
-    /// Subaccount type (e.g., "2of2", "2of3", "`2of2_no_recovery`")
+    /// Subaccount type
     #[serde(rename = "type")]
-    pub subaccount_type: String,
+    pub subaccount_type: SubaccountType,
     /// Recovery mnemonic (if applicable)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recovery_mnemonic: Option<String>,
@@ -69,7 +188,7 @@ pub struct CreateSubaccountRequest {
     pub name: String,
     /// Subaccount type
     #[serde(rename = "type")]
-    pub subaccount_type: String,
+    pub subaccount_type: SubaccountType,
     /// Recovery mnemonic (for 2of3)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recovery_mnemonic: Option<String>,
@@ -78,6 +197,12 @@ pub struct CreateSubaccountRequest {
     pub recovery_xpub: Option<String>,
 }
 
+impl CliParams for CreateSubaccountRequest {
+    fn is_sensitive(&self) -> bool {
+        self.recovery_mnemonic.is_some()
+    }
+}
+
 /// Create subaccount parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateSubaccountParams {
@@ -85,7 +210,7 @@ pub struct CreateSubaccountParams {
     pub name: String,
     /// Subaccount type
     #[serde(rename = "type")]
-    pub subaccount_type: String,
+    pub subaccount_type: SubaccountType,
     /// Recovery mnemonic (for 2of3)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recovery_mnemonic: Option<String>,
@@ -94,6 +219,12 @@ pub struct CreateSubaccountParams {
     pub recovery_xpub: Option<String>,
 }
 
+impl CliParams for CreateSubaccountParams {
+    fn is_sensitive(&self) -> bool {
+        self.recovery_mnemonic.is_some()
+    }
+}
+
 /// Update subaccount request
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct UpdateSubaccountRequest {
@@ -107,6 +238,8 @@ pub struct UpdateSubaccountRequest {
     pub hidden: Option<bool>,
 }
 
+impl CliParams for UpdateSubaccountRequest {}
+
 /// Update subaccount parameters
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct UpdateSubaccountParams {
@@ -118,6 +251,8 @@ pub struct UpdateSubaccountParams {
     pub hidden: Option<bool>,
 }
 
+impl CliParams for UpdateSubaccountParams {}
+
 /// Subaccount list
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubaccountList {
@@ -157,7 +292,7 @@ pub struct Utxo {
     pub block_height: Option<u32>,
     /// Address type
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub address_type: Option<String>,
+    pub address_type: Option<AddressType>,
     /// Subaccount
     pub subaccount: Pointer,
     /// Pointer
@@ -1,6 +1,8 @@
 //! Transaction-related types for the Green API
 
 use super::common::{Address, AssetId, BlockHeight, Satoshis, Script, TxId};
+use super::fee::FeeRate;
+use crate::transport::CliParams;
 use serde::{Deserialize, Serialize};
 
 /// Transaction input
@@ -63,7 +65,7 @@ pub struct TxOutput {
 }
 
 /// Full transaction details
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Transaction {
     /// Transaction ID
     pub txid: TxId,
@@ -87,9 +89,9 @@ pub struct Transaction {
     /// Fee paid
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fee: Option<Satoshis>,
-    /// Fee rate in satoshis per vbyte
+    /// Fee rate paid
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub fee_rate: Option<f64>,
+    pub fee_rate: Option<FeeRate>,
     /// Block hash this transaction is included in
     #[serde(skip_serializing_if = "Option::is_none")]
     pub block_hash: Option<String>,
@@ -122,6 +124,23 @@ pub struct Transaction {
     pub hex: Option<String>,
 }
 
+impl Transaction {
+    /// Whether the transaction opts in to replacement under BIP125
+    ///
+    /// True if the wallet reports it as replaceable or any input has a
+    /// sequence number below `0xfffffffe`.
+    #[must_use]
+    pub fn signals_rbf(&self) -> bool {
+        self.can_rbf || self.inputs.iter().any(|input| input.sequence < 0xffff_fffe)
+    }
+
+    /// Whether any input spends an output of the wallet
+    #[must_use]
+    pub fn spends_wallet_outputs(&self) -> bool {
+        self.inputs.iter().any(|input| input.is_relevant)
+    }
+}
+
 /// Transaction list response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionList {
@@ -135,15 +154,187 @@ pub struct TransactionList {
     pub next_page: Option<String>,
 }
 
+/// Transaction list query parameters
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GetTransactionsParams {
+    /// Subaccount to query (None for all)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subaccount: Option<u32>,
+    /// Index of the first transaction to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first: Option<u32>,
+    /// Maximum number of transactions to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+}
+
+impl CliParams for GetTransactionsParams {}
+
+/// Which way a transaction moves funds, from the wallet's point of view
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TxDirection {
+    /// Funds received from outside the wallet
+    Incoming,
+    /// Funds sent out of the wallet
+    Outgoing,
+    /// Funds moved between the wallet's own addresses
+    Redeposit,
+}
+
+impl TxDirection {
+    /// Direction of `tx`
+    ///
+    /// Uses the transaction type reported by green-cli, or works it out from
+    /// which inputs and outputs belong to the wallet if there is none.
+    #[must_use]
+    pub fn of(tx: &Transaction) -> Self {
+        match tx.tx_type.as_deref() {
+            Some("incoming") => Self::Incoming,
+            Some("outgoing") => Self::Outgoing,
+            Some("redeposit") => Self::Redeposit,
+            _ if !tx.inputs.iter().any(|input| input.is_relevant) => Self::Incoming,
+            _ if tx.outputs.iter().all(|output| output.is_relevant) => Self::Redeposit,
+            _ => Self::Outgoing,
+        }
+    }
+}
+
+/// How far a block's timestamp may trail the blocks before it, in seconds
+///
+/// Consensus only requires a block's time to exceed the median of the eleven
+/// before it, and allows it up to two hours ahead, so confirmed history is
+/// not strictly ordered by time.
+pub const BLOCK_TIME_MARGIN: u64 = 2 * 60 * 60;
+
+/// Criteria for walking transaction history page by page
+///
+/// Only the subaccount is passed to green-cli; the other criteria are applied
+/// to each page as it arrives. Timestamps are Unix seconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct TransactionFilter {
+    /// Subaccount to list (None for all)
+    pub subaccount: Option<u32>,
+    /// Earliest timestamp to include
+    pub since: Option<u64>,
+    /// Latest timestamp to include
+    pub until: Option<u64>,
+    /// Only transactions with an input or output of this asset (`"btc"` for bitcoin)
+    pub asset_id: Option<AssetId>,
+    /// Only transactions moving funds this way
+    pub direction: Option<TxDirection>,
+    /// Number of transactions fetched per page
+    pub page_size: Option<u32>,
+}
+
+impl TransactionFilter {
+    /// Filter matching every transaction
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only list `subaccount`
+    #[allow(clippy::missing_const_for_fn)] // Cannot be const due to Option::Some
+    pub fn subaccount(mut self, subaccount: u32) -> Self {
+        self.subaccount = Some(subaccount);
+        self
+    }
+
+    /// Skip transactions before `timestamp`
+    #[allow(clippy::missing_const_for_fn)] // Cannot be const due to Option::Some
+    pub fn since(mut self, timestamp: u64) -> Self {
+        self.since = Some(timestamp);
+        self
+    }
+
+    /// Skip transactions after `timestamp`
+    #[allow(clippy::missing_const_for_fn)] // Cannot be const due to Option::Some
+    pub fn until(mut self, timestamp: u64) -> Self {
+        self.until = Some(timestamp);
+        self
+    }
+
+    /// Only transactions moving `asset_id`
+    pub fn asset(mut self, asset_id: impl Into<AssetId>) -> Self {
+        self.asset_id = Some(asset_id.into());
+        self
+    }
+
+    /// Only transactions moving funds in `direction`
+    #[allow(clippy::missing_const_for_fn)] // Cannot be const due to Option::Some
+    pub fn direction(mut self, direction: TxDirection) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Fetch `page_size` transactions per green-cli call
+    #[allow(clippy::missing_const_for_fn)] // Cannot be const due to Option::Some
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Whether `tx` meets every criterion
+    ///
+    /// Transactions without a timestamp never match a time range.
+    #[must_use]
+    pub fn matches(&self, tx: &Transaction) -> bool {
+        let in_range = tx.timestamp.map_or_else(
+            || self.since.is_none() && self.until.is_none(),
+            |time| {
+                self.since.is_none_or(|since| time >= since)
+                    && self.until.is_none_or(|until| time <= until)
+            },
+        );
+        in_range
+            && self
+                .subaccount
+                .is_none_or(|sub| tx.subaccounts.is_empty() || tx.subaccounts.contains(&sub))
+            && self
+                .direction
+                .is_none_or(|direction| TxDirection::of(tx) == direction)
+            && self
+                .asset_id
+                .as_deref()
+                .is_none_or(|asset| moves_asset(tx, asset))
+    }
+
+    /// Whether `tx` is confirmed more than [`BLOCK_TIME_MARGIN`] before the range
+    ///
+    /// History is listed newest first, so paging can stop once a whole page
+    /// is past the range.
+    #[must_use]
+    pub const fn is_past_range(&self, tx: &Transaction) -> bool {
+        tx.block_height.is_some()
+            && matches!(
+                (self.since, tx.timestamp),
+                (Some(since), Some(time)) if time.saturating_add(BLOCK_TIME_MARGIN) < since
+            )
+    }
+}
+
+fn moves_asset(tx: &Transaction, asset: &str) -> bool {
+    let is_asset = |asset_id: Option<&AssetId>| asset_id.map_or("btc", String::as_str) == asset;
+    tx.outputs
+        .iter()
+        .any(|output| is_asset(output.asset_id.as_ref()))
+        || tx
+            .inputs
+            .iter()
+            .filter_map(|input| input.prevout.as_ref())
+            .any(|prevout| is_asset(prevout.asset_id.as_ref()))
+}
+
 /// Create transaction request
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CreateTransactionRequest {
     /// Recipients: address -> amount mapping
     #[serde(skip_serializing_if = "Option::is_none")]
     pub addressees: Option<Vec<Addressee>>,
-    /// Fee rate in satoshis per vbyte
+    /// Fee rate to pay
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub fee_rate: Option<u64>,
+    pub fee_rate: Option<FeeRate>,
     /// Subaccount to send from
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subaccount: Option<u32>,
@@ -156,6 +347,28 @@ pub struct CreateTransactionRequest {
     /// UTXOs to use
     #[serde(skip_serializing_if = "Option::is_none")]
     pub utxos: Option<Vec<UtxoRef>>,
+    /// Unconfirmed transaction this one replaces (RBF fee bump)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_transaction: Option<Transaction>,
+}
+
+impl CliParams for CreateTransactionRequest {}
+
+/// Outcome of replacing a transaction with a higher fee one
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FeeBump {
+    /// Transaction that was replaced
+    pub old_txid: TxId,
+    /// Replacement transaction
+    pub new_txid: TxId,
+    /// Fee paid by the replaced transaction
+    pub old_fee: Satoshis,
+    /// Fee paid by the replacement
+    pub new_fee: Satoshis,
+    /// Fee rate requested for the replacement
+    pub fee_rate: FeeRate,
+    /// Replacement transaction as broadcast
+    pub transaction: Transaction,
 }
 
 /// Transaction recipient
@@ -1,7 +1,14 @@
 //! UTXO-related types for the Green API
 
+use super::address::AddressType;
 use super::common::{Address, AssetId, BlockHeight, Satoshis, Script, TxId};
+use super::fee::FeeRate;
+use super::wallet::WalletSettings;
+use crate::coin_selection::{BITCOIN_ASSET, DEFAULT_DUST_LIMIT};
+use crate::estimation::ScriptType;
+use crate::transport::CliParams;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// UTXO details
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -23,7 +30,7 @@ pub struct UtxoDetails {
     pub address: Option<Address>,
     /// Address type
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub address_type: Option<String>,
+    pub address_type: Option<AddressType>,
     /// Script pubkey
     #[serde(skip_serializing_if = "Option::is_none")]
     pub script_pubkey: Option<Script>,
@@ -65,6 +72,8 @@ pub struct GetUtxosRequest {
     pub sort_by_value: Option<bool>,
 }
 
+impl CliParams for GetUtxosRequest {}
+
 /// UTXOs response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UtxosResponse {
@@ -78,7 +87,9 @@ pub struct UtxosResponse {
     pub next: Option<String>,
 }
 
-/// Update UTXO request (for freezing/unfreezing)
+/// Update UTXO request (for freezing/unfreezing and memos)
+///
+/// Fields left as `None` are not changed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateUtxoRequest {
     /// Transaction hash
@@ -86,17 +97,80 @@ pub struct UpdateUtxoRequest {
     /// Output index
     pub vout: u32,
     /// Freeze status
-    pub is_frozen: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_frozen: Option<bool>,
     /// Optional memo
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memo: Option<String>,
 }
 
+impl UpdateUtxoRequest {
+    /// Request to freeze an output so it is never selected for spending
+    #[must_use]
+    pub fn freeze(txhash: impl Into<TxId>, vout: u32) -> Self {
+        Self {
+            txhash: txhash.into(),
+            vout,
+            is_frozen: Some(true),
+            memo: None,
+        }
+    }
+
+    /// Request to thaw a frozen output, making it spendable again
+    #[must_use]
+    pub fn thaw(txhash: impl Into<TxId>, vout: u32) -> Self {
+        Self {
+            txhash: txhash.into(),
+            vout,
+            is_frozen: Some(false),
+            memo: None,
+        }
+    }
+
+    /// Request to set the memo of an output
+    #[must_use]
+    pub fn memo(txhash: impl Into<TxId>, vout: u32, memo: impl Into<String>) -> Self {
+        Self {
+            txhash: txhash.into(),
+            vout,
+            is_frozen: None,
+            memo: Some(memo.into()),
+        }
+    }
+}
+
+impl CliParams for UpdateUtxoRequest {}
+
+/// Outcome of updating one output in a batch
+#[derive(Debug)]
+pub struct UtxoUpdateResult {
+    /// Transaction hash of the output
+    pub txhash: TxId,
+    /// Output index
+    pub vout: u32,
+    /// The updated output, or why the update failed
+    pub result: crate::Result<UnspentOutput>,
+}
+
+impl UtxoUpdateResult {
+    /// Whether the update succeeded
+    #[must_use]
+    pub const fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
 /// UTXO summary by asset
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Built by [`UtxoReport::new`] for each asset, across the wallet and per
+/// subaccount.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct UtxoSummary {
     /// Asset ID
     pub asset_id: AssetId,
+    /// Subaccount summarized, or `None` for the whole wallet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subaccount: Option<u32>,
     /// Number of UTXOs
     pub utxo_count: u32,
     /// Total amount
@@ -107,6 +181,210 @@ pub struct UtxoSummary {
     /// Total frozen amount
     #[serde(default)]
     pub frozen_satoshi: Satoshis,
+    /// Number of dust UTXOs
+    #[serde(default)]
+    pub dust_count: u32,
+    /// Total dust amount
+    #[serde(default)]
+    pub dust_satoshi: Satoshis,
+    /// UTXOs by confirmation depth, shallowest first
+    #[serde(default)]
+    pub confirmations: Vec<ConfirmationBucket>,
+}
+
+impl UtxoSummary {
+    fn empty(asset_id: AssetId, subaccount: Option<u32>) -> Self {
+        Self {
+            asset_id,
+            subaccount,
+            utxo_count: 0,
+            total_satoshi: 0,
+            frozen_count: 0,
+            frozen_satoshi: 0,
+            dust_count: 0,
+            dust_satoshi: 0,
+            confirmations: CONFIRMATION_BUCKETS
+                .windows(2)
+                .map(|range| ConfirmationBucket {
+                    min_confirmations: range[0],
+                    max_confirmations: Some(range[1] - 1),
+                    utxo_count: 0,
+                    total_satoshi: 0,
+                })
+                .chain(std::iter::once(ConfirmationBucket {
+                    min_confirmations: CONFIRMATION_BUCKETS[CONFIRMATION_BUCKETS.len() - 1],
+                    max_confirmations: None,
+                    utxo_count: 0,
+                    total_satoshi: 0,
+                }))
+                .collect(),
+        }
+    }
+
+    fn add(&mut self, utxo: &UnspentOutput, is_dust: bool) {
+        self.utxo_count += 1;
+        self.total_satoshi += utxo.satoshi;
+        if utxo.is_frozen {
+            self.frozen_count += 1;
+            self.frozen_satoshi += utxo.satoshi;
+        }
+        if is_dust {
+            self.dust_count += 1;
+            self.dust_satoshi += utxo.satoshi;
+        }
+        let confirmations = utxo.confirmations.unwrap_or(0);
+        if let Some(bucket) = self
+            .confirmations
+            .iter_mut()
+            .rev()
+            .find(|bucket| confirmations >= bucket.min_confirmations)
+        {
+            bucket.utxo_count += 1;
+            bucket.total_satoshi += utxo.satoshi;
+        }
+    }
+}
+
+/// Lower bounds of the confirmation depth ranges in a [`UtxoSummary`]
+pub const CONFIRMATION_BUCKETS: [u32; 5] = [0, 1, 6, 100, 1000];
+
+/// UTXOs within a range of confirmation depths
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConfirmationBucket {
+    /// Fewest confirmations in the range
+    pub min_confirmations: u32,
+    /// Most confirmations in the range, or `None` if unbounded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_confirmations: Option<u32>,
+    /// Number of UTXOs in the range
+    pub utxo_count: u32,
+    /// Total amount in the range
+    pub total_satoshi: Satoshis,
+}
+
+/// Rules for deciding which outputs are dust
+///
+/// An output of the fee asset is dust if it is below the dust limit, or if
+/// spending it at the fee rate costs at least as much as it is worth. Other
+/// Liquid assets are never dust: their amounts cannot be compared with a
+/// limit and fee in the policy asset.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DustPolicy {
+    /// Smallest output that is not dust
+    pub dust_limit: Satoshis,
+    /// Fee rate spending costs are estimated at
+    pub fee_rate: FeeRate,
+    /// Key of the asset fees are paid in
+    #[serde(default = "default_fee_asset")]
+    pub fee_asset: AssetId,
+}
+
+fn default_fee_asset() -> AssetId {
+    BITCOIN_ASSET.to_string()
+}
+
+impl DustPolicy {
+    /// Create a policy from a dust limit and fee rate
+    #[must_use]
+    pub fn new(dust_limit: Satoshis, fee_rate: FeeRate) -> Self {
+        Self {
+            dust_limit,
+            fee_rate,
+            fee_asset: default_fee_asset(),
+        }
+    }
+
+    /// Set the key of the asset fees are paid in
+    ///
+    /// Defaults to `"btc"`. On Liquid, set it to the policy asset (L-BTC) ID.
+    #[must_use]
+    pub fn fee_asset(mut self, asset_id: impl Into<AssetId>) -> Self {
+        self.fee_asset = asset_id.into();
+        self
+    }
+
+    /// Create a policy from the wallet's dust limit
+    ///
+    /// Uses the default limit of 546 sat if the settings do not set one.
+    #[must_use]
+    pub fn from_settings(settings: &WalletSettings, fee_rate: FeeRate) -> Self {
+        Self::new(settings.dust_limit.unwrap_or(DEFAULT_DUST_LIMIT), fee_rate)
+    }
+
+    /// Estimated fee to spend `utxo` as an input
+    #[must_use]
+    pub fn spend_cost(&self, utxo: &UnspentOutput) -> Satoshis {
+        self.fee_rate
+            .fee_for_vsize(ScriptType::for_utxo(utxo).input_vsize())
+    }
+
+    /// Whether `utxo` is dust under this policy
+    ///
+    /// Always `false` for assets other than the fee asset.
+    #[must_use]
+    pub fn is_dust(&self, utxo: &UnspentOutput) -> bool {
+        let asset = utxo.asset_id.as_deref().unwrap_or(BITCOIN_ASSET);
+        asset == self.fee_asset
+            && (utxo.satoshi < self.dust_limit || utxo.satoshi <= self.spend_cost(utxo))
+    }
+}
+
+/// Summary of a wallet's unspent outputs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UtxoReport {
+    /// Policy dust was identified with
+    pub dust_policy: DustPolicy,
+    /// Summary of each asset across all subaccounts
+    pub by_asset: HashMap<AssetId, UtxoSummary>,
+    /// Summary of each asset in each subaccount
+    pub by_subaccount: HashMap<u32, HashMap<AssetId, UtxoSummary>>,
+    /// Outputs that are dust, smallest first
+    pub dust: Vec<UnspentOutput>,
+}
+
+impl UtxoReport {
+    /// Summarize outputs as returned by `get_unspent_outputs`
+    ///
+    /// Outputs without a confirmation count are counted as unconfirmed.
+    #[must_use]
+    pub fn new(utxos: &HashMap<AssetId, Vec<UnspentOutput>>, dust_policy: DustPolicy) -> Self {
+        let mut by_asset = HashMap::new();
+        let mut by_subaccount: HashMap<u32, HashMap<AssetId, UtxoSummary>> = HashMap::new();
+        let mut dust = Vec::new();
+
+        for (asset_id, outputs) in utxos {
+            for utxo in outputs {
+                let is_dust = dust_policy.is_dust(utxo);
+                by_asset
+                    .entry(asset_id.clone())
+                    .or_insert_with(|| UtxoSummary::empty(asset_id.clone(), None))
+                    .add(utxo, is_dust);
+                by_subaccount
+                    .entry(utxo.subaccount)
+                    .or_default()
+                    .entry(asset_id.clone())
+                    .or_insert_with(|| UtxoSummary::empty(asset_id.clone(), Some(utxo.subaccount)))
+                    .add(utxo, is_dust);
+                if is_dust {
+                    dust.push(utxo.clone());
+                }
+            }
+        }
+        dust.sort_by_key(|utxo| utxo.satoshi);
+
+        Self {
+            dust_policy,
+            by_asset,
+            by_subaccount,
+            dust,
+        }
+    }
+
+    /// Summary of `asset_id` in `subaccount`, if it holds any
+    #[must_use]
+    pub fn subaccount_summary(&self, subaccount: u32, asset_id: &str) -> Option<&UtxoSummary> {
+        self.by_subaccount.get(&subaccount)?.get(asset_id)
+    }
 }
 
 /// Unspent output representation
@@ -132,7 +410,7 @@ pub struct UnspentOutput {
     pub address: Option<Address>,
     /// Address type
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub address_type: Option<String>,
+    pub address_type: Option<AddressType>,
     /// Script pubkey
     #[serde(skip_serializing_if = "Option::is_none")]
     pub script_pubkey: Option<Script>,
@@ -194,6 +472,23 @@ pub enum UtxoSortBy {
     ConfirmationsDesc,
 }
 
+impl UtxoSortBy {
+    /// Sort `utxos` in place by this criterion
+    ///
+    /// Unconfirmed outputs have no block height or confirmation count and sort
+    /// before confirmed ones in ascending order.
+    pub fn sort(self, utxos: &mut [UnspentOutput]) {
+        match self {
+            Self::Value => utxos.sort_by_key(|u| u.satoshi),
+            Self::ValueDesc => utxos.sort_by_key(|u| std::cmp::Reverse(u.satoshi)),
+            Self::Age => utxos.sort_by_key(|u| u.block_height),
+            Self::AgeDesc => utxos.sort_by_key(|u| std::cmp::Reverse(u.block_height)),
+            Self::Confirmations => utxos.sort_by_key(|u| u.confirmations),
+            Self::ConfirmationsDesc => utxos.sort_by_key(|u| std::cmp::Reverse(u.confirmations)),
+        }
+    }
+}
+
 /// Collection of unspent outputs
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct UnspentOutputs {
@@ -232,3 +527,5 @@ pub struct GetUnspentOutputsParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_value: Option<Satoshis>,
 }
+
+impl CliParams for GetUnspentOutputsParams {}
@@ -1,8 +1,12 @@
 //! Wallet-related types for the Green API
 
 use super::common::{AssetId, Satoshis};
+use super::fee::{FeePriority, FeeRate};
+use crate::config::Network;
+use crate::transport::CliParams;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
 /// Wallet information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,7 +51,10 @@ pub struct NetworkInfo {
 }
 
 /// Login credentials
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// The `Debug` output hides every secret, and the credentials are marked
+/// sensitive so they are never passed to green-cli on the command line.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct LoginCredentials {
     /// Mnemonic phrase
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -63,6 +70,149 @@ pub struct LoginCredentials {
     pub watch_only: Option<WatchOnlyData>,
 }
 
+impl LoginCredentials {
+    /// Credentials for logging in with a mnemonic
+    #[must_use]
+    pub fn from_mnemonic(mnemonic: impl Into<String>) -> Self {
+        Self {
+            mnemonic: Some(mnemonic.into()),
+            pin: None,
+            password: None,
+            watch_only: None,
+        }
+    }
+
+    /// Credentials for logging in with the PIN set up on this device
+    #[must_use]
+    pub fn from_pin(pin: impl Into<String>) -> Self {
+        Self {
+            mnemonic: None,
+            pin: Some(pin.into()),
+            password: None,
+            watch_only: None,
+        }
+    }
+
+    /// Credentials for a watch-only login
+    #[must_use]
+    pub const fn from_watch_only(watch_only: WatchOnlyData) -> Self {
+        Self {
+            mnemonic: None,
+            pin: None,
+            password: None,
+            watch_only: Some(watch_only),
+        }
+    }
+
+    /// Set the password protecting the mnemonic
+    #[must_use]
+    pub fn with_password(mut self, password: impl Into<String>) -> Self {
+        self.password = Some(password.into());
+        self
+    }
+}
+
+/// Credentials always carry a mnemonic, PIN, password or watch-only secret
+impl CliParams for LoginCredentials {
+    fn is_sensitive(&self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for LoginCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let redact = |secret: &Option<String>| secret.as_ref().map(|_| "<redacted>");
+        f.debug_struct("LoginCredentials")
+            .field("mnemonic", &redact(&self.mnemonic))
+            .field("pin", &redact(&self.pin))
+            .field("password", &redact(&self.password))
+            .field(
+                "watch_only",
+                &self.watch_only.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
+/// Request to create a wallet from a mnemonic
+///
+/// Like [`LoginCredentials`], the request is sensitive and its `Debug` output
+/// hides the mnemonic and password.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CreateWalletRequest {
+    /// Mnemonic phrase the wallet is derived from
+    pub mnemonic: String,
+    /// Password protecting the mnemonic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Wallet name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl CreateWalletRequest {
+    /// Request to create a wallet from `mnemonic`
+    #[must_use]
+    pub fn new(mnemonic: impl Into<String>) -> Self {
+        Self {
+            mnemonic: mnemonic.into(),
+            password: None,
+            name: None,
+        }
+    }
+
+    /// Set the password protecting the mnemonic
+    #[must_use]
+    pub fn with_password(mut self, password: impl Into<String>) -> Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Set the wallet name
+    #[must_use]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+}
+
+impl CliParams for CreateWalletRequest {
+    fn is_sensitive(&self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for CreateWalletRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateWalletRequest")
+            .field("mnemonic", &"<redacted>")
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+/// Request to protect the logged-in wallet with a PIN
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SetPinRequest {
+    /// The new PIN
+    pub pin: String,
+}
+
+impl CliParams for SetPinRequest {
+    fn is_sensitive(&self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for SetPinRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SetPinRequest")
+            .field("pin", &"<redacted>")
+            .finish()
+    }
+}
+
 /// Watch-only wallet data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WatchOnlyData {
@@ -87,7 +237,7 @@ pub struct WalletSettings {
     pub rbf: Option<bool>,
     /// Default fee rate
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub default_fee_rate: Option<u64>,
+    pub default_fee_rate: Option<FeeRate>,
     /// Dust limit
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dust_limit: Option<Satoshis>,
@@ -99,8 +249,82 @@ pub struct WalletSettings {
 /// Fee estimates
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeeEstimates {
-    /// Fee rates indexed by number of blocks
-    pub fees: HashMap<u32, u64>,
+    /// Fee rates by confirmation target in blocks
+    ///
+    /// Target 0, when present, is the minimum relay fee rate.
+    pub fees: HashMap<u32, FeeRate>,
+}
+
+impl FeeEstimates {
+    /// Minimum relay fee rate reported with the estimates (target 0)
+    #[must_use]
+    pub fn min_relay_fee(&self) -> Option<FeeRate> {
+        self.fees.get(&0).copied()
+    }
+
+    /// Lowest fee rate a transaction on `network` should pay
+    ///
+    /// The reported minimum relay fee rate, or the network's default one if
+    /// none was reported.
+    #[must_use]
+    pub fn relay_floor(&self, network: Network) -> FeeRate {
+        self.min_relay_fee()
+            .unwrap_or_else(|| FeeRate::min_relay(network))
+    }
+
+    /// Fee rate to confirm within `target_blocks`
+    ///
+    /// Between two estimated targets the rate is interpolated linearly,
+    /// rounding up; outside the estimated range the nearest target's rate is
+    /// used. The result is never below the reported minimum relay fee rate.
+    /// Returns `None` if there are no estimates.
+    #[must_use]
+    pub fn for_target(&self, target_blocks: u32) -> Option<FeeRate> {
+        let by_target: BTreeMap<u32, u64> = self
+            .fees
+            .iter()
+            .filter(|(&blocks, _)| blocks > 0)
+            .map(|(&blocks, rate)| (blocks, rate.to_sat_per_kvb()))
+            .collect();
+        let below = by_target.range(..=target_blocks).next_back();
+        let above = by_target.range(target_blocks..).next();
+        let sat_per_kvb = match (below, above) {
+            (Some((&low, &low_rate)), Some((&high, &high_rate))) if low != high => {
+                interpolate(target_blocks, (low, low_rate), (high, high_rate))
+            }
+            (Some((_, &rate)), _) | (None, Some((_, &rate))) => rate,
+            (None, None) => return None,
+        };
+        let rate = FeeRate::from_sat_per_kvb(sat_per_kvb);
+        Some(self.min_relay_fee().map_or(rate, |floor| rate.max(floor)))
+    }
+
+    /// Fee rate for a priority preset on `network`
+    ///
+    /// Falls back to [`FeeEstimates::relay_floor`] when there are no
+    /// estimates, and never returns less than it.
+    #[must_use]
+    pub fn for_priority(&self, priority: FeePriority, network: Network) -> FeeRate {
+        let floor = self.relay_floor(network);
+        priority
+            .target_blocks()
+            .and_then(|blocks| self.for_target(blocks))
+            .map_or(floor, |rate| rate.max(floor))
+    }
+}
+
+/// Rate at `target` on the line between two estimates, rounded up
+fn interpolate(target: u32, (low, low_rate): (u32, u64), (high, high_rate): (u32, u64)) -> u64 {
+    let rise = i128::from(high_rate) - i128::from(low_rate);
+    let run = i128::from(high - low);
+    let scaled = rise * i128::from(target - low);
+    // Integer division truncates towards zero, which rounds a fall up already
+    let step = if scaled > 0 {
+        (scaled + run - 1) / run
+    } else {
+        scaled / run
+    };
+    u64::try_from(i128::from(low_rate) + step).unwrap_or(low_rate)
 }
 
 /// Block information
@@ -0,0 +1,283 @@
+//! Integration tests for offline address parsing
+//! These tests decode published Bitcoin and Liquid addresses and check that
+//! clients and `TxBuilder` reject addresses for another network
+
+use green_rs::address::{
+    validate_address, AddressEncoding, AddressScriptType, ParsedAddress, PaymentUri,
+};
+use green_rs::api::address::AddressApi;
+use green_rs::api::{TransactionApi, TxBuilder};
+use green_rs::testkit::FakeGreen;
+use green_rs::types::address::GetReceiveAddressRequest;
+use green_rs::{ErrorKind, Network};
+
+const LIQUID_CONFIDENTIAL: &str = "lq1qqf8er278e6nyvuwtgf39e6ewvdcnjupn9a86rzpx655y5lhkt0walu3djf9cklkxd3ryld97hu8h3xepw7sh2rlu7q45dcew5";
+
+fn parse(address: &str) -> ParsedAddress {
+    ParsedAddress::parse(address).unwrap()
+}
+
+#[test]
+fn test_bitcoin_addresses() {
+    let p2wpkh = parse("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+    assert_eq!(p2wpkh.network, Network::Mainnet);
+    assert_eq!(p2wpkh.encoding, AddressEncoding::Bech32);
+    assert_eq!(p2wpkh.script_type, AddressScriptType::P2wpkh);
+    assert_eq!(
+        hex::encode(p2wpkh.witness_program().unwrap()),
+        "751e76e8199196d454941c45d1b3a323f1433bd6"
+    );
+    assert_eq!(
+        p2wpkh.script_pubkey(),
+        "0014751e76e8199196d454941c45d1b3a323f1433bd6"
+    );
+
+    let p2tr = parse("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0");
+    assert_eq!(p2tr.encoding, AddressEncoding::Bech32m);
+    assert_eq!(p2tr.script_type, AddressScriptType::P2tr);
+    assert_eq!(p2tr.witness_version, Some(1));
+    assert!(p2tr.script_pubkey().starts_with("5120"));
+
+    let legacy = parse("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2");
+    assert_eq!(legacy.encoding, AddressEncoding::Base58);
+    assert_eq!(legacy.script_type, AddressScriptType::P2pkh);
+    assert_eq!(legacy.witness_program(), None);
+    assert_eq!(
+        legacy.script_pubkey(),
+        "76a91477bff20c60e522dfaa3350c39b030a5d004e839a88ac"
+    );
+    assert_eq!(
+        parse("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy").script_type,
+        AddressScriptType::P2sh
+    );
+
+    // Base58 testnet prefixes are shared with regtest
+    let testnet = parse("mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn");
+    assert_eq!(testnet.network, Network::Testnet);
+    assert!(testnet.is_valid_for(Network::ElectrumLocaltest));
+    assert!(!parse("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx")
+        .is_valid_for(Network::ElectrumLocaltest));
+
+    // Uppercase is accepted and formatted back in lowercase
+    let upper = parse("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4");
+    assert_eq!(upper, p2wpkh);
+    assert_eq!(
+        upper.to_string(),
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+    );
+}
+
+#[test]
+fn test_liquid_addresses() {
+    let confidential = parse(LIQUID_CONFIDENTIAL);
+    assert_eq!(confidential.network, Network::Liquid);
+    assert_eq!(confidential.encoding, AddressEncoding::Blech32);
+    assert_eq!(confidential.script_type, AddressScriptType::P2wpkh);
+    assert!(confidential.is_confidential());
+    let pubkey = confidential.blinding_pubkey.as_ref().unwrap();
+    assert_eq!(pubkey.len(), 33);
+    assert_eq!(confidential.to_string(), LIQUID_CONFIDENTIAL);
+
+    let unconfidential = confidential.to_unconfidential();
+    assert_eq!(
+        unconfidential.to_string(),
+        "ex1q7gkeyjut0mrxc3j0kjlt7rmcnvsh0gt45d3fud"
+    );
+    assert_eq!(parse(&unconfidential.to_string()), unconfidential);
+    assert_eq!(unconfidential.encoding, AddressEncoding::Bech32);
+
+    let base58 =
+        parse("VJLCbLBTCdxhWyjVLdjcSmGAksVMtabYg15maSi93zknQD2ihC38R7CUd8KbDFnV8A4hiykxnRB3Uv6d");
+    assert_eq!(base58.network, Network::Liquid);
+    assert_eq!(base58.script_type, AddressScriptType::P2sh);
+    assert!(base58.is_confidential());
+    assert_eq!(
+        base58.to_unconfidential().to_string(),
+        "GjzwdM3QQBLizDdJPzFRaNjfufkMdbzBvr"
+    );
+
+    // Segwit v1 uses blech32m, and the testnet prefixes round trip
+    let taproot = ParsedAddress {
+        network: Network::LiquidTestnet,
+        encoding: AddressEncoding::Blech32m,
+        script_type: AddressScriptType::P2tr,
+        witness_version: Some(1),
+        program: vec![7; 32],
+        blinding_pubkey: Some(pubkey.clone()),
+    };
+    let encoded = taproot.to_string();
+    assert!(encoded.starts_with("tlq1p"));
+    assert_eq!(parse(&encoded), taproot);
+    assert!(taproot.to_unconfidential().to_string().starts_with("tex1p"));
+}
+
+#[test]
+fn test_invalid_addresses() {
+    for address in [
+        "",
+        "not-an-address",
+        // Bad checksums
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5",
+        "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN3",
+        &LIQUID_CONFIDENTIAL.replace("dcew5", "dcew6"),
+        // Mixed case
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xW7kv8f3t4",
+        // Segwit v0 with a bech32m checksum
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh",
+        // Unknown prefix
+        "ltc1qw508d6qejxtdg4y5r3zarvary0c5xw7kgmn4n9",
+    ] {
+        let err = ParsedAddress::parse(address).unwrap_err();
+        assert_eq!(err.kind(), Some(ErrorKind::InvalidAddress), "{address}");
+    }
+
+    let err = validate_address(LIQUID_CONFIDENTIAL, Some(Network::LiquidTestnet)).unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::InvalidAddress));
+    assert!(validate_address(LIQUID_CONFIDENTIAL, None).is_ok());
+}
+
+#[test]
+fn test_clients_validate_against_their_network() {
+    let wallet = FakeGreen::with_network(Network::Liquid);
+    wallet.fund(0, 100_000);
+    let client = wallet.client();
+
+    // Addresses the wallet hands out are confidential for its network
+    let receive = client
+        .get_new_address(GetReceiveAddressRequest::default())
+        .unwrap();
+    let parsed = client.validate_address(&receive.address).unwrap();
+    assert!(parsed.is_confidential());
+
+    // Addresses for another network are rejected before green-cli runs
+    let calls = wallet.calls().len();
+    let mainnet = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+    let err = client.validate_address(mainnet).unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::InvalidAddress));
+    let err = client.send_to_address(mainnet, 10_000, None).unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::InvalidAddress));
+
+    let builder = TxBuilder::with_client(client.clone()).add_output(mainnet.to_string(), 10_000);
+    assert_eq!(
+        builder.to_request().unwrap_err().kind(),
+        Some(ErrorKind::InvalidAddress)
+    );
+    let err = builder.dump().err().unwrap();
+    assert_eq!(err.kind(), Some(ErrorKind::InvalidAddress));
+    assert_eq!(wallet.calls().len(), calls);
+
+    assert!(client
+        .send_to_address(LIQUID_CONFIDENTIAL, 10_000, None)
+        .is_ok());
+}
+
+#[test]
+fn test_payment_uris() {
+    let uri: PaymentUri = "bitcoin:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2?amount=20.3&label=Luke-Jr&message=Donation%20for%20project%20xyz"
+        .parse()
+        .unwrap();
+    assert_eq!(uri.network, Network::Mainnet);
+    assert_eq!(uri.amount, Some(2_030_000_000));
+    assert_eq!(uri.label.as_deref(), Some("Luke-Jr"));
+    assert_eq!(uri.message.as_deref(), Some("Donation for project xyz"));
+    assert_eq!(
+        uri.to_string(),
+        "bitcoin:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2?amount=20.3&label=Luke-Jr&message=Donation%20for%20project%20xyz"
+    );
+    let addressee = uri.to_addressee().unwrap();
+    assert_eq!(addressee.satoshi, 2_030_000_000);
+
+    // Unknown optional parameters are ignored, required ones are not
+    let plain = PaymentUri::parse(
+        "BITCOIN:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2?somethingyoudontunderstand=50",
+    )
+    .unwrap();
+    assert_eq!(
+        plain.to_string(),
+        "bitcoin:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2"
+    );
+    assert_eq!(
+        plain.to_addressee().unwrap_err().kind(),
+        Some(ErrorKind::InvalidAmount)
+    );
+
+    let asset = "6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d";
+    let liquid = PaymentUri::new(LIQUID_CONFIDENTIAL)
+        .unwrap()
+        .amount(1)
+        .asset_id(asset);
+    assert_eq!(
+        liquid.to_string(),
+        format!("liquidnetwork:{LIQUID_CONFIDENTIAL}?amount=0.00000001&assetid={asset}")
+    );
+    assert_eq!(PaymentUri::parse(&liquid.to_string()).unwrap(), liquid);
+    assert_eq!(
+        liquid.to_addressee().unwrap().asset_id.as_deref(),
+        Some(asset)
+    );
+
+    for (uri, kind) in [
+        (
+            "bitcoin:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2?req-somethingyoudontunderstand=50",
+            ErrorKind::Other,
+        ),
+        (
+            "bitcoin:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2?amount=1.123456789",
+            ErrorKind::InvalidAmount,
+        ),
+        (
+            "bitcoin:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2?amount=-1",
+            ErrorKind::InvalidAmount,
+        ),
+        (
+            "bitcoin:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2?label=a&label=b",
+            ErrorKind::Other,
+        ),
+        (
+            "litecoin:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2",
+            ErrorKind::InvalidAddress,
+        ),
+        (
+            &format!("bitcoin:{LIQUID_CONFIDENTIAL}"),
+            ErrorKind::InvalidAddress,
+        ),
+        (
+            &format!("liquidnetwork:{LIQUID_CONFIDENTIAL}?amount=1"),
+            ErrorKind::Other,
+        ),
+    ] {
+        let err = PaymentUri::parse(uri).unwrap_err();
+        assert_eq!(err.kind(), Some(kind), "{uri}");
+    }
+}
+
+#[test]
+fn test_tx_builder_pays_payment_uri() {
+    let wallet = FakeGreen::new();
+    wallet.fund(0, 100_000);
+    let client = wallet.client();
+
+    let uri = client
+        .get_receive_address(GetReceiveAddressRequest::default())
+        .unwrap()
+        .payment_uri()
+        .unwrap()
+        .amount(25_000)
+        .message("Invoice 7");
+    assert!(uri.to_string().starts_with("bitcoin:bcrt1"));
+
+    let request = TxBuilder::with_client(client.clone())
+        .add_payment_uri(PaymentUri::parse(&uri.to_string()).unwrap())
+        .unwrap()
+        .to_request()
+        .unwrap();
+    let addressee = &request.addressees.unwrap()[0];
+    assert_eq!(addressee.address, uri.address);
+    assert_eq!(addressee.satoshi, 25_000);
+
+    let err = TxBuilder::with_client(client)
+        .add_payment_uri(PaymentUri::new(&uri.address).unwrap())
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), Some(ErrorKind::InvalidAmount));
+}
@@ -0,0 +1,170 @@
+//! Integration tests for address labels and the address book
+//! These tests label `FakeGreen` receive addresses and keep counterparties
+//! in an address book stored in a temporary directory
+
+use green_rs::address::{AddressBook, AddressBookEntry};
+use green_rs::api::address::{AddressApi, AsyncAddressApi};
+use green_rs::api::TxBuilder;
+use green_rs::testkit::FakeGreen;
+use green_rs::types::address::{GetPreviousAddressesRequest, GetReceiveAddressRequest};
+use green_rs::{Error, ErrorKind, Network};
+
+const EXCHANGE: &str = "bcrt1qvewsdxxmeraett7ztsaym88jsrv85kzmev28cl";
+const SUPPLIER: &str = "bcrt1q6qk53s4wtrddddtfwwnny6urn8t8mmx4mcfkae";
+
+fn kind(result: Result<impl std::fmt::Debug, Error>) -> Option<ErrorKind> {
+    result.unwrap_err().kind()
+}
+
+#[tokio::test]
+async fn test_receive_address_labels() {
+    let wallet = FakeGreen::new();
+    let client = wallet.client();
+    let address = client
+        .get_new_address(GetReceiveAddressRequest::default())
+        .unwrap();
+
+    let labelled = client
+        .set_address_label(0, address.pointer, "Invoice 7")
+        .unwrap();
+    assert_eq!(labelled.address, address.address);
+    assert_eq!(labelled.label.as_deref(), Some("Invoice 7"));
+
+    let previous = client
+        .get_previous_addresses(GetPreviousAddressesRequest {
+            subaccount: Some(0),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(previous[0].label.as_deref(), Some("Invoice 7"));
+
+    // An empty label removes it
+    let cleared = wallet
+        .async_client()
+        .set_address_label(0, address.pointer, "")
+        .await
+        .unwrap();
+    assert_eq!(cleared.label, None);
+
+    assert_eq!(
+        kind(client.set_address_label(0, address.pointer + 1, "Missing")),
+        Some(ErrorKind::NotFound)
+    );
+}
+
+#[test]
+fn test_address_book_validates_and_persists() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("contacts.json");
+
+    let mut book = AddressBook::open(&path).unwrap();
+    assert!(book.entries().is_empty());
+    book.insert(
+        AddressBookEntry::new("exchange", EXCHANGE, Network::ElectrumLocaltest)
+            .notes("Deposit address"),
+    )
+    .unwrap();
+    book.insert(AddressBookEntry::new(
+        "supplier",
+        SUPPLIER,
+        Network::ElectrumLocaltest,
+    ))
+    .unwrap();
+
+    // Invalid addresses, wrong networks, blank and duplicate names are refused
+    for (entry, expected) in [
+        (
+            AddressBookEntry::new("typo", "bcrt1qnotanaddress", Network::ElectrumLocaltest),
+            ErrorKind::InvalidAddress,
+        ),
+        (
+            AddressBookEntry::new("mainnet", EXCHANGE, Network::Mainnet),
+            ErrorKind::InvalidAddress,
+        ),
+        (
+            AddressBookEntry::new(" ", EXCHANGE, Network::ElectrumLocaltest),
+            ErrorKind::Other,
+        ),
+        (
+            AddressBookEntry::new("exchange", SUPPLIER, Network::ElectrumLocaltest),
+            ErrorKind::Other,
+        ),
+    ] {
+        assert_eq!(kind(book.insert(entry)), Some(expected));
+    }
+    assert_eq!(book.entries().len(), 2);
+
+    book.save().unwrap();
+    let reopened = AddressBook::open(&path).unwrap();
+    assert_eq!(reopened, book);
+    assert_eq!(
+        reopened.get("exchange").unwrap().notes.as_deref(),
+        Some("Deposit address")
+    );
+
+    book.remove("supplier").unwrap();
+    assert!(book.remove("supplier").is_none());
+    book.save().unwrap();
+    assert_eq!(AddressBook::open(&path).unwrap().entries().len(), 1);
+
+    // A hand-edited file is validated when opened
+    std::fs::write(
+        &path,
+        r#"[{"name": "broken", "address": "bc1qtypo", "network": "mainnet"}]"#,
+    )
+    .unwrap();
+    assert_eq!(
+        kind(AddressBook::open(&path)),
+        Some(ErrorKind::InvalidAddress)
+    );
+}
+
+#[test]
+fn test_tx_builder_pays_book_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut book = AddressBook::open(dir.path().join("contacts.json")).unwrap();
+    book.insert(AddressBookEntry::new(
+        "exchange",
+        EXCHANGE,
+        Network::ElectrumLocaltest,
+    ))
+    .unwrap();
+    book.insert(AddressBookEntry::new(
+        "mainnet",
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        Network::Mainnet,
+    ))
+    .unwrap();
+
+    let wallet = FakeGreen::new();
+    wallet.fund(0, 100_000);
+    let builder = TxBuilder::with_client(wallet.client());
+
+    let request = builder
+        .add_book_output(&book, "exchange", 25_000)
+        .unwrap()
+        .to_request()
+        .unwrap();
+    let addressee = &request.addressees.unwrap()[0];
+    assert_eq!(
+        (addressee.address.as_str(), addressee.satoshi),
+        (EXCHANGE, 25_000)
+    );
+
+    let builder = TxBuilder::with_client(wallet.client());
+    assert_eq!(
+        builder
+            .add_book_output(&book, "nobody", 1_000)
+            .err()
+            .and_then(|err| err.kind()),
+        Some(ErrorKind::NotFound)
+    );
+    let builder = TxBuilder::with_client(wallet.client());
+    assert_eq!(
+        builder
+            .add_book_output(&book, "mainnet", 1_000)
+            .err()
+            .and_then(|err| err.kind()),
+        Some(ErrorKind::InvalidAddress)
+    );
+}
@@ -0,0 +1,133 @@
+//! Integration tests for address gap-limit scanning
+//! These tests walk every previous address of a `FakeGreen` subaccount with
+//! the sync pager and the async stream, and check the usage report
+
+use futures::StreamExt;
+use green_rs::api::address::{AddressApi, AsyncAddressApi};
+use green_rs::api::TransactionApi;
+use green_rs::testkit::FakeGreen;
+use green_rs::transport::{CliOutput, ScriptedTransport};
+use green_rs::types::address::{GapLimitPolicy, GapWarning, GetReceiveAddressRequest};
+use green_rs::{ClientConfig, ErrorKind, GreenClient};
+
+/// Wallet whose subaccount 0 has 21 receive addresses: pointer 1 funded,
+/// pointer 3 paid twice and nothing after it
+fn wallet() -> FakeGreen {
+    let wallet = FakeGreen::new();
+    wallet.fund(0, 100_000);
+    wallet.mine(1);
+    let client = wallet.client();
+
+    let mut addresses = Vec::new();
+    for _ in 2..=21 {
+        let address = client
+            .get_new_address(GetReceiveAddressRequest::default())
+            .unwrap();
+        addresses.push(address.address);
+    }
+    for _ in 0..2 {
+        client.send_to_address(&addresses[1], 10_000, None).unwrap();
+    }
+    wallet
+}
+
+fn page_fetches(wallet: &FakeGreen) -> usize {
+    wallet
+        .calls()
+        .iter()
+        .filter(|call| call.args[..2] == ["get", "previous-addresses"])
+        .count()
+}
+
+#[test]
+fn test_pager_walks_every_page() {
+    let wallet = wallet();
+    let pointers: Vec<u32> = wallet
+        .client()
+        .previous_address_pager(0)
+        .map(|details| details.unwrap().pointer)
+        .collect();
+
+    assert_eq!(pointers, (1..=21).rev().collect::<Vec<_>>());
+    // Three pages of up to ten addresses, then an empty one
+    assert_eq!(page_fetches(&wallet), 4);
+}
+
+#[test]
+fn test_report_counts_gap_and_reuse() {
+    let wallet = wallet();
+    let report = wallet
+        .client()
+        .get_address_report(0, GapLimitPolicy::default())
+        .unwrap();
+
+    assert_eq!(report.address_count, 21);
+    assert_eq!((report.used_count, report.unused_count), (2, 19));
+    assert_eq!(report.last_pointer, Some(21));
+    assert_eq!(report.last_used_pointer, Some(3));
+    assert_eq!(report.gap, 18);
+    assert_eq!(report.reused.len(), 1);
+    assert_eq!(report.reused[0].pointer, 3);
+    assert_eq!(report.reused[0].tx_count, 2);
+    assert_eq!(
+        report.gap_warning,
+        Some(GapWarning::Approaching { gap: 18, limit: 20 })
+    );
+
+    let strict = wallet
+        .client()
+        .get_address_report(0, GapLimitPolicy::new(18))
+        .unwrap();
+    let warning = strict.gap_warning.unwrap();
+    assert_eq!(warning, GapWarning::Reached { gap: 18, limit: 18 });
+    assert!(warning.to_string().contains("not found on recovery"));
+
+    let relaxed = GapLimitPolicy::new(100).warn_at(50);
+    let report = wallet.client().get_address_report(0, relaxed).unwrap();
+    assert_eq!(report.gap_warning, None);
+}
+
+#[tokio::test]
+async fn test_stream_matches_pager() {
+    let wallet = wallet();
+    let streamed: Vec<u32> = wallet
+        .async_client()
+        .previous_address_stream(0)
+        .map(|details| details.unwrap().pointer)
+        .collect()
+        .await;
+    assert_eq!(streamed, (1..=21).rev().collect::<Vec<_>>());
+
+    let report = wallet
+        .async_client()
+        .get_address_report(0, GapLimitPolicy::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        report,
+        wallet
+            .client()
+            .get_address_report(0, GapLimitPolicy::default())
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_pager_stops_after_errors() {
+    let transport = ScriptedTransport::new().on_prefix(
+        ["get", "previous-addresses"],
+        CliOutput::failure(1, "Error: Login required"),
+    );
+    let client = GreenClient::with_transport(ClientConfig::default(), transport.clone());
+
+    let mut pager = client.previous_address_pager(0);
+    assert_eq!(
+        pager.next().unwrap().unwrap_err().kind(),
+        Some(ErrorKind::LoginRequired)
+    );
+    assert!(pager.next().is_none());
+    assert!(client
+        .get_address_report(0, GapLimitPolicy::default())
+        .is_err());
+    assert_eq!(transport.calls().len(), 2);
+}
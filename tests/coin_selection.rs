@@ -0,0 +1,276 @@
+//! Integration tests for local coin selection
+//! These tests run each strategy over hand-built outputs, then pass a
+//! selection to the `FakeGreen` wallet through `CreateTransactionRequest::utxos`
+
+use green_rs::api::utxo::UtxoApi;
+use green_rs::api::TransactionApi;
+use green_rs::coin_selection::{CoinSelector, SelectionStrategy};
+use green_rs::estimation::{ScriptType, TxEstimator};
+use green_rs::testkit::FakeGreen;
+use green_rs::types::transaction::{Addressee, CreateTransactionRequest};
+use green_rs::types::wallet::FeeEstimates;
+use green_rs::types::{AddressType, FeeRate, GetUnspentOutputsParams, UnspentOutput};
+use green_rs::{Error, ErrorKind, Network};
+use std::collections::HashMap;
+
+const RECIPIENT: &str = "bcrt1q6qk53s4wtrddddtfwwnny6urn8t8mmx4mcfkae";
+
+fn utxo(txhash: &str, satoshi: u64, block_height: Option<u32>) -> UnspentOutput {
+    UnspentOutput {
+        txhash: txhash.to_string(),
+        vout: 0,
+        satoshi,
+        asset_id: None,
+        block_height,
+        confirmations: block_height.map(|height| 1000 - height),
+        address: None,
+        address_type: Some(AddressType::P2wpkh),
+        script_pubkey: None,
+        subaccount: 0,
+        pointer: 0,
+        is_internal: false,
+        is_confidential: false,
+        is_frozen: false,
+        memo: None,
+    }
+}
+
+fn btc(utxos: Vec<UnspentOutput>) -> HashMap<String, Vec<UnspentOutput>> {
+    HashMap::from([("btc".to_string(), utxos)])
+}
+
+fn pay(satoshi: u64) -> Addressee {
+    Addressee {
+        address: RECIPIENT.to_string(),
+        satoshi,
+        asset_id: None,
+    }
+}
+
+/// Sizes p2wpkh inputs, recipients and change: 272, 124 and 124 weight units
+fn p2wpkh_sizing() -> TxEstimator {
+    TxEstimator::new(Network::Mainnet)
+        .script_type(ScriptType::P2wpkh)
+        .recipient_script_type(ScriptType::P2wpkh)
+}
+
+fn txhashes(inputs: &[UnspentOutput]) -> Vec<&str> {
+    inputs.iter().map(|utxo| utxo.txhash.as_str()).collect()
+}
+
+#[test]
+fn test_largest_first_returns_change() {
+    let utxos = btc(vec![
+        utxo("small", 10_000, Some(100)),
+        utxo("large", 40_000, Some(200)),
+        utxo("medium", 25_000, Some(300)),
+    ]);
+
+    let selection = CoinSelector::new(SelectionStrategy::LargestFirst)
+        .fee_rate(FeeRate::from_sat_per_vb(2))
+        .estimator(p2wpkh_sizing())
+        .select(&utxos, &[pay(50_000)])
+        .unwrap();
+
+    assert_eq!(txhashes(&selection.inputs), ["large", "medium"]);
+    // Overhead, two p2wpkh inputs, recipient and change outputs
+    assert_eq!(selection.vsize, (42 + 2 * 272 + 2 * 124_u32).div_ceil(4));
+    assert_eq!(selection.fee, 2 * u64::from(selection.vsize));
+    assert_eq!(selection.change["btc"], 65_000 - 50_000 - selection.fee);
+    assert_eq!(selection.strategy, SelectionStrategy::LargestFirst);
+}
+
+#[test]
+fn test_branch_and_bound_finds_changeless_match() {
+    // At 1 sat/vB each p2wpkh input costs 68 sat, so 6_068 + 4_110 covers
+    // 10_000 plus the 42 vB of overhead and recipient output exactly
+    let utxos = btc(vec![
+        utxo("whale", 50_000, Some(100)),
+        utxo("a", 6_068, Some(100)),
+        utxo("b", 4_110, Some(100)),
+        utxo("dust", 1_000, Some(100)),
+    ]);
+
+    let selection = CoinSelector::default()
+        .estimator(p2wpkh_sizing())
+        .select(&utxos, &[pay(10_000)])
+        .unwrap();
+
+    assert_eq!(selection.strategy, SelectionStrategy::BranchAndBound);
+    assert_eq!(txhashes(&selection.inputs), ["a", "b"]);
+    assert!(selection.change.is_empty());
+    assert_eq!(selection.fee, 178);
+    assert_eq!(selection.vsize, (42 + 2 * 272 + 124_u32).div_ceil(4));
+}
+
+#[test]
+fn test_branch_and_bound_falls_back_to_knapsack() {
+    let utxos = btc(vec![
+        utxo("big", 50_000, Some(100)),
+        utxo("smaller", 30_000, Some(100)),
+    ]);
+
+    let selection = CoinSelector::default()
+        .select(&utxos, &[pay(10_000)])
+        .unwrap();
+
+    assert_eq!(selection.strategy, SelectionStrategy::Knapsack);
+    assert_eq!(txhashes(&selection.inputs), ["smaller"]);
+    assert_eq!(selection.change["btc"], 30_000 - 10_000 - selection.fee);
+}
+
+#[test]
+fn test_oldest_first_spends_deepest_outputs() {
+    let utxos = btc(vec![
+        utxo("recent", 80_000, Some(300)),
+        utxo("mempool", 90_000, None),
+        utxo("oldest", 20_000, Some(100)),
+        utxo("older", 20_000, Some(200)),
+    ]);
+    let selector = CoinSelector::new(SelectionStrategy::OldestFirst);
+
+    let one = selector.select(&utxos, &[pay(15_000)]).unwrap();
+    assert_eq!(txhashes(&one.inputs), ["oldest"]);
+
+    let three = selector.select(&utxos, &[pay(50_000)]).unwrap();
+    assert_eq!(txhashes(&three.inputs), ["oldest", "older", "recent"]);
+
+    let all = selector.select(&utxos, &[pay(150_000)]).unwrap();
+    assert_eq!(all.inputs.last().unwrap().txhash, "mempool");
+}
+
+#[test]
+fn test_frozen_and_foreign_outputs_are_skipped() {
+    let mut frozen = utxo("frozen", 100_000, Some(100));
+    frozen.is_frozen = true;
+    let mut other_subaccount = utxo("other", 100_000, Some(100));
+    other_subaccount.subaccount = 1;
+    let utxos = btc(vec![
+        frozen,
+        other_subaccount,
+        utxo("spendable", 5_000, Some(100)),
+    ]);
+
+    let err = CoinSelector::default()
+        .subaccount(0)
+        .select(&utxos, &[pay(20_000)])
+        .unwrap_err();
+    assert!(matches!(err, Error::Validation { .. }));
+    assert_eq!(err.kind(), Some(ErrorKind::InsufficientFunds));
+
+    let err = CoinSelector::default()
+        .select(&utxos, &[pay(0)])
+        .unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::InvalidAmount));
+    assert_eq!(
+        CoinSelector::default()
+            .select(&utxos, &[])
+            .unwrap_err()
+            .kind(),
+        Some(ErrorKind::InvalidAmount)
+    );
+}
+
+#[test]
+fn test_liquid_assets_are_funded_separately() {
+    let asset = "aa".repeat(32);
+    let asset_utxo = |txhash: &str, satoshi| UnspentOutput {
+        asset_id: Some(asset.clone()),
+        is_confidential: true,
+        ..utxo(txhash, satoshi, Some(100))
+    };
+    let utxos = HashMap::from([
+        ("btc".to_string(), vec![utxo("lbtc", 10_000, Some(100))]),
+        (
+            asset.clone(),
+            vec![asset_utxo("x", 700), asset_utxo("y", 500)],
+        ),
+    ]);
+    let recipients = [Addressee {
+        address: RECIPIENT.to_string(),
+        satoshi: 1_000,
+        asset_id: Some(asset.clone()),
+    }];
+
+    let liquid = TxEstimator::new(Network::Liquid).script_type(ScriptType::P2wpkh);
+    let selection = CoinSelector::new(SelectionStrategy::LargestFirst)
+        .estimator(liquid.clone())
+        .select(&utxos, &recipients)
+        .unwrap();
+
+    assert_eq!(txhashes(&selection.inputs), ["x", "y", "lbtc"]);
+    assert_eq!(selection.change[&asset], 200);
+    // The L-BTC input pays for the asset inputs and asset change too, and
+    // every confidential output carries its range and surjection proofs
+    let weight = liquid.overhead_weight()
+        + 3 * liquid.input_weight(ScriptType::P2wpkh)
+        + liquid.recipient_weight(3)
+        + 2 * liquid.change_weight(3);
+    assert_eq!(selection.vsize, weight.div_ceil(4));
+    assert!(selection.vsize > 3 * 1_100);
+    // Each part of the fee is rounded up on its own
+    assert!(selection.fee >= u64::from(selection.vsize));
+    assert!(selection.fee <= u64::from(selection.vsize) + 5);
+    assert_eq!(selection.change["btc"], 10_000 - selection.fee);
+    assert_eq!(selection.input_total(&asset), 1_200);
+}
+
+#[test]
+fn test_fee_rate_from_estimates() {
+    let estimates = FeeEstimates {
+        fees: [(1, 20), (3, 10), (6, 5), (12, 2)]
+            .into_iter()
+            .map(|(blocks, rate)| (blocks, FeeRate::from_sat_per_vb(rate)))
+            .collect(),
+    };
+    let utxos = btc(vec![utxo("coin", 100_000, Some(100))]);
+    let rate_for = |target| {
+        CoinSelector::default()
+            .fee_estimates(&estimates, target)
+            .select(&utxos, &[pay(10_000)])
+            .unwrap()
+            .fee_rate
+    };
+
+    // A third of the way from 10 to 5 sat/vB, rounded up
+    assert_eq!(rate_for(4), FeeRate::from_sat_per_kvb(8_334));
+    assert_eq!(rate_for(6), FeeRate::from_sat_per_vb(5));
+    assert_eq!(rate_for(144), FeeRate::from_sat_per_vb(2));
+    assert_eq!(rate_for(0), FeeRate::from_sat_per_vb(20));
+}
+
+#[test]
+fn test_selection_funds_fake_wallet_transaction() {
+    let wallet = FakeGreen::new();
+    for amount in [3_000, 12_000, 45_000, 8_000] {
+        wallet.fund(0, amount);
+    }
+    wallet.mine(1);
+    let client = wallet.client();
+
+    let utxos = client
+        .get_unspent_outputs(GetUnspentOutputsParams::default())
+        .unwrap();
+    let selection = CoinSelector::default()
+        .fee_rate(FeeRate::from_sat_per_vb(2))
+        .select(&utxos, &[pay(18_000)])
+        .unwrap();
+
+    let mut request = CreateTransactionRequest {
+        addressees: Some(vec![pay(18_000)]),
+        ..Default::default()
+    };
+    selection.apply_to(&mut request);
+    assert_eq!(request.fee_rate, Some(FeeRate::from_sat_per_vb(2)));
+
+    let created = client.create_transaction(request).unwrap();
+    let selected: Vec<_> = selection
+        .utxo_refs()
+        .into_iter()
+        .map(|utxo| (utxo.txid, utxo.vout))
+        .collect();
+    assert!(!created.transaction.inputs.is_empty());
+    for input in &created.transaction.inputs {
+        assert!(selected.contains(&(input.txid.clone(), input.vout)));
+    }
+}
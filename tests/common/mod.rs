@@ -0,0 +1,66 @@
+//! Shared helpers for integration tests that mock green-cli with a shell script stub
+
+#![allow(dead_code)]
+
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+// Global mutex to ensure tests don't interfere with each other's environment
+static ENV_MUTEX: Mutex<()> = Mutex::new(());
+
+/// Directory holding the mock green-cli script and JSON fixtures
+fn fixtures_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+}
+
+/// Path of the mock green-cli script, for clients configured without PATH
+pub fn mock_binary() -> PathBuf {
+    fixtures_path().join("green-cli")
+}
+
+/// Helper function to set up PATH to use our mock green-cli
+fn setup_mock_path() -> String {
+    let current_path = env::var("PATH").unwrap_or_default();
+    format!("{}:{}", fixtures_path().display(), current_path)
+}
+
+/// Test helper that sets up environment and cleans up after test
+pub struct TestEnvironment {
+    _guard: MutexGuard<'static, ()>,
+    original_path: String,
+    env_vars_to_remove: Vec<String>,
+}
+
+impl TestEnvironment {
+    pub fn new() -> Self {
+        let guard = ENV_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+        let original_path = env::var("PATH").unwrap_or_default();
+        env::set_var("PATH", setup_mock_path());
+
+        Self {
+            _guard: guard,
+            original_path,
+            env_vars_to_remove: Vec::new(),
+        }
+    }
+
+    pub fn set_var(&mut self, key: &str, value: &str) {
+        env::set_var(key, value);
+        self.env_vars_to_remove.push(key.to_string());
+    }
+}
+
+impl Drop for TestEnvironment {
+    fn drop(&mut self) {
+        // Restore original PATH
+        env::set_var("PATH", &self.original_path);
+
+        // Remove any environment variables we set
+        for var in &self.env_vars_to_remove {
+            env::remove_var(var);
+        }
+    }
+}
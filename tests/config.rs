@@ -0,0 +1,119 @@
+//! Integration tests for client configuration
+//! These tests point the clients directly at the mock green-cli script instead of mutating PATH
+
+mod common;
+
+use common::mock_binary;
+use green_rs::api::{AsyncWalletExt, WalletExt};
+use green_rs::types::FeeRate;
+use green_rs::{AsyncGreenClient, ClientConfig, Error, GreenClient, Network};
+
+#[test]
+fn test_configured_binary_path() {
+    let client = GreenClient::with_config(ClientConfig::builder().binary(mock_binary()).build());
+
+    let balance = client.get_balance().expect("Expected balance from mock");
+    assert_eq!(balance.get("btc"), Some(100000));
+}
+
+#[test]
+fn test_configured_env_is_passed() {
+    let client = GreenClient::with_config(
+        ClientConfig::builder()
+            .binary(mock_binary())
+            .env("MOCK_EMPTY_BALANCE", "1")
+            .build(),
+    );
+
+    let balance = client.get_balance().expect("Expected empty balance");
+    assert!(balance.is_empty());
+}
+
+#[test]
+fn test_configured_network_is_passed() {
+    let client = GreenClient::with_config(
+        ClientConfig::builder()
+            .binary(mock_binary())
+            .network(Network::Testnet)
+            .config_dir(std::env::temp_dir())
+            .env("MOCK_EXPECT_NETWORK", "testnet")
+            .build(),
+    );
+
+    assert!(client.get_fee_estimates().is_ok());
+}
+
+#[test]
+fn test_clients_on_different_networks() {
+    let liquid = GreenClient::with_config(
+        ClientConfig::builder()
+            .binary(mock_binary())
+            .network(Network::Liquid)
+            .env("MOCK_EXPECT_NETWORK", "liquid")
+            .build(),
+    );
+    let mainnet = GreenClient::with_config(
+        ClientConfig::builder()
+            .binary(mock_binary())
+            .network(Network::Mainnet)
+            .env("MOCK_EXPECT_NETWORK", "liquid")
+            .build(),
+    );
+
+    assert!(liquid.get_balance().is_ok());
+    match mainnet.get_balance() {
+        Err(Error::Cli(msg)) => assert!(msg.message.contains("got mainnet")),
+        other => panic!("Expected network mismatch error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_missing_binary_is_io_error() {
+    let client = GreenClient::with_config(
+        ClientConfig::builder()
+            .binary("/nonexistent/green-cli")
+            .build(),
+    );
+
+    match client.get_balance() {
+        Err(Error::Io(_)) => {}
+        other => panic!("Expected IO error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_network_names() {
+    assert_eq!(Network::LiquidTestnet.to_string(), "testnet-liquid");
+    assert_eq!(
+        "liquid-testnet".parse::<Network>().unwrap(),
+        Network::LiquidTestnet
+    );
+    assert_eq!(
+        "electrum-localtest".parse::<Network>().unwrap(),
+        Network::ElectrumLocaltest
+    );
+    assert!("regtest".parse::<Network>().is_err());
+    assert!(Network::Liquid.is_liquid());
+    assert!(!Network::Testnet.is_liquid());
+}
+
+#[tokio::test]
+async fn test_async_configured_client() {
+    let client = AsyncGreenClient::with_config(
+        ClientConfig::builder()
+            .binary(mock_binary())
+            .network(Network::ElectrumLocaltest)
+            .env("MOCK_EXPECT_NETWORK", "electrum-localtest")
+            .build(),
+    );
+
+    let fee_estimates = client
+        .get_fee_estimates()
+        .await
+        .expect("Expected fee estimates from mock");
+    assert_eq!(
+        fee_estimates.fees.get(&6),
+        Some(&FeeRate::from_sat_per_vb(25))
+    );
+    assert_eq!(client.config().network, Some(Network::ElectrumLocaltest));
+}
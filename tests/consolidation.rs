@@ -0,0 +1,183 @@
+//! Integration tests for UTXO consolidation planning
+//! These tests check plan arithmetic on hand-built outputs, then execute plans
+//! against the `FakeGreen` wallet with both clients
+
+use green_rs::api::utxo::{AsyncUtxoApi, UtxoApi};
+use green_rs::consolidation::ConsolidationPlanner;
+use green_rs::estimation::{ScriptType, TxEstimator};
+use green_rs::testkit::FakeGreen;
+use green_rs::types::{AddressType, FeeRate, GetUnspentOutputsParams, UnspentOutput};
+use green_rs::{ErrorKind, Network};
+use std::collections::HashMap;
+
+fn utxo(txhash: &str, satoshi: u64) -> UnspentOutput {
+    UnspentOutput {
+        txhash: txhash.to_string(),
+        vout: 1,
+        satoshi,
+        asset_id: None,
+        block_height: Some(100),
+        confirmations: Some(10),
+        address: None,
+        address_type: Some(AddressType::P2wpkh),
+        script_pubkey: None,
+        subaccount: 0,
+        pointer: 0,
+        is_internal: false,
+        is_confidential: false,
+        is_frozen: false,
+        memo: None,
+    }
+}
+
+fn hot_wallet() -> HashMap<String, Vec<UnspentOutput>> {
+    let mut utxos: Vec<UnspentOutput> = (1..=7)
+        .map(|n| utxo(&format!("coin{n}"), n * 1_000))
+        .collect();
+    utxos.push(UnspentOutput {
+        is_frozen: true,
+        ..utxo("frozen", 500)
+    });
+    utxos.push(utxo("dust", 50));
+    utxos.push(UnspentOutput {
+        subaccount: 1,
+        ..utxo("elsewhere", 900)
+    });
+    HashMap::from([("btc".to_string(), utxos)])
+}
+
+fn all_utxos() -> GetUnspentOutputsParams {
+    GetUnspentOutputsParams::default()
+}
+
+#[test]
+fn test_plan_batches_cost_and_savings() {
+    let plan = ConsolidationPlanner::new(0)
+        .fee_rate(FeeRate::from_sat_per_vb(2))
+        .future_fee_rate(FeeRate::from_sat_per_vb(25))
+        .max_inputs(3)
+        .estimator(TxEstimator::new(Network::Mainnet).script_type(ScriptType::P2wpkh))
+        .plan(&hot_wallet())
+        .unwrap();
+
+    assert_eq!(plan.transactions.len(), 2);
+    assert_eq!(plan.input_count(), 6);
+    let merged: Vec<u64> = plan
+        .transactions
+        .iter()
+        .map(|tx| tx.output_satoshi)
+        .collect();
+    // Overhead, three p2wpkh inputs and one output at 2 sat/vB
+    assert_eq!(
+        plan.transactions[0].vsize,
+        (42 + 3 * 272 + 124_u32).div_ceil(4)
+    );
+    assert_eq!(merged, [6_000 - 492, 15_000 - 492]);
+    assert_eq!(plan.cost, 984);
+
+    // Six inputs at 25 sat/vB later, against two merged ones
+    assert_eq!(plan.future_cost_unconsolidated, 6 * 68 * 25);
+    assert_eq!(plan.future_cost_consolidated, 2 * 68 * 25);
+    assert_eq!(plan.savings(), 10_200 - 3_400 - 984);
+
+    // Uneconomic dust and the lone leftover are skipped; frozen and other
+    // subaccounts' outputs are not considered at all
+    let skipped: Vec<&str> = plan.skipped.iter().map(|u| u.txhash.as_str()).collect();
+    assert_eq!(skipped, ["dust", "coin7"]);
+}
+
+#[test]
+fn test_plan_limits_and_requests() {
+    let utxos = hot_wallet();
+
+    // At an unchanged fee rate, consolidating only costs
+    let flat = ConsolidationPlanner::new(0)
+        .fee_rate(FeeRate::from_sat_per_vb(2))
+        .plan(&utxos)
+        .unwrap();
+    assert_eq!(flat.transactions.len(), 1);
+    assert!(flat.savings() < 0);
+
+    let small_only = ConsolidationPlanner::new(0)
+        .max_value(3_000)
+        .plan(&utxos)
+        .unwrap();
+    assert_eq!(small_only.input_count(), 3);
+    let request = small_only.request(
+        &small_only.transactions[0],
+        "bcrt1q264tqpl3d86pk6a6p5895av05jgww5k8wghaef",
+    );
+    assert!(request.send_all);
+    assert_eq!(request.subaccount, Some(0));
+    assert_eq!(request.fee_rate, Some(FeeRate::from_sat_per_vb(1)));
+    assert_eq!(request.utxos.unwrap().len(), 3);
+    assert_eq!(
+        request.addressees.unwrap()[0].address,
+        "bcrt1q264tqpl3d86pk6a6p5895av05jgww5k8wghaef"
+    );
+
+    assert!(ConsolidationPlanner::new(5)
+        .plan(&utxos)
+        .unwrap()
+        .is_empty());
+    let err = ConsolidationPlanner::new(0)
+        .max_inputs(1)
+        .plan(&utxos)
+        .unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::Other));
+}
+
+#[test]
+fn test_execute_merges_outputs_in_fake_wallet() {
+    let wallet = FakeGreen::new();
+    for amount in [1_500, 2_500, 3_500, 4_500, 5_500, 6_500, 90_000] {
+        wallet.fund(0, amount);
+    }
+    wallet.mine(1);
+    let client = wallet.client();
+    let before = wallet.balance(Some(0));
+
+    let utxos = client.get_unspent_outputs(all_utxos()).unwrap();
+    let plan = ConsolidationPlanner::new(0)
+        .max_inputs(3)
+        .max_value(10_000)
+        .plan(&utxos)
+        .unwrap();
+    assert_eq!(plan.transactions.len(), 2);
+
+    let sent = plan.execute(&client).unwrap();
+    assert_eq!(sent.len(), 2);
+    let fees: u64 = sent.iter().map(|tx| tx.fee.unwrap()).sum();
+
+    let remaining = client.get_unspent_outputs(all_utxos()).unwrap();
+    assert_eq!(remaining["btc"].len(), 3);
+    assert_eq!(
+        wallet.balance(Some(0)).get("btc"),
+        before.get("btc").map(|sat| sat - fees)
+    );
+    let new_addresses = wallet
+        .calls()
+        .iter()
+        .filter(|call| call.args[..2] == ["get", "new-address"])
+        .count();
+    assert_eq!(new_addresses, 2);
+}
+
+#[tokio::test]
+async fn test_async_execute() {
+    let wallet = FakeGreen::new();
+    for amount in [2_000, 3_000, 4_000, 5_000] {
+        wallet.fund(0, amount);
+    }
+    let client = wallet.async_client();
+
+    let utxos = client.get_unspent_outputs(all_utxos()).await.unwrap();
+    let plan = ConsolidationPlanner::new(0).plan(&utxos).unwrap();
+    assert_eq!(plan.input_count(), 4);
+
+    let sent = plan.execute_async(&client).await.unwrap();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].inputs.len(), 4);
+    let remaining = client.get_unspent_outputs(all_utxos()).await.unwrap();
+    assert_eq!(remaining["btc"].len(), 1);
+}
@@ -0,0 +1,162 @@
+//! Integration tests for child-pays-for-parent acceleration
+//! These tests check child fee arithmetic on a fixture parent, then send
+//! children of stuck payments in the `FakeGreen` wallet with both clients
+
+use green_rs::api::{AsyncTransactionApi, TransactionApi};
+use green_rs::cpfp::{package_fee_rate, CpfpPlanner};
+use green_rs::estimation::{ScriptType, TxEstimator};
+use green_rs::testkit::FakeGreen;
+use green_rs::types::{AddressType, FeeRate, Transaction};
+use green_rs::{ErrorKind, Network};
+
+const FIXTURE: &str = include_str!("fixtures/transaction_success.json");
+
+/// Outgoing fixture payment at 10 sat/vB with change at output 1, unconfirmed
+fn stuck_outgoing() -> Transaction {
+    let mut tx: Transaction = serde_json::from_str(FIXTURE).unwrap();
+    tx.block_height = None;
+    tx.confirmations = 0;
+    tx
+}
+
+#[test]
+fn test_plan_child_fee_for_target_rate() {
+    let parent = stuck_outgoing();
+    let plan = CpfpPlanner::new()
+        .fee_rate(FeeRate::from_sat_per_vb(30))
+        .address_type(AddressType::P2wpkh)
+        .estimator(TxEstimator::new(Network::Mainnet).script_type(ScriptType::P2wpkh))
+        .plan(&parent)
+        .unwrap();
+
+    assert_eq!(plan.outpoint.vout, 1);
+    assert_eq!(plan.subaccount, 0);
+    // Overhead, one p2wpkh input and one p2wpkh output
+    assert_eq!(plan.child_vsize, (42 + 272 + 124_u32).div_ceil(4));
+    // 30 sat/vB over 141 + 110 vB is 7 530 sat, 6 120 more than the parent
+    // pays, so the child rate is rounded up to the next sat/kvB
+    assert_eq!(plan.child_fee_rate, FeeRate::from_sat_per_kvb(55_637));
+    assert_eq!(plan.child_fee, 6_121);
+    assert_eq!(plan.output_satoshi(), 48_590 - 6_121);
+    assert_eq!(plan.package_fee_rate_before, FeeRate::from_sat_per_vb(10));
+    assert_eq!(
+        plan.package_fee_rate_after,
+        FeeRate::from_sat_per_kvb(30_003)
+    );
+    assert!(plan.package_fee_rate_after >= plan.target_fee_rate);
+
+    // Confidential Liquid outputs carry range and surjection proofs
+    let liquid = CpfpPlanner::new()
+        .fee_rate(FeeRate::from_sat_per_vb(30))
+        .address_type(AddressType::P2wpkh)
+        .estimator(TxEstimator::new(Network::Liquid).script_type(ScriptType::P2wpkh))
+        .plan(&parent)
+        .unwrap();
+    assert!(liquid.child_vsize > 1_100);
+    assert!(liquid.child_fee > plan.child_fee);
+
+    let request = plan.request("bcrt1q264tqpl3d86pk6a6p5895av05jgww5k8wghaef");
+    assert!(request.send_all);
+    assert_eq!(request.fee_rate, Some(plan.child_fee_rate));
+    assert_eq!(request.utxos.unwrap(), vec![plan.outpoint.clone()]);
+}
+
+#[test]
+fn test_plan_rejections() {
+    let parent = stuck_outgoing();
+    let kind = |planner: CpfpPlanner, tx: &Transaction| planner.plan(tx).unwrap_err().kind();
+
+    // The parent already pays 10 sat/vB
+    assert_eq!(
+        kind(
+            CpfpPlanner::new().fee_rate(FeeRate::from_sat_per_vb(10)),
+            &parent
+        ),
+        Some(ErrorKind::InvalidFee)
+    );
+    // Output 0 pays the recipient, not the wallet
+    assert_eq!(
+        kind(
+            CpfpPlanner::new()
+                .fee_rate(FeeRate::from_sat_per_vb(30))
+                .vout(0),
+            &parent
+        ),
+        Some(ErrorKind::Other)
+    );
+    assert_eq!(
+        kind(
+            CpfpPlanner::new().fee_rate(FeeRate::from_sat_per_vb(2_000)),
+            &parent
+        ),
+        Some(ErrorKind::InsufficientFunds)
+    );
+    assert_eq!(
+        kind(
+            CpfpPlanner::new()
+                .fee_rate(FeeRate::from_sat_per_vb(30))
+                .fee_asset("aa".repeat(32)),
+            &parent
+        ),
+        Some(ErrorKind::Other)
+    );
+    let confirmed: Transaction = serde_json::from_str(FIXTURE).unwrap();
+    assert_eq!(
+        kind(
+            CpfpPlanner::new().fee_rate(FeeRate::from_sat_per_vb(30)),
+            &confirmed
+        ),
+        Some(ErrorKind::Other)
+    );
+}
+
+#[test]
+fn test_execute_accelerates_incoming_payment() {
+    let wallet = FakeGreen::new();
+    let incoming = wallet.fund(0, 50_000);
+    let client = wallet.client();
+    let parent = client.get_transaction_details(&incoming).unwrap();
+
+    let plan = CpfpPlanner::new()
+        .fee_rate(FeeRate::from_sat_per_vb(10))
+        .address_type(AddressType::Csv)
+        .plan(&parent)
+        .unwrap();
+    assert_eq!(plan.package_fee_rate_before, FeeRate::ZERO);
+    assert!(plan.package_fee_rate_after >= FeeRate::from_sat_per_vb(10));
+
+    let child = plan.execute(&client).unwrap();
+    assert_eq!(child.inputs.len(), 1);
+    assert_eq!(child.inputs[0].txid, incoming);
+    assert_eq!(child.tx_type.as_deref(), Some("redeposit"));
+    assert_eq!(child.outputs.len(), 1);
+    assert_eq!(
+        wallet.balance(Some(0)).get("btc"),
+        Some(50_000 - child.fee.unwrap())
+    );
+    assert!(package_fee_rate(&parent, &child).unwrap() > plan.package_fee_rate_before);
+
+    wallet.mine(1);
+    let mined = client.get_transaction_details(&child.txid).unwrap();
+    assert!(mined.block_height.is_some());
+}
+
+#[tokio::test]
+async fn test_async_execute() {
+    let wallet = FakeGreen::new();
+    let incoming = wallet.fund(0, 20_000);
+    let client = wallet.async_client();
+    let parent = client.get_transaction_details(&incoming).await.unwrap();
+
+    let plan = CpfpPlanner::new()
+        .fee_rate(FeeRate::from_sat_per_vb(5))
+        .plan(&parent)
+        .unwrap();
+    let child = plan.execute_async(&client).await.unwrap();
+
+    assert_eq!(child.inputs[0].txid, incoming);
+    assert_eq!(
+        child.memo.as_deref(),
+        Some(format!("CPFP for {incoming}").as_str())
+    );
+}
@@ -1,6 +1,7 @@
 //! Tests for the error module
 
-use green_rs::{Error, Result};
+use green_rs::transport::CliOutput;
+use green_rs::{CliError, Error, ErrorKind, Result};
 use std::io;
 
 #[test]
@@ -33,7 +34,7 @@ fn test_cli_error() {
 
     match error {
         Error::Cli(msg) => {
-            assert!(msg.contains("permission denied"));
+            assert!(msg.message.contains("permission denied"));
         }
         _ => panic!("Expected Cli error variant"),
     }
@@ -67,3 +68,80 @@ fn test_error_display() {
     let timeout_err = Error::Timeout;
     assert_eq!(timeout_err.to_string(), "Operation timed out");
 }
+
+#[test]
+fn test_cli_failure_keeps_exit_code_and_raw_stderr() {
+    let stderr = "Traceback (most recent call last):\n  ...\nError: Insufficient funds\n";
+
+    match CliOutput::failure(2, stderr).into_result() {
+        Err(Error::Cli(err)) => {
+            assert_eq!(err.kind, ErrorKind::InsufficientFunds);
+            assert_eq!(err.exit_code, Some(2));
+            assert_eq!(err.message, "Insufficient funds");
+            assert_eq!(err.stderr, stderr);
+        }
+        other => panic!("Expected CLI error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_json_error_payload_is_parsed() {
+    let err = CliError::from_output(Some(1), "", r#"{"error": "id_invalid_address"}"#);
+    assert_eq!(err.kind, ErrorKind::InvalidAddress);
+    assert_eq!(err.code.as_deref(), Some("id_invalid_address"));
+
+    let err = CliError::from_output(
+        Some(1),
+        r#"{"error": {"code": "id_twofactor_required", "message": "Two factor authentication required"}}"#,
+        "",
+    );
+    assert_eq!(err.kind, ErrorKind::TwoFactorRequired);
+    assert_eq!(err.message, "Two factor authentication required");
+}
+
+#[test]
+fn test_connection_failures_map_to_network_error() {
+    let result = CliOutput::failure(1, "Error: id_connection_failed").into_result();
+
+    match result {
+        Err(Error::Network(err)) => {
+            assert_eq!(err.kind, ErrorKind::Network);
+            assert_eq!(err.exit_code, Some(1));
+        }
+        other => panic!("Expected network error, got {:?}", other),
+    }
+    assert_eq!(
+        Error::network("Connection refused").kind(),
+        Some(ErrorKind::Network)
+    );
+}
+
+#[test]
+fn test_error_kind_classification() {
+    let cases = [
+        ("Error: Login required", ErrorKind::LoginRequired),
+        ("Wallet locked", ErrorKind::LoginRequired),
+        ("id_invalid_pin", ErrorKind::InvalidCredentials),
+        ("2FA required", ErrorKind::TwoFactorRequired),
+        (
+            "Error: Amount below the dust threshold",
+            ErrorKind::InvalidAmount,
+        ),
+        ("id_fee_rate_is_below_minimum", ErrorKind::InvalidFee),
+        ("Transaction abc not found", ErrorKind::NotFound),
+        ("Network is unreachable", ErrorKind::Network),
+        ("Something else broke", ErrorKind::Other),
+    ];
+
+    for (text, kind) in cases {
+        assert_eq!(ErrorKind::classify(text), kind, "{text}");
+    }
+    assert_eq!(Error::Timeout.kind(), None);
+}
+
+#[test]
+fn test_empty_output_reports_exit_code() {
+    let err = CliError::from_output(Some(3), "", "");
+    assert_eq!(err.kind, ErrorKind::Other);
+    assert_eq!(err.message, "green-cli exited with code 3");
+}
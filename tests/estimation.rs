@@ -0,0 +1,195 @@
+//! Integration tests for transaction size and fee estimation
+//! These tests size Bitcoin and Liquid requests and reconcile an estimate
+//! with the size the `FakeGreen` wallet reports for the created transaction
+
+use green_rs::api::subaccount::SubaccountExt;
+use green_rs::api::{TransactionApi, TxBuilder};
+use green_rs::estimation::{ScriptType, TxEstimator};
+use green_rs::testkit::FakeGreen;
+use green_rs::types::subaccount::{CreateSubaccountParams, SubaccountType};
+use green_rs::types::transaction::{Addressee, CreateTransactionRequest, UtxoRef};
+use green_rs::types::{AddressType, FeeRate};
+use green_rs::{ErrorKind, GreenClient, Network};
+
+fn payment(satoshi: u64) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        addressees: Some(vec![Addressee {
+            address: "bcrt1qvewsdxxmeraett7ztsaym88jsrv85kzmev28cl".to_string(),
+            satoshi,
+            asset_id: None,
+        }]),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_script_types() {
+    let of = |subaccount: Option<&str>, address: Option<&str>| {
+        ScriptType::from_types(
+            subaccount.map(SubaccountType::from).as_ref(),
+            address.map(AddressType::from).as_ref(),
+        )
+    };
+    assert_eq!(of(Some("2of2"), None), ScriptType::Csv);
+    assert_eq!(of(Some("2of2"), Some("p2wsh")), ScriptType::P2wsh2of2);
+    assert_eq!(of(Some("2of2_no_recovery"), None), ScriptType::P2wsh2of2);
+    assert_eq!(of(Some("2of3"), Some("p2wsh")), ScriptType::P2wsh2of3);
+    assert_eq!(of(None, Some("p2sh")), ScriptType::P2sh2of3);
+    assert_eq!(of(Some("p2sh-p2wpkh"), None), ScriptType::P2shP2wpkh);
+    assert_eq!(of(None, Some("unknown")), ScriptType::P2wsh2of3);
+
+    let vsizes: Vec<u32> = [
+        ScriptType::P2tr,
+        ScriptType::P2wpkh,
+        ScriptType::P2shP2wpkh,
+        ScriptType::Csv,
+        ScriptType::P2wsh2of3,
+        ScriptType::P2sh2of3,
+    ]
+    .iter()
+    .map(|script_type| script_type.input_vsize())
+    .collect();
+    assert_eq!(vsizes, [58, 68, 91, 97, 105, 297]);
+    assert_eq!(ScriptType::P2wpkh.output_weight(), 31 * 4);
+}
+
+#[test]
+fn test_bitcoin_estimate_at_several_rates() {
+    let estimator = TxEstimator::new(Network::Mainnet)
+        .script_type(ScriptType::Csv)
+        .fee_rates([FeeRate::from_sat_per_vb(1), FeeRate::from_sat_per_vb(25)]);
+
+    let estimate = estimator.estimate(&payment(50_000)).unwrap();
+    // Overhead, one csv input, a 34-byte script recipient and csv change
+    assert_eq!(estimate.weight, 42 + 388 + 2 * 43 * 4);
+    assert_eq!(estimate.vsize, 194);
+    assert_eq!((estimate.input_count, estimate.output_count), (1, 2));
+    let fees: Vec<u64> = estimate.fees.iter().map(|fee| fee.fee).collect();
+    assert_eq!(fees, [194, 25 * 194]);
+
+    // Listed inputs are counted, and send_all leaves no change
+    let mut sweep = payment(50_000);
+    sweep.send_all = true;
+    sweep.utxos = Some(vec![
+        UtxoRef {
+            txid: "aa".repeat(32),
+            vout: 0,
+        },
+        UtxoRef {
+            txid: "bb".repeat(32),
+            vout: 1,
+        },
+    ]);
+    let estimate = estimator.estimate(&sweep).unwrap();
+    assert_eq!((estimate.input_count, estimate.output_count), (2, 1));
+    assert_eq!(estimate.weight, 42 + 2 * 388 + 43 * 4);
+
+    let mut nothing = payment(0);
+    nothing.addressees = None;
+    assert_eq!(
+        estimator.estimate(&nothing).unwrap_err().kind(),
+        Some(ErrorKind::InvalidAmount)
+    );
+}
+
+#[test]
+fn test_liquid_confidential_overhead() {
+    let estimator = TxEstimator::new(Network::Liquid);
+    let confidential = estimator.estimate(&payment(10_000)).unwrap();
+    // Recipient and change carry commitments, a surjection proof and a range
+    // proof; the fee output is explicit
+    assert_eq!(confidential.weight, 44 + 391 + 2 * 4_781 + 178);
+    assert_eq!(confidential.vsize, 2_544);
+    assert_eq!(confidential.output_count, 3);
+    assert_eq!(
+        confidential.fees[0].fee_rate,
+        FeeRate::min_relay(Network::Liquid)
+    );
+    assert_eq!(confidential.fees[0].fee, 255);
+
+    let explicit = estimator
+        .clone()
+        .confidential(false)
+        .estimate(&payment(10_000))
+        .unwrap();
+    assert_eq!(explicit.vsize, 1_427);
+
+    // Sending another asset needs an input and change for each asset
+    let mut asset_payment = payment(10_000);
+    asset_payment.addressees.as_mut().unwrap()[0].asset_id = Some("aa".repeat(32));
+    let estimate = estimator.estimate(&asset_payment).unwrap();
+    assert_eq!((estimate.input_count, estimate.output_count), (2, 4));
+}
+
+#[test]
+fn test_reconcile_with_created_transaction() {
+    let wallet = FakeGreen::new();
+    let client = wallet.client();
+    let spending = client
+        .create_subaccount(CreateSubaccountParams {
+            name: "Spending".to_string(),
+            subaccount_type: SubaccountType::P2wpkh,
+            recovery_mnemonic: None,
+            recovery_xpub: None,
+        })
+        .unwrap();
+    wallet.fund(spending.pointer, 100_000);
+
+    let mut request = payment(30_000);
+    request.subaccount = Some(spending.pointer);
+    request.fee_rate = Some(FeeRate::from_sat_per_vb(4));
+    let estimate = TxEstimator::new(Network::ElectrumLocaltest)
+        .subaccount(&spending)
+        .recipient_script_type(ScriptType::P2wpkh)
+        .estimate(&request)
+        .unwrap();
+    assert_eq!(estimate.fees[0].fee_rate, FeeRate::from_sat_per_vb(4));
+
+    let created = client.create_transaction(request).unwrap();
+    let reconciliation = estimate.reconcile(&created);
+    assert_eq!(reconciliation.difference(), 0);
+    assert!(reconciliation.is_within(0));
+    assert_eq!(reconciliation.reported_fee, estimate.fees[0].fee);
+    assert_eq!(
+        reconciliation.reported_fee_rate(),
+        FeeRate::from_sat_per_vb(4)
+    );
+
+    // The default 2of2 csv sizing overestimates p2wpkh inputs and outputs
+    let csv = TxEstimator::new(Network::ElectrumLocaltest)
+        .estimate(&payment(30_000))
+        .unwrap()
+        .reconcile(&created);
+    assert!(csv.difference() < 0);
+    assert!(!csv.is_within(10));
+}
+
+#[test]
+fn test_estimate_builder() {
+    let builder = TxBuilder::with_client(GreenClient::new())
+        .add_output(
+            "bcrt1qvewsdxxmeraett7ztsaym88jsrv85kzmev28cl".to_string(),
+            20_000,
+        )
+        .add_output(
+            "bcrt1q6qk53s4wtrddddtfwwnny6urn8t8mmx4mcfkae".to_string(),
+            10_000,
+        )
+        .add_input(format!("{}:1", "cc".repeat(32)))
+        .set_fee_rate(FeeRate::from_sat_per_vb(3));
+
+    let request = builder.to_request().unwrap();
+    assert_eq!(request.addressees.as_ref().unwrap().len(), 2);
+    assert_eq!(request.utxos.as_ref().unwrap()[0].vout, 1);
+
+    let estimate = TxEstimator::new(Network::Mainnet)
+        .script_type(ScriptType::P2wpkh)
+        .recipient_script_type(ScriptType::P2wpkh)
+        .estimate_builder(&builder)
+        .unwrap();
+    assert_eq!(estimate.vsize, 11 + 68 + 3 * 31);
+    assert_eq!(estimate.fees[0].fee, 3 * 172);
+
+    let malformed = builder.add_input("not-an-outpoint".to_string());
+    assert!(malformed.to_request().is_err());
+}
@@ -0,0 +1,215 @@
+//! Integration tests for replace-by-fee fee bumping
+//! These tests bump transactions in the `FakeGreen` wallet and check the
+//! BIP125 checks made before anything is broadcast
+
+use green_rs::api::fee_bump::{min_replacement_fee, min_replacement_fee_rate};
+use green_rs::api::{AsyncTransactionApi, TransactionApi};
+use green_rs::testkit::FakeGreen;
+use green_rs::transport::{CliCommand, CliOutput, ScriptedTransport};
+use green_rs::types::{FeeRate, Transaction};
+use green_rs::{ClientConfig, ErrorKind, GreenClient};
+use serde_json::Value;
+
+const FIXTURE: &str = include_str!("fixtures/transaction_success.json");
+
+/// The fixture transaction, still unconfirmed
+fn unconfirmed_fixture() -> Value {
+    let mut tx: Value = serde_json::from_str(FIXTURE).unwrap();
+    tx.as_object_mut().unwrap().remove("block_height");
+    tx["confirmations"] = Value::from(0);
+    tx
+}
+
+fn sends(calls: &[CliCommand]) -> usize {
+    calls
+        .iter()
+        .filter(|call| call.args[..2] == ["send", "transaction"])
+        .count()
+}
+
+/// Wallet with a single unconfirmed payment of 15 000 sat at 5 sat/vB
+fn stuck_payment() -> (FakeGreen, Transaction) {
+    let wallet = FakeGreen::new();
+    wallet.fund(0, 100_000);
+    wallet.mine(1);
+    let sent = wallet
+        .client()
+        .send_to_address("bcrt1q6qk53s4wtrddddtfwwnny6urn8t8mmx4mcfkae", 15_000, None)
+        .unwrap();
+    (wallet, sent)
+}
+
+#[test]
+fn test_minimum_replacement_fee() {
+    assert_eq!(min_replacement_fee(1_410, 141), 1_551);
+
+    let tx: Transaction = serde_json::from_value(unconfirmed_fixture()).unwrap();
+    // 1 410 sat at 141 vB is 10 sat/vB, so at least 11 sat/vB is needed
+    assert_eq!(
+        min_replacement_fee_rate(&tx).unwrap(),
+        FeeRate::from_sat_per_vb(11)
+    );
+
+    let mut sizeless = tx;
+    sizeless.vsize = None;
+    sizeless.weight = None;
+    assert!(min_replacement_fee_rate(&sizeless).is_err());
+}
+
+#[test]
+fn test_bump_fee_replaces_payment() {
+    let (wallet, sent) = stuck_payment();
+    let client = wallet.client();
+    let before = wallet.balance(Some(0)).get("btc").unwrap();
+
+    let bump = client
+        .bump_fee(&sent.txid, FeeRate::from_sat_per_vb(12))
+        .unwrap();
+
+    assert_eq!(bump.old_txid, sent.txid);
+    assert_ne!(bump.new_txid, sent.txid);
+    assert_eq!(bump.old_fee, sent.fee.unwrap());
+    assert_eq!(bump.new_fee, 12 * u64::from(sent.vsize.unwrap()));
+    assert_eq!(bump.transaction.inputs, sent.inputs);
+    let paid: Vec<_> = bump
+        .transaction
+        .outputs
+        .iter()
+        .filter(|output| !output.is_change)
+        .collect();
+    assert_eq!(paid.len(), 1);
+    assert_eq!(paid[0].satoshi, 15_000);
+    assert_eq!(
+        wallet.balance(Some(0)).get("btc"),
+        Some(before - (bump.new_fee - bump.old_fee))
+    );
+
+    assert!(wallet.transaction(&sent.txid).unwrap().has_been_replaced);
+    wallet.mine(1);
+    assert!(wallet
+        .transaction(&bump.new_txid)
+        .unwrap()
+        .block_height
+        .is_some());
+    assert!(wallet
+        .transaction(&sent.txid)
+        .unwrap()
+        .block_height
+        .is_none());
+}
+
+#[test]
+fn test_bump_fee_follows_replacement_chain() {
+    let (wallet, sent) = stuck_payment();
+    let client = wallet.client();
+
+    let first = client
+        .bump_fee(&sent.txid, FeeRate::from_sat_per_vb(8))
+        .unwrap();
+    let second = client
+        .bump_fee(&first.new_txid, FeeRate::from_sat_per_vb(20))
+        .unwrap();
+
+    assert_eq!(second.old_txid, first.new_txid);
+    assert_eq!(second.old_fee, first.new_fee);
+    assert!(second.new_fee > first.new_fee);
+    let replaced: Vec<bool> = [&sent.txid, &first.new_txid, &second.new_txid]
+        .iter()
+        .map(|txid| wallet.transaction(txid).unwrap().has_been_replaced)
+        .collect();
+    assert_eq!(replaced, [true, true, false]);
+}
+
+#[test]
+fn test_bump_fee_rejects_before_sending() {
+    let (wallet, sent) = stuck_payment();
+    let client = wallet.client();
+    let kind = |txid: &str, rate: u64| {
+        client
+            .bump_fee(txid, FeeRate::from_sat_per_vb(rate))
+            .unwrap_err()
+            .kind()
+    };
+
+    // Sent at 5 sat/vB, so 6 sat/vB is the least a replacement may pay
+    assert_eq!(kind(&sent.txid, 5), Some(ErrorKind::InvalidFee));
+    let incoming = wallet.fund(0, 20_000);
+    assert_eq!(kind(&incoming, 30), Some(ErrorKind::Other));
+    let funding = wallet.transactions()[0].txid.clone();
+    assert_eq!(kind(&funding, 30), Some(ErrorKind::Other));
+
+    let bump = client
+        .bump_fee(&sent.txid, FeeRate::from_sat_per_vb(6))
+        .unwrap();
+    assert_eq!(kind(&sent.txid, 50), Some(ErrorKind::Other));
+    wallet.mine(1);
+    assert_eq!(kind(&bump.new_txid, 50), Some(ErrorKind::Other));
+    assert_eq!(sends(&wallet.calls()), 2);
+}
+
+#[test]
+fn test_bump_fee_requires_rbf_signal() {
+    let mut final_tx = unconfirmed_fixture();
+    final_tx["can_rbf"] = Value::Bool(false);
+    final_tx["inputs"][0]["sequence"] = Value::from(0xffff_ffff_u32);
+    let signalling = unconfirmed_fixture();
+    let txid = signalling["txid"].as_str().unwrap().to_string();
+
+    let transport = ScriptedTransport::new().on_prefix(
+        ["get", "transaction"],
+        CliOutput::success(final_tx.to_string()),
+    );
+    let client = GreenClient::with_transport(ClientConfig::default(), transport.clone());
+    let err = client
+        .bump_fee(&txid, FeeRate::from_sat_per_vb(50))
+        .unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::Other));
+    assert_eq!(sends(&transport.calls()), 0);
+
+    let mut replacement = signalling.clone();
+    replacement["txid"] = Value::from("ab".repeat(32));
+    replacement["fee"] = Value::from(1_551);
+    let transport = ScriptedTransport::new()
+        .on_prefix(
+            ["get", "transaction"],
+            CliOutput::success(signalling.to_string()),
+        )
+        .on_prefix(
+            ["send", "transaction"],
+            CliOutput::success(replacement.to_string()),
+        );
+    let client = GreenClient::with_transport(ClientConfig::default(), transport.clone());
+    let bump = client
+        .bump_fee(&txid, FeeRate::from_sat_per_vb(11))
+        .unwrap();
+    assert_eq!(bump.old_fee, 1_410);
+    assert_eq!(bump.new_fee, 1_551);
+
+    let calls = transport.calls();
+    let request: Value = serde_json::from_str(calls[1].params.as_ref().unwrap().json()).unwrap();
+    assert_eq!(request["previous_transaction"]["txid"], Value::from(txid));
+    assert_eq!(request["fee_rate"], Value::from(11));
+    assert_eq!(request["utxos"][0]["vout"], Value::from(1));
+    assert_eq!(request["addressees"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_async_bump_fee() {
+    let (wallet, sent) = stuck_payment();
+    let client = wallet.async_client();
+
+    let bump = client
+        .bump_fee(&sent.txid, FeeRate::from_sat_per_vb(10))
+        .await
+        .unwrap();
+    assert_eq!(bump.old_txid, sent.txid);
+    assert_eq!(bump.fee_rate, FeeRate::from_sat_per_vb(10));
+    let replaced = client.get_transaction_details(&sent.txid).await.unwrap();
+    assert!(replaced.has_been_replaced);
+
+    let err = client
+        .bump_fee(&sent.txid, FeeRate::from_sat_per_vb(20))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::Other));
+}
@@ -0,0 +1,214 @@
+//! Integration tests for paginated transaction history
+//! These tests walk `FakeGreen` history with the sync pager and the async
+//! stream, counting the pages each one fetches
+
+use futures::StreamExt;
+use green_rs::api::{AsyncTransactionApi, TransactionApi};
+use green_rs::testkit::FakeGreen;
+use green_rs::transport::{CliOutput, ScriptedTransport};
+use green_rs::types::{TransactionFilter, TxDirection};
+use green_rs::{AsyncGreenClient, ClientConfig, ErrorKind, GreenClient};
+
+/// Wallet with six transactions, oldest first: three incoming payments, an
+/// outgoing payment, another incoming payment and an incoming asset
+fn ledger() -> (FakeGreen, Vec<String>) {
+    let wallet = FakeGreen::new();
+    let mut txids = Vec::new();
+    for amount in [10_000, 20_000, 30_000] {
+        txids.push(wallet.fund(0, amount));
+        wallet.mine(1);
+    }
+    let sent = wallet
+        .client()
+        .send_to_address("bcrt1q6qk53s4wtrddddtfwwnny6urn8t8mmx4mcfkae", 15_000, None)
+        .unwrap();
+    txids.push(sent.txid);
+    wallet.mine(1);
+    txids.push(wallet.fund(0, 40_000));
+    txids.push(wallet.fund_asset(0, 500, Some("aa".repeat(32))));
+    wallet.mine(1);
+    (wallet, txids)
+}
+
+fn page_fetches(wallet: &FakeGreen) -> usize {
+    wallet
+        .calls()
+        .iter()
+        .filter(|call| call.args[..2] == ["get", "transactions"])
+        .count()
+}
+
+fn timestamp(wallet: &FakeGreen, txid: &str) -> u64 {
+    wallet.transaction(txid).unwrap().timestamp.unwrap()
+}
+
+#[test]
+fn test_pager_walks_every_page() {
+    let (wallet, txids) = ledger();
+    let client = wallet.client();
+
+    let listed: Vec<String> = client
+        .transaction_pager(TransactionFilter::new().subaccount(0).page_size(2))
+        .map(|tx| tx.unwrap().txid)
+        .collect();
+
+    let newest_first: Vec<String> = txids.into_iter().rev().collect();
+    assert_eq!(listed, newest_first);
+    assert_eq!(page_fetches(&wallet), 3);
+}
+
+#[test]
+fn test_pager_stops_when_consumer_stops() {
+    let (wallet, _) = ledger();
+    let client = wallet.client();
+
+    let first_three: Vec<_> = client
+        .transaction_pager(TransactionFilter::new().page_size(2))
+        .take(3)
+        .collect();
+
+    assert_eq!(first_three.len(), 3);
+    assert_eq!(page_fetches(&wallet), 2);
+}
+
+#[test]
+fn test_pager_filters() {
+    let (wallet, txids) = ledger();
+    let client = wallet.client();
+    let txids_of = |filter: TransactionFilter| -> Vec<String> {
+        client
+            .transaction_pager(filter.page_size(2))
+            .map(|tx| tx.unwrap().txid)
+            .collect()
+    };
+
+    assert_eq!(
+        txids_of(TransactionFilter::new().direction(TxDirection::Outgoing)),
+        [txids[3].clone()]
+    );
+    assert_eq!(
+        txids_of(TransactionFilter::new().asset("aa".repeat(32))),
+        [txids[5].clone()]
+    );
+    assert_eq!(txids_of(TransactionFilter::new().asset("btc")).len(), 5);
+    assert!(txids_of(TransactionFilter::new().subaccount(3)).is_empty());
+
+    let until_second = TransactionFilter::new().until(timestamp(&wallet, &txids[1]));
+    assert_eq!(txids_of(until_second), [txids[1].clone(), txids[0].clone()]);
+}
+
+#[test]
+fn test_pager_stops_before_time_range() {
+    let wallet = FakeGreen::new();
+    let mut txids = Vec::new();
+    for amount in [10_000, 20_000, 30_000, 40_000] {
+        txids.push(wallet.fund(0, amount));
+        wallet.mine(1);
+        if txids.len() == 2 {
+            // Four hours between the older and the recent payments
+            wallet.mine(24);
+        }
+    }
+    let client = wallet.client();
+
+    let since = timestamp(&wallet, &txids[2]);
+    let recent: Vec<String> = client
+        .transaction_pager(TransactionFilter::new().since(since).page_size(2))
+        .map(|tx| tx.unwrap().txid)
+        .collect();
+
+    assert_eq!(recent, [txids[3].clone(), txids[2].clone()]);
+    // The second page is more than two hours older, so the walk ends there
+    assert_eq!(page_fetches(&wallet), 2);
+}
+
+#[test]
+fn test_pager_keeps_out_of_order_block_times() {
+    let since = 1_700_000_000;
+    let tx = |txid: &str, height: u32, timestamp: u64| {
+        serde_json::json!({
+            "txid": txid, "version": 2, "locktime": 0, "inputs": [], "outputs": [],
+            "block_height": height, "timestamp": timestamp,
+        })
+    };
+    // Block 101 was timestamped before the range, but block 100 after it
+    let page = serde_json::json!({
+        "transactions": [
+            tx("newest", 102, since + 1_200),
+            tx("early-block", 101, since - 300),
+            tx("late-block", 100, since + 60),
+            tx("old", 99, since - 3_600),
+        ],
+        "more": false,
+    });
+    let transport = ScriptedTransport::new().on(
+        ["get", "transactions", "--json"],
+        CliOutput::success(page.to_string()),
+    );
+    let client = GreenClient::with_transport(ClientConfig::default(), transport);
+
+    let listed: Vec<String> = client
+        .transaction_pager(TransactionFilter::new().since(since))
+        .map(|tx| tx.unwrap().txid)
+        .collect();
+
+    assert_eq!(listed, ["newest", "late-block"]);
+}
+
+#[tokio::test]
+async fn test_stream_matches_pager() {
+    let (wallet, _) = ledger();
+    let filter = TransactionFilter::new()
+        .direction(TxDirection::Incoming)
+        .page_size(4);
+
+    let from_pager: Vec<_> = wallet
+        .client()
+        .transaction_pager(filter.clone())
+        .map(Result::unwrap)
+        .collect();
+    let client = wallet.async_client();
+    let from_stream: Vec<_> = client
+        .transaction_stream(filter)
+        .map(Result::unwrap)
+        .collect()
+        .await;
+
+    assert_eq!(from_stream, from_pager);
+    assert_eq!(from_stream.len(), 5);
+}
+
+#[tokio::test]
+async fn test_stream_stops_early_and_after_errors() {
+    let (wallet, txids) = ledger();
+    let client = wallet.async_client();
+    let newest = client
+        .transaction_stream(TransactionFilter::new().page_size(2))
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(newest.txid, txids[5]);
+    assert_eq!(page_fetches(&wallet), 1);
+
+    let transport = ScriptedTransport::new().on_prefix(
+        ["get", "transactions"],
+        CliOutput::failure(1, "Error: Login required"),
+    );
+    let failing = AsyncGreenClient::with_transport(ClientConfig::default(), transport.clone());
+    let results: Vec<_> = failing
+        .transaction_stream(TransactionFilter::new())
+        .collect()
+        .await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        results[0].as_ref().unwrap_err().kind(),
+        Some(ErrorKind::LoginRequired)
+    );
+
+    let sync_failing = GreenClient::with_transport(ClientConfig::default(), transport.clone());
+    let mut pager = sync_failing.transaction_pager(TransactionFilter::new());
+    assert!(pager.next().unwrap().is_err());
+    assert!(pager.next().is_none());
+    assert_eq!(transport.calls().len(), 2);
+}
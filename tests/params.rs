@@ -0,0 +1,136 @@
+//! Integration tests for passing JSON params to green-cli
+//! These tests point the clients at the mock green-cli script, which records the
+//! command line it received and the params however they were passed
+
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use common::mock_binary;
+use green_rs::transport::{CliOutput, ParamsMode, ScriptedTransport};
+use green_rs::types::transaction::GetTransactionsParams;
+use green_rs::types::wallet::LoginCredentials;
+use green_rs::{AsyncGreenClient, ClientConfig, Error, GreenClient};
+use std::path::Path;
+
+const MNEMONIC: &str =
+    "abandon ability able about above absent absorb abstract absurd abuse access accident";
+
+fn credentials() -> LoginCredentials {
+    LoginCredentials {
+        mnemonic: Some(MNEMONIC.to_string()),
+        pin: None,
+        password: Some("hunter2-secret".to_string()),
+        watch_only: None,
+    }
+}
+
+/// Client config recording argv and params into files under `dir`
+fn recording_config(dir: &Path, mode: ParamsMode) -> ClientConfig {
+    ClientConfig::builder()
+        .binary(mock_binary())
+        .env("MOCK_ARGV_FILE", dir.join("argv"))
+        .env("MOCK_PARAMS_FILE", dir.join("params"))
+        .params_mode(mode)
+        .build()
+}
+
+fn read(dir: &Path, name: &str) -> String {
+    std::fs::read_to_string(dir.join(name)).unwrap()
+}
+
+#[test]
+fn test_plain_params_stay_on_command_line_by_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = GreenClient::with_config(recording_config(dir.path(), ParamsMode::Argv));
+    let params = GetTransactionsParams {
+        subaccount: Some(1),
+        ..Default::default()
+    };
+
+    client
+        .run_command_with_params(&["get", "transactions", "--json"], &params)
+        .unwrap();
+
+    assert!(read(dir.path(), "argv").contains(r#"--params {"subaccount":1}"#));
+    assert_eq!(
+        read(dir.path(), "params").trim(),
+        r#"none {"subaccount":1}"#
+    );
+}
+
+#[test]
+fn test_sensitive_params_never_reach_argv() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = GreenClient::with_config(recording_config(dir.path(), ParamsMode::Argv));
+
+    client
+        .run_command_with_params(&["get", "balance", "--json"], &credentials())
+        .unwrap();
+
+    let argv = read(dir.path(), "argv");
+    assert!(!argv.contains("abandon"));
+    assert!(argv.contains("--params-file"));
+
+    // The params file was private to the user and is gone afterwards
+    let params = read(dir.path(), "params");
+    assert!(params.starts_with("600 "));
+    assert!(params.contains(MNEMONIC));
+    let path = argv.split_whitespace().last().unwrap();
+    assert!(!Path::new(path).exists());
+}
+
+#[test]
+fn test_stdin_mode_passes_params_over_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = GreenClient::with_config(recording_config(dir.path(), ParamsMode::Stdin));
+
+    client
+        .run_command_with_params(&["get", "balance", "--json"], &credentials())
+        .unwrap();
+
+    assert!(read(dir.path(), "argv").ends_with("--params -\n"));
+    assert!(read(dir.path(), "params").contains(MNEMONIC));
+}
+
+#[tokio::test]
+async fn test_async_file_mode_passes_params_in_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = AsyncGreenClient::with_config(recording_config(dir.path(), ParamsMode::File));
+    let params = GetTransactionsParams {
+        count: Some(5),
+        ..Default::default()
+    };
+
+    client
+        .run_command_with_params(&["get", "transactions", "--json"], &params)
+        .await
+        .unwrap();
+
+    assert!(!read(dir.path(), "argv").contains("count"));
+    assert_eq!(read(dir.path(), "params").trim(), r#"600 {"count":5}"#);
+}
+
+#[test]
+fn test_secrets_are_redacted_from_errors() {
+    let transport = ScriptedTransport::new().on_prefix(
+        ["login"],
+        CliOutput::failure(1, format!("Error: id_login_failed for mnemonic {MNEMONIC}")),
+    );
+    let client = GreenClient::with_transport(ClientConfig::default(), transport.clone());
+
+    let err = client
+        .run_command_with_params(&["login", "--json"], &credentials())
+        .unwrap_err();
+
+    match err {
+        Error::Cli(ref cli) => {
+            assert!(!cli.stderr.contains("abandon"));
+            assert!(cli.message.contains("<redacted>"));
+        }
+        ref other => panic!("Expected CLI error, got {:?}", other),
+    }
+    assert!(!err.to_string().contains("abandon"));
+    assert!(!format!("{:?}", transport.calls()).contains("abandon"));
+    assert!(!format!("{:?}", credentials()).contains("hunter2"));
+}
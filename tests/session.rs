@@ -0,0 +1,171 @@
+//! Integration tests for the wallet session lifecycle
+//! These tests create, unlock and lock wallets through `FakeGreen`, and check
+//! the commands sent to the mock green-cli script
+
+use green_rs::api::{AsyncWalletApi, TransactionApi, TxBuilder, WalletApi, WalletExt};
+use green_rs::testkit::FakeGreen;
+use green_rs::types::wallet::{CreateWalletRequest, LoginCredentials, WatchOnlyData};
+use green_rs::{ClientConfig, ErrorKind, GreenClient};
+use std::path::PathBuf;
+
+const MNEMONIC: &str =
+    "legal winner thank year wave sausage worth useful legal winner thank yellow";
+
+#[test]
+fn test_bootstrap_wallet_with_pin() {
+    let wallet = FakeGreen::without_wallet();
+    let client = wallet.client();
+
+    let err = client.get_wallet_info().unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::NotFound));
+
+    let info = client
+        .create_wallet(CreateWalletRequest::new(MNEMONIC).with_name("Regtest"))
+        .unwrap();
+    assert_eq!(info.name, "Regtest");
+    assert_eq!(info.network, "electrum-localtest");
+    assert!(!info.is_locked);
+    assert!(!info.watch_only);
+    assert_eq!(wallet.mnemonic().as_deref(), Some(MNEMONIC));
+
+    client.set_pin("246810").unwrap();
+    client.logout().unwrap();
+    assert!(client.get_wallet_info().unwrap().is_locked);
+
+    let info = client.login(LoginCredentials::from_pin("246810")).unwrap();
+    assert!(!info.is_locked);
+    client.get_balance().unwrap();
+}
+
+#[test]
+fn test_locked_wallet_requires_login() {
+    let wallet = FakeGreen::new();
+    let client = wallet.client();
+    client.logout().unwrap();
+
+    let err = client.get_balance().unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::LoginRequired));
+
+    let mnemonic = wallet.mnemonic().unwrap();
+    client
+        .login(LoginCredentials::from_mnemonic(mnemonic))
+        .unwrap();
+    client.get_balance().unwrap();
+}
+
+#[test]
+fn test_rejected_credentials() {
+    let wallet = FakeGreen::without_wallet();
+    let client = wallet.client();
+
+    let err = client
+        .create_wallet(CreateWalletRequest::new("not a mnemonic"))
+        .unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::InvalidCredentials));
+
+    client
+        .create_wallet(CreateWalletRequest::new(MNEMONIC).with_password("hunter2"))
+        .unwrap();
+    client.set_pin("1234").unwrap();
+    client.logout().unwrap();
+
+    let wrong_pin = client
+        .login(LoginCredentials::from_pin("4321"))
+        .unwrap_err();
+    assert_eq!(wrong_pin.kind(), Some(ErrorKind::InvalidCredentials));
+    let no_password = client
+        .login(LoginCredentials::from_mnemonic(MNEMONIC))
+        .unwrap_err();
+    assert_eq!(no_password.kind(), Some(ErrorKind::InvalidCredentials));
+
+    client
+        .login(LoginCredentials::from_mnemonic(MNEMONIC).with_password("hunter2"))
+        .unwrap();
+}
+
+#[test]
+fn test_watch_only_session_cannot_sign() {
+    let wallet = FakeGreen::new();
+    wallet.fund(0, 50_000);
+    let client = wallet.client();
+    client.logout().unwrap();
+
+    let stranger = WatchOnlyData {
+        xpub: "tpubsomeoneelse".to_string(),
+        core_descriptors: None,
+    };
+    assert!(client
+        .login(LoginCredentials::from_watch_only(stranger))
+        .is_err());
+
+    let info = client
+        .login(LoginCredentials::from_watch_only(
+            wallet.watch_only_data().unwrap(),
+        ))
+        .unwrap();
+    assert!(info.watch_only);
+    assert_eq!(client.get_balance().unwrap().get("btc"), Some(50_000));
+
+    assert!(client
+        .send_to_address("bcrt1qvewsdxxmeraett7ztsaym88jsrv85kzmev28cl", 10_000, None)
+        .is_err());
+    let signed = TxBuilder::with_client(client.clone())
+        .add_output(
+            "bcrt1qvewsdxxmeraett7ztsaym88jsrv85kzmev28cl".to_string(),
+            10_000,
+        )
+        .dump()
+        .unwrap()
+        .sign();
+    assert!(signed.is_err());
+    assert!(client.set_pin("1234").is_err());
+}
+
+#[tokio::test]
+async fn test_async_session_lifecycle() {
+    let wallet = FakeGreen::without_wallet();
+    let client = wallet.async_client();
+
+    client
+        .create_wallet(CreateWalletRequest::new(MNEMONIC))
+        .await
+        .unwrap();
+    client.set_pin("1357").await.unwrap();
+    client.logout().await.unwrap();
+    assert!(client.get_wallet_info().await.unwrap().is_locked);
+
+    let info = client
+        .login(LoginCredentials::from_pin("1357"))
+        .await
+        .unwrap();
+    assert!(!info.is_locked);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_session_commands_keep_secrets_off_argv() {
+    let dir = tempfile::tempdir().unwrap();
+    let mock = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/green-cli");
+    let client = GreenClient::with_config(
+        ClientConfig::builder()
+            .binary(mock)
+            .env("MOCK_ARGV_FILE", dir.path().join("argv"))
+            .build(),
+    );
+    let argv = || std::fs::read_to_string(dir.path().join("argv")).unwrap();
+
+    let info = client
+        .create_wallet(CreateWalletRequest::new(MNEMONIC))
+        .unwrap();
+    assert_eq!(info.name, "Green Wallet");
+    assert!(argv().starts_with("create wallet --json --params-file "));
+
+    client.login(LoginCredentials::from_pin("2468")).unwrap();
+    assert!(argv().starts_with("login --json --params-file "));
+
+    client.set_pin("2468").unwrap();
+    assert!(!argv().contains("2468"));
+
+    client.logout().unwrap();
+    assert_eq!(argv().trim(), "logout --json");
+}
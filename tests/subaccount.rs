@@ -0,0 +1,160 @@
+//! Integration tests for subaccount operations on both clients
+//! These tests check that `GreenClient` and `AsyncGreenClient` issue the same
+//! commands and map the same responses to the same values
+
+use green_rs::api::subaccount::{AsyncSubaccountExt, SubaccountExt};
+use green_rs::api::utxo::{AsyncUtxoApi, UtxoApi};
+use green_rs::testkit::FakeGreen;
+use green_rs::transport::{CliOutput, ScriptedTransport};
+use green_rs::types::subaccount::{CreateSubaccountParams, SubaccountType, UpdateSubaccountParams};
+use green_rs::types::{GetUnspentOutputsParams, UtxoSortBy};
+use green_rs::{AsyncGreenClient, ClientConfig, ErrorKind, GreenClient};
+use serde_json::Value;
+
+const SUBACCOUNTS: &str = r#"{"subaccounts": [
+    {"pointer": 0, "name": "Main", "type": "2of2", "hidden": false},
+    {"pointer": 1, "name": "Savings", "type": "2of3", "recovery_xpub": "tpubrecovery",
+     "required_ca": 1, "available_ca": 2, "hidden": true}
+]}"#;
+
+fn create_params(name: &str) -> CreateSubaccountParams {
+    CreateSubaccountParams {
+        name: name.to_string(),
+        subaccount_type: SubaccountType::P2wpkh,
+        recovery_mnemonic: None,
+        recovery_xpub: None,
+    }
+}
+
+fn rename(name: &str) -> UpdateSubaccountParams {
+    UpdateSubaccountParams {
+        name: Some(name.to_string()),
+        hidden: None,
+    }
+}
+
+fn to_value<V: serde::Serialize>(value: &V) -> Value {
+    serde_json::to_value(value).unwrap()
+}
+
+#[tokio::test]
+async fn test_clients_map_subaccount_responses_identically() {
+    let transport = ScriptedTransport::new()
+        .on(
+            ["get", "subaccounts", "--json"],
+            CliOutput::success(SUBACCOUNTS),
+        )
+        .on_prefix(
+            ["get", "subaccount"],
+            CliOutput::success(r#"{"pointer": 1, "name": "Savings", "type": "2of3"}"#),
+        );
+    let sync_client = GreenClient::with_transport(ClientConfig::default(), transport.clone());
+    let async_client = AsyncGreenClient::with_transport(ClientConfig::default(), transport.clone());
+
+    let sync_list = sync_client.get_subaccounts().unwrap();
+    let async_list = async_client.get_subaccounts().await.unwrap();
+    assert_eq!(to_value(&sync_list), to_value(&async_list));
+    assert_eq!(async_list.len(), 2);
+    assert!(async_list[1].hidden);
+    assert_eq!(async_list[1].recovery_xpub.as_deref(), Some("tpubrecovery"));
+
+    let sync_one = sync_client.get_subaccount(1).unwrap();
+    let async_one = async_client.get_subaccount(1).await.unwrap();
+    assert_eq!(to_value(&sync_one), to_value(&async_one));
+
+    // Both clients issued exactly the same commands
+    let calls = transport.calls();
+    assert_eq!(calls.len(), 4);
+    assert_eq!(calls[0].args, calls[1].args);
+    assert_eq!(calls[2].args, calls[3].args);
+    assert_eq!(
+        calls[2].args,
+        ["get", "subaccount", "--subaccount", "1", "--json"]
+    );
+}
+
+#[tokio::test]
+async fn test_async_create_and_rename_subaccount() {
+    let wallet = FakeGreen::new();
+    let client = wallet.async_client();
+
+    let created = client
+        .create_subaccount(create_params("Spending"))
+        .await
+        .unwrap();
+    assert_eq!(created.pointer, 1);
+    assert_eq!(created.subaccount_type, SubaccountType::P2wpkh);
+
+    let renamed = client.update_subaccount(1, rename("Daily")).await.unwrap();
+    assert_eq!(renamed.name, "Daily");
+    assert_eq!(client.get_subaccount(1).await.unwrap().name, "Daily");
+
+    let names: Vec<String> = client
+        .get_subaccounts()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|sub| sub.name)
+        .collect();
+    assert_eq!(names, ["Main Account", "Daily"]);
+
+    let err = client.get_subaccount(7).await.unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::NotFound));
+}
+
+#[tokio::test]
+async fn test_sync_and_async_subaccount_flows_match() {
+    let sync_wallet = FakeGreen::new();
+    let async_wallet = FakeGreen::new();
+    let sync_client = sync_wallet.client();
+    let async_client = async_wallet.async_client();
+
+    let sync_created = sync_client.create_subaccount(create_params("Ops")).unwrap();
+    let async_created = async_client
+        .create_subaccount(create_params("Ops"))
+        .await
+        .unwrap();
+    assert_eq!(to_value(&sync_created), to_value(&async_created));
+
+    let sync_updated = sync_client
+        .update_subaccount(1, rename("Treasury"))
+        .unwrap();
+    let async_updated = async_client
+        .update_subaccount(1, rename("Treasury"))
+        .await
+        .unwrap();
+    assert_eq!(to_value(&sync_updated), to_value(&async_updated));
+
+    assert_eq!(
+        to_value(&sync_client.get_subaccounts().unwrap()),
+        to_value(&async_client.get_subaccounts().await.unwrap())
+    );
+    let sync_args: Vec<_> = sync_wallet.calls().into_iter().map(|c| c.args).collect();
+    let async_args: Vec<_> = async_wallet.calls().into_iter().map(|c| c.args).collect();
+    assert_eq!(sync_args, async_args);
+}
+
+#[tokio::test]
+async fn test_clients_group_and_sort_utxos_identically() {
+    let wallet = FakeGreen::new();
+    wallet.fund(0, 5_000);
+    wallet.fund(0, 90_000);
+    wallet.fund_asset(0, 700, Some("aa".repeat(32)));
+    wallet.fund(0, 20_000);
+    let params = GetUnspentOutputsParams {
+        sort_by: Some(UtxoSortBy::ValueDesc),
+        ..Default::default()
+    };
+
+    let sync_utxos = wallet.client().get_unspent_outputs(params.clone()).unwrap();
+    let async_utxos = wallet
+        .async_client()
+        .get_unspent_outputs(params)
+        .await
+        .unwrap();
+
+    assert_eq!(sync_utxos, async_utxos);
+    let values: Vec<u64> = sync_utxos["btc"].iter().map(|u| u.satoshi).collect();
+    assert_eq!(values, [90_000, 20_000, 5_000]);
+    assert_eq!(sync_utxos[&"aa".repeat(32)].len(), 1);
+}
@@ -0,0 +1,360 @@
+//! Integration tests for the stateful fake Green wallet
+//! These tests run full wallet flows through `FakeGreen` without spawning green-cli
+
+use green_rs::api::address::AddressApi;
+use green_rs::api::subaccount::SubaccountExt;
+use green_rs::api::utxo::UtxoApi;
+use green_rs::api::{AsyncTransactionApi, AsyncWalletExt, TransactionApi, TxBuilder, WalletExt};
+use green_rs::testkit::FakeGreen;
+use green_rs::types::address::{
+    AddressType, GetPreviousAddressesRequest, GetReceiveAddressRequest,
+};
+use green_rs::types::subaccount::{CreateSubaccountParams, SubaccountType};
+use green_rs::types::transaction::{Addressee, CreateTransactionRequest};
+use green_rs::types::{FeeRate, GetUnspentOutputsParams};
+use green_rs::{Error, ErrorKind};
+
+const RECIPIENT: &str = "bcrt1q6qk53s4wtrddddtfwwnny6urn8t8mmx4mcfkae";
+
+#[test]
+fn test_create_sign_send_updates_balance_and_utxos() {
+    let wallet = FakeGreen::new();
+    let funding = wallet.fund(0, 100_000);
+    wallet.mine(1);
+    let client = wallet.client();
+
+    let txid = TxBuilder::with_client(client.clone())
+        .add_output(RECIPIENT.to_string(), 30_000)
+        .set_fee_rate(FeeRate::from_sat_per_vb(2))
+        .dump()
+        .unwrap()
+        .sign()
+        .unwrap()
+        .send()
+        .unwrap();
+
+    let tx = client.get_transaction_details(&txid).unwrap();
+    let fee = tx.fee.unwrap();
+    assert!(fee > 0);
+    assert_eq!(tx.inputs[0].txid, funding);
+    assert_eq!(tx.confirmations, 0);
+
+    let balance = client.get_balance().unwrap();
+    assert_eq!(balance.get("btc"), Some(100_000 - 30_000 - fee));
+
+    // The funding output is spent and only the change remains
+    let utxos = client
+        .get_unspent_outputs(GetUnspentOutputsParams::default())
+        .unwrap();
+    let btc = &utxos["btc"];
+    assert_eq!(btc.len(), 1);
+    assert_eq!(btc[0].txhash, txid);
+    assert!(btc[0].is_internal);
+}
+
+#[test]
+fn test_send_requires_signature() {
+    let wallet = FakeGreen::new();
+    wallet.fund(0, 50_000);
+
+    let builder = TxBuilder::with_client(wallet.client())
+        .add_output(RECIPIENT.to_string(), 10_000)
+        .dump()
+        .unwrap();
+    // Bypass the builder's own check by sending the unsigned file directly
+    let path = builder.get_temp_path().unwrap().to_string();
+    let result = wallet
+        .client()
+        .run_command(&["tx", "send", "--file", &path, "--json"]);
+
+    assert!(matches!(result, Err(Error::Cli(err)) if err.message.contains("not signed")));
+    assert_eq!(wallet.balance(None).get("btc"), Some(50_000));
+}
+
+#[test]
+fn test_insufficient_funds_leaves_wallet_untouched() {
+    let wallet = FakeGreen::new();
+    wallet.fund(0, 20_000);
+
+    let result = TxBuilder::with_client(wallet.client())
+        .add_output(RECIPIENT.to_string(), 25_000)
+        .dump()
+        .unwrap()
+        .sign();
+
+    assert_eq!(
+        result.err().and_then(|err| err.kind()),
+        Some(ErrorKind::InsufficientFunds)
+    );
+    assert_eq!(wallet.utxos().len(), 1);
+    assert_eq!(wallet.transactions().len(), 1);
+}
+
+#[test]
+fn test_mining_confirms_transactions_and_utxos() {
+    let wallet = FakeGreen::new();
+    let txid = wallet.fund(0, 10_000);
+    let height = wallet.block_height();
+
+    assert_eq!(wallet.transaction(&txid).unwrap().confirmations, 0);
+    wallet.mine(3);
+
+    assert_eq!(wallet.block_height(), height + 3);
+    assert_eq!(wallet.transaction(&txid).unwrap().confirmations, 3);
+    assert_eq!(wallet.utxos()[0].confirmations, Some(3));
+
+    let confirmed = wallet
+        .client()
+        .get_unspent_outputs(GetUnspentOutputsParams {
+            min_confs: Some(4),
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(confirmed.is_empty());
+}
+
+#[test]
+fn test_payment_to_own_subaccount_creates_utxo() {
+    let wallet = FakeGreen::new();
+    let client = wallet.client();
+    wallet.fund(0, 80_000);
+
+    let savings = client
+        .create_subaccount(CreateSubaccountParams {
+            name: "Savings".to_string(),
+            subaccount_type: SubaccountType::P2wpkh,
+            recovery_mnemonic: None,
+            recovery_xpub: None,
+        })
+        .unwrap();
+    let address = client
+        .get_receive_address(GetReceiveAddressRequest {
+            subaccount: Some(savings.pointer),
+            address_type: None,
+        })
+        .unwrap();
+    assert_eq!(address.address_type, AddressType::P2wpkh);
+
+    client
+        .create_transaction(CreateTransactionRequest {
+            addressees: Some(vec![Addressee {
+                address: address.address.clone(),
+                satoshi: 25_000,
+                asset_id: None,
+            }]),
+            subaccount: Some(0),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(wallet.balance(Some(savings.pointer)).get("btc"), None);
+
+    let sent = client
+        .send_to_address(&address.address, 25_000, None)
+        .unwrap();
+    assert_eq!(sent.tx_type.as_deref(), Some("redeposit"));
+    assert_eq!(
+        wallet.balance(Some(savings.pointer)).get("btc"),
+        Some(25_000)
+    );
+
+    let used = client
+        .get_previous_addresses(GetPreviousAddressesRequest {
+            subaccount: Some(savings.pointer),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(used.len(), 1);
+    assert!(used[0].is_used);
+    assert_eq!(used[0].tx_count, 1);
+    assert_eq!(client.get_subaccounts().unwrap().len(), 2);
+}
+
+#[test]
+fn test_send_all_sweeps_subaccount() {
+    let wallet = FakeGreen::new();
+    wallet.fund(0, 40_000);
+    wallet.fund(0, 60_000);
+
+    let result = wallet
+        .client()
+        .create_transaction(CreateTransactionRequest {
+            addressees: Some(vec![Addressee {
+                address: RECIPIENT.to_string(),
+                satoshi: 0,
+                asset_id: None,
+            }]),
+            send_all: true,
+            fee_rate: Some(FeeRate::from_sat_per_vb(1)),
+            ..Default::default()
+        })
+        .unwrap();
+
+    assert_eq!(result.transaction.inputs.len(), 2);
+    assert_eq!(result.transaction.outputs.len(), 1);
+    assert_eq!(
+        result.transaction.outputs[0].satoshi + result.estimated_fee,
+        100_000
+    );
+}
+
+#[test]
+fn test_builder_sweeps_subaccount_and_chosen_inputs() {
+    let wallet = FakeGreen::new();
+    let client = wallet.client();
+    wallet.fund(0, 40_000);
+    let kept = wallet.fund(0, 60_000);
+    wallet.fund(0, 20_000);
+
+    // Only the chosen output is swept
+    let txid = TxBuilder::with_client(client.clone())
+        .send_all(RECIPIENT.to_string())
+        .add_input(format!("{kept}:0"))
+        .set_fee_rate(FeeRate::from_sat_per_vb(2))
+        .dump()
+        .unwrap()
+        .sign()
+        .unwrap()
+        .send()
+        .unwrap();
+    let tx = client.get_transaction_details(&txid).unwrap();
+    assert_eq!(tx.inputs.len(), 1);
+    assert_eq!(tx.outputs[0].satoshi, 60_000 - 2 * (11 + 68 + 31));
+    assert_eq!(wallet.balance(Some(0)).get("btc"), Some(60_000));
+
+    let builder = TxBuilder::with_client(client.clone())
+        .sweep_subaccount(0, RECIPIENT.to_string())
+        .set_fee_rate(FeeRate::from_sat_per_vb(2));
+    let (swept, fee) = builder.check_sweep().unwrap();
+    assert_eq!(fee, 2 * (11 + 2 * 68 + 31));
+    assert_eq!(swept + fee, 60_000);
+    builder.dump().unwrap().sign().unwrap().send().unwrap();
+    assert_eq!(wallet.balance(Some(0)).get("btc"), None);
+}
+
+#[test]
+fn test_builder_sweep_sanity_checks() {
+    let wallet = FakeGreen::new();
+    let client = wallet.client();
+    wallet.fund(0, 5_000);
+    let sweep = || {
+        TxBuilder::with_client(client.clone())
+            .sweep_subaccount(0, RECIPIENT.to_string())
+            .set_fee_rate(FeeRate::from_sat_per_vb(10))
+    };
+
+    // 1 100 sat of fees is 22% of the 5 000 sat swept
+    let err = sweep().dump().err().unwrap();
+    assert_eq!(err.kind(), Some(ErrorKind::InvalidFee));
+    assert!(sweep().set_max_fee_percent(25.0).dump().is_ok());
+
+    let err = sweep()
+        .set_fee_rate(FeeRate::from_sat_per_vb(45))
+        .set_max_fee_percent(100.0)
+        .dump()
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), Some(ErrorKind::InsufficientFunds));
+
+    // 3 900 sat are left after fees, below a 4 000 sat dust limit
+    let err = sweep()
+        .set_max_fee_percent(100.0)
+        .set_dust_limit(4_000)
+        .dump()
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), Some(ErrorKind::InsufficientFunds));
+
+    let err = sweep()
+        .add_output(RECIPIENT.to_string(), 1_000)
+        .dump()
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), Some(ErrorKind::InvalidAmount));
+    assert_eq!(wallet.balance(Some(0)).get("btc"), Some(5_000));
+}
+
+#[test]
+fn test_builder_sweeps_single_asset() {
+    let wallet = FakeGreen::new();
+    let asset = "aa".repeat(32);
+    wallet.fund(0, 50_000);
+    wallet.fund_asset(0, 700, Some(asset.clone()));
+    wallet.fund_asset(0, 300, Some(asset.clone()));
+
+    let txid = TxBuilder::with_client(wallet.client())
+        .sweep_subaccount(0, RECIPIENT.to_string())
+        .set_sweep_asset(asset.clone())
+        .dump()
+        .unwrap()
+        .sign()
+        .unwrap()
+        .send()
+        .unwrap();
+
+    let tx = wallet.transaction(&txid).unwrap();
+    let paid = tx.outputs.iter().find(|output| !output.is_change).unwrap();
+    assert_eq!(paid.asset_id.as_deref(), Some(asset.as_str()));
+    assert_eq!(paid.satoshi, 1_000);
+    let balance = wallet.balance(Some(0));
+    assert_eq!(balance.get(&asset), None);
+    assert_eq!(balance.get("btc"), Some(50_000 - tx.fee.unwrap()));
+}
+
+#[test]
+fn test_builder_asset_sweep_checks_lbtc_fee() {
+    let wallet = FakeGreen::new();
+    let asset = "aa".repeat(32);
+    wallet.fund(0, 1_000);
+    wallet.fund_asset(0, 700, Some(asset.clone()));
+    wallet.fund_asset(0, 300, Some(asset.clone()));
+    let sweep = || {
+        TxBuilder::with_client(wallet.client())
+            .sweep_subaccount(0, RECIPIENT.to_string())
+            .set_sweep_asset(asset.clone())
+            .set_fee_rate(FeeRate::from_sat_per_vb(1))
+    };
+
+    // The L-BTC fee is compared with the 1 000 sat of L-BTC spent, not with
+    // the amount of the swept asset
+    let err = sweep().check_sweep().unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::InvalidFee));
+    assert!(err.to_string().contains("of L-BTC"));
+
+    let (swept, fee) = sweep().set_max_fee_percent(30.0).check_sweep().unwrap();
+    assert_eq!(swept, 1_000);
+    // Three inputs, the recipient and L-BTC change at 1 sat/vB
+    assert_eq!(fee, 11 + 3 * 68 + 2 * 31);
+}
+
+#[test]
+fn test_transaction_history_pages() {
+    let wallet = FakeGreen::new();
+    let txids: Vec<_> = (1..=5).map(|n| wallet.fund(0, n * 1_000)).collect();
+    let client = wallet.client();
+
+    let first = client.get_transactions(Some(0), None, Some(2)).unwrap();
+    assert!(first.more);
+    assert_eq!(first.next_page.as_deref(), Some("2"));
+    assert_eq!(first.transactions[0].txid, txids[4]);
+
+    let last = client.get_transactions(Some(0), Some(4), Some(2)).unwrap();
+    assert!(!last.more);
+    assert_eq!(last.transactions.len(), 1);
+    assert_eq!(last.transactions[0].txid, txids[0]);
+}
+
+#[tokio::test]
+async fn test_async_client_shares_wallet_state() {
+    let wallet = FakeGreen::new();
+    wallet.fund(0, 70_000);
+    let client = wallet.async_client();
+
+    let tx = client
+        .send_to_address(RECIPIENT, 20_000, None)
+        .await
+        .unwrap();
+    let balance = client.get_balance().await.unwrap();
+
+    assert_eq!(balance.get("btc"), Some(70_000 - 20_000 - tx.fee.unwrap()));
+    assert_eq!(wallet.calls().len(), 2);
+}
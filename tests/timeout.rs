@@ -0,0 +1,212 @@
+//! Integration tests for command timeouts and cancellation
+//! These tests point the clients at the mock green-cli script and make it hang with `MOCK_SLEEP`
+
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use common::mock_binary;
+use green_rs::api::{AsyncWalletExt, WalletExt};
+use green_rs::transport::ParamsMode;
+use green_rs::types::address::UpdateAddressRequest;
+use green_rs::{AsyncGreenClient, ClientConfig, Error, GreenClient};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+fn hanging_config(pid_file: &Path, timeout: Duration) -> ClientConfig {
+    ClientConfig::builder()
+        .binary(mock_binary())
+        .env("MOCK_SLEEP", "30")
+        .env("MOCK_PID_FILE", pid_file)
+        .timeout(timeout)
+        .build()
+}
+
+/// Params far larger than a pipe buffer, sent over stdin to a mock that never reads them
+fn unread_stdin_config(pid_file: &Path) -> (ClientConfig, UpdateAddressRequest) {
+    let config = ClientConfig::builder()
+        .binary(mock_binary())
+        .env("MOCK_SLEEP", "30")
+        .env("MOCK_PID_FILE", pid_file)
+        .params_mode(ParamsMode::Stdin)
+        .timeout(Duration::from_millis(300))
+        .build();
+    (
+        config,
+        UpdateAddressRequest::label(0, 1, "x".repeat(1 << 20)),
+    )
+}
+
+/// Read the pid the mock wrote, waiting briefly for it to appear
+fn read_pid(pid_file: &Path) -> u32 {
+    for _ in 0..100 {
+        if let Ok(contents) = std::fs::read_to_string(pid_file) {
+            if let Ok(pid) = contents.trim().parse() {
+                return pid;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    panic!("mock green-cli never wrote its pid");
+}
+
+/// Whether `pid` is still running (zombies awaiting reaping count as exited)
+fn is_running(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{pid}/stat"))
+        .map(|stat| {
+            let state = stat.rsplit(')').next().unwrap_or("").trim_start();
+            !state.starts_with('Z')
+        })
+        .unwrap_or(false)
+}
+
+/// Wait for `pid` to exit, returning whether it did within a second
+fn wait_for_exit(pid: u32) -> bool {
+    for _ in 0..100 {
+        if !is_running(pid) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+#[test]
+fn test_sync_command_times_out_and_is_killed() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("pid");
+    let client = GreenClient::with_config(hanging_config(&pid_file, Duration::from_millis(300)));
+
+    let started = Instant::now();
+    match client.get_balance() {
+        Err(Error::Timeout) => {}
+        other => panic!("Expected timeout, got {:?}", other),
+    }
+    assert!(started.elapsed() < Duration::from_secs(10));
+
+    let pid = read_pid(&pid_file);
+    assert!(wait_for_exit(pid), "green-cli was not killed");
+}
+
+#[test]
+fn test_sync_per_call_timeout_override() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("pid");
+    let client = GreenClient::with_config(
+        ClientConfig::builder()
+            .binary(mock_binary())
+            .env("MOCK_SLEEP", "30")
+            .env("MOCK_PID_FILE", &pid_file)
+            .no_timeout()
+            .build(),
+    );
+
+    let result = client.run_command_with_timeout(
+        &["get", "balance", "--json"],
+        Some(Duration::from_millis(200)),
+    );
+    assert!(matches!(result, Err(Error::Timeout)));
+    assert!(wait_for_exit(read_pid(&pid_file)));
+}
+
+#[test]
+fn test_sync_timeout_covers_unread_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("pid");
+    let (config, params) = unread_stdin_config(&pid_file);
+    let client = GreenClient::with_config(config);
+
+    let started = Instant::now();
+    let result = client.run_command_with_params(&["update", "address", "--json"], &params);
+    assert!(matches!(result, Err(Error::Timeout)));
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert!(wait_for_exit(read_pid(&pid_file)));
+}
+
+#[test]
+fn test_sync_fast_command_within_timeout() {
+    let client = GreenClient::with_config(
+        ClientConfig::builder()
+            .binary(mock_binary())
+            .timeout(Duration::from_secs(10))
+            .build(),
+    );
+
+    let balance = client
+        .get_balance()
+        .expect("Expected balance within timeout");
+    assert_eq!(balance.get("btc"), Some(100000));
+}
+
+#[tokio::test]
+async fn test_async_command_times_out_and_is_killed() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("pid");
+    let client =
+        AsyncGreenClient::with_config(hanging_config(&pid_file, Duration::from_millis(300)));
+
+    let started = Instant::now();
+    match client.get_fee_estimates().await {
+        Err(Error::Timeout) => {}
+        other => panic!("Expected timeout, got {:?}", other),
+    }
+    assert!(started.elapsed() < Duration::from_secs(10));
+
+    let pid = read_pid(&pid_file);
+    assert!(wait_for_exit(pid), "green-cli was not killed");
+}
+
+#[tokio::test]
+async fn test_async_per_call_timeout_override() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("pid");
+    let client = AsyncGreenClient::with_config(hanging_config(&pid_file, Duration::from_secs(60)));
+
+    let result = client
+        .run_command_with_timeout(
+            &["get", "balance", "--json"],
+            Some(Duration::from_millis(200)),
+        )
+        .await;
+    assert!(matches!(result, Err(Error::Timeout)));
+    assert!(wait_for_exit(read_pid(&pid_file)));
+}
+
+#[tokio::test]
+async fn test_async_timeout_covers_unread_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("pid");
+    let (config, params) = unread_stdin_config(&pid_file);
+    let client = AsyncGreenClient::with_config(config);
+
+    let started = Instant::now();
+    let result = client
+        .run_command_with_params(&["update", "address", "--json"], &params)
+        .await;
+    assert!(matches!(result, Err(Error::Timeout)));
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert!(wait_for_exit(read_pid(&pid_file)));
+}
+
+#[tokio::test]
+async fn test_async_dropped_future_kills_child() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("pid");
+    let client = AsyncGreenClient::with_config(
+        ClientConfig::builder()
+            .binary(mock_binary())
+            .env("MOCK_SLEEP", "30")
+            .env("MOCK_PID_FILE", &pid_file)
+            .no_timeout()
+            .build(),
+    );
+
+    // Abandon the command from the outside once the child is running
+    let cancelled = tokio::time::timeout(Duration::from_millis(300), client.get_balance()).await;
+    assert!(cancelled.is_err());
+
+    assert!(
+        wait_for_exit(read_pid(&pid_file)),
+        "green-cli was not killed"
+    );
+}
@@ -0,0 +1,186 @@
+//! Integration tests for transaction API functionality
+//! These tests mock green-cli with a shell script stub to test JSON deserialization and error paths
+
+mod common;
+
+use common::TestEnvironment;
+use green_rs::api::{AsyncTransactionApi, TransactionApi};
+use green_rs::types::transaction::{Addressee, CreateTransactionRequest};
+use green_rs::types::FeeRate;
+use green_rs::{AsyncGreenClient, Error, ErrorKind, GreenClient};
+
+const TXID: &str = "8f2d7ac5b5cb3d8e1c9a1d6a2e9c3b1f4e5d6c7b8a9f0e1d2c3b4a5968778695";
+
+fn create_request() -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        addressees: Some(vec![Addressee {
+            address: "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_string(),
+            satoshi: 50000,
+            asset_id: None,
+        }]),
+        fee_rate: Some(FeeRate::from_sat_per_vb(10)),
+        subaccount: Some(0),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_sync_get_transactions_with_mock() {
+    let _env = TestEnvironment::new();
+
+    let client = GreenClient::new();
+    let list = client
+        .get_transactions(Some(0), Some(0), Some(2))
+        .expect("Expected successful transaction list");
+
+    assert_eq!(list.transactions.len(), 2);
+    assert!(list.more);
+    assert_eq!(list.next_page.as_deref(), Some("2"));
+    assert_eq!(list.transactions[0].txid, TXID);
+    assert_eq!(list.transactions[0].confirmations, 6);
+    assert_eq!(list.transactions[1].tx_type.as_deref(), Some("incoming"));
+    assert!(!list.transactions[1].can_rbf);
+}
+
+#[test]
+fn test_sync_get_transaction_details_with_mock() {
+    let _env = TestEnvironment::new();
+
+    let client = GreenClient::new();
+    let tx = client
+        .get_transaction_details(TXID)
+        .expect("Expected successful transaction details");
+
+    assert_eq!(tx.txid, TXID);
+    assert_eq!(tx.inputs.len(), 1);
+    assert_eq!(tx.outputs.len(), 2);
+    assert_eq!(tx.fee, Some(1410));
+    assert_eq!(tx.vsize, Some(141));
+    assert!(tx.outputs[1].is_change);
+    assert_eq!(tx.block_height, Some(800_000));
+}
+
+#[test]
+fn test_sync_create_transaction_with_mock() {
+    let _env = TestEnvironment::new();
+
+    let client = GreenClient::new();
+    let result = client
+        .create_transaction(create_request())
+        .expect("Expected successful transaction creation");
+
+    assert_eq!(result.estimated_vsize, 141);
+    assert_eq!(result.estimated_fee, 1410);
+    assert_eq!(result.inputs_to_sign.len(), 1);
+    assert_eq!(result.inputs_to_sign[0].required_signatures, 2);
+    assert_eq!(result.transaction.txid, TXID);
+    assert!(!result.unsigned_hex.is_empty());
+}
+
+#[test]
+fn test_sync_send_to_address_with_mock() {
+    let _env = TestEnvironment::new();
+
+    let client = GreenClient::new();
+    let tx = client
+        .send_to_address("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq", 50000, None)
+        .expect("Expected successful send");
+
+    assert_eq!(tx.txid, TXID);
+    assert_eq!(tx.outputs[0].satoshi, 50000);
+}
+
+#[test]
+fn test_sync_transaction_cli_error_with_mock() {
+    let mut env = TestEnvironment::new();
+    env.set_var("MOCK_FAIL", "Insufficient funds");
+
+    let client = GreenClient::new();
+    match client.get_transactions(None, None, None) {
+        Err(Error::Cli(err)) => {
+            assert_eq!(err.kind, ErrorKind::InsufficientFunds);
+            assert_eq!(err.exit_code, Some(1));
+            assert_eq!(err.message, "Insufficient funds");
+        }
+        Ok(_) => panic!("Expected CLI error, got successful result"),
+        Err(e) => panic!("Expected CLI error, got different error: {:?}", e),
+    }
+}
+
+#[test]
+fn test_sync_transaction_json_error_with_mock() {
+    let mut env = TestEnvironment::new();
+    env.set_var("MOCK_INVALID_JSON", "1");
+
+    let client = GreenClient::new();
+    match client.get_transaction_details(TXID) {
+        Err(Error::Json(_)) => {}
+        Ok(_) => panic!("Expected JSON error, got successful result"),
+        Err(e) => panic!("Expected JSON error, got different error: {:?}", e),
+    }
+}
+
+#[tokio::test]
+async fn test_async_get_transactions_with_mock() {
+    let _env = TestEnvironment::new();
+
+    let client = AsyncGreenClient::new();
+    let list = client
+        .get_transactions(None, Some(0), Some(10))
+        .await
+        .expect("Expected successful transaction list");
+
+    assert_eq!(list.transactions.len(), 2);
+    assert!(list.more);
+}
+
+#[tokio::test]
+async fn test_async_get_transaction_details_with_mock() {
+    let _env = TestEnvironment::new();
+
+    let client = AsyncGreenClient::new();
+    let tx = client
+        .get_transaction_details(TXID)
+        .await
+        .expect("Expected successful transaction details");
+
+    assert_eq!(tx.txid, TXID);
+    assert_eq!(tx.fee, Some(1410));
+}
+
+#[tokio::test]
+async fn test_async_create_and_send_with_mock() {
+    let _env = TestEnvironment::new();
+
+    let client = AsyncGreenClient::new();
+    let result = client
+        .create_transaction(create_request())
+        .await
+        .expect("Expected successful transaction creation");
+    assert_eq!(result.estimated_fee, 1410);
+
+    let tx = client
+        .send_to_address("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq", 50000, None)
+        .await
+        .expect("Expected successful send");
+    assert_eq!(tx.txid, TXID);
+}
+
+#[tokio::test]
+async fn test_async_transaction_cli_error_with_mock() {
+    let mut env = TestEnvironment::new();
+    env.set_var("MOCK_FAIL", "Invalid address");
+
+    let client = AsyncGreenClient::new();
+    match client
+        .send_to_address("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq", 1000, None)
+        .await
+    {
+        Err(Error::Cli(err)) => {
+            assert_eq!(err.kind, ErrorKind::InvalidAddress);
+            assert!(err.stderr.contains("Invalid address"));
+        }
+        Ok(_) => panic!("Expected CLI error, got successful result"),
+        Err(e) => panic!("Expected CLI error, got different error: {:?}", e),
+    }
+}
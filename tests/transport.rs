@@ -0,0 +1,120 @@
+//! Integration tests for pluggable command transports
+//! These tests drive both clients through `ScriptedTransport` without spawning green-cli
+
+use green_rs::api::address::AddressApi;
+use green_rs::api::{AsyncTransactionApi, AsyncWalletExt, TransactionApi, TxBuilder, WalletExt};
+use green_rs::transport::{CliOutput, ScriptedTransport};
+use green_rs::types::address::GetReceiveAddressRequest;
+use green_rs::types::FeeRate;
+use green_rs::{AsyncGreenClient, ClientConfig, Error, ErrorKind, GreenClient, Network};
+
+const BALANCE: &str = include_str!("fixtures/balance_success.json");
+const FEES: &str = include_str!("fixtures/fee_estimates_success.json");
+const TRANSACTIONS: &str = include_str!("fixtures/transactions_success.json");
+const SIGNED: &str = include_str!("fixtures/tx_signed_success.json");
+const SENT: &str = include_str!("fixtures/tx_send_success.json");
+
+fn script() -> ScriptedTransport {
+    ScriptedTransport::new()
+        .on(["get", "balance", "--json"], CliOutput::success(BALANCE))
+        .on(["get", "fee-estimates", "--json"], CliOutput::success(FEES))
+        .on_prefix(["get", "transactions"], CliOutput::success(TRANSACTIONS))
+        .on_prefix(
+            ["get", "new-address"],
+            CliOutput::failure(1, "Error: Login required"),
+        )
+        .on_prefix(["tx", "sign"], CliOutput::success(SIGNED))
+        .on_prefix(["tx", "send"], CliOutput::success(SENT))
+}
+
+#[test]
+fn test_sync_client_with_scripted_transport() {
+    let client = GreenClient::with_transport(ClientConfig::default(), script());
+
+    let balance = client.get_balance().unwrap();
+    assert_eq!(balance.get("usdt"), Some(250000000));
+
+    let fees = client.get_fee_estimates().unwrap();
+    assert_eq!(fees.fees.get(&144), Some(&FeeRate::from_sat_per_vb(10)));
+
+    let list = client.get_transactions(Some(0), None, Some(2)).unwrap();
+    assert_eq!(list.transactions.len(), 2);
+}
+
+#[test]
+fn test_scripted_failure_maps_to_cli_error() {
+    let client = GreenClient::with_transport(ClientConfig::default(), script());
+
+    match client.get_new_address(GetReceiveAddressRequest::default()) {
+        Err(Error::Cli(err)) => assert_eq!(err.kind, ErrorKind::LoginRequired),
+        other => panic!("Expected CLI error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_scripted_transport_records_commands() {
+    let transport = script();
+    let config = ClientConfig::builder().network(Network::Testnet).build();
+    let client = GreenClient::with_transport(config, transport.clone());
+
+    client.get_transactions(Some(1), Some(0), Some(5)).unwrap();
+
+    let calls = transport.calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].global_args, ["--network", "testnet"]);
+    assert_eq!(calls[0].args, ["get", "transactions", "--json"]);
+    assert_eq!(
+        calls[0].params.as_ref().map(|params| params.json()),
+        Some(r#"{"subaccount":1,"first":0,"count":5}"#)
+    );
+}
+
+#[test]
+fn test_unscripted_command_is_reported() {
+    let client = GreenClient::with_transport(ClientConfig::default(), ScriptedTransport::new());
+
+    match client.get_balance() {
+        Err(Error::Unexpected(msg)) => assert!(msg.contains("get balance --json")),
+        other => panic!("Expected unexpected error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_tx_builder_with_scripted_transport() {
+    let transport = script();
+    let client = GreenClient::with_transport(ClientConfig::default(), transport.clone());
+
+    let txid = TxBuilder::with_client(client)
+        .add_output(
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_string(),
+            50000,
+        )
+        .dump()
+        .unwrap()
+        .sign()
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(
+        txid,
+        "8f2d7ac5b5cb3d8e1c9a1d6a2e9c3b1f4e5d6c7b8a9f0e1d2c3b4a5968778695"
+    );
+    let commands: Vec<_> = transport
+        .calls()
+        .into_iter()
+        .map(|c| c.args[1].clone())
+        .collect();
+    assert_eq!(commands, ["sign", "send"]);
+}
+
+#[tokio::test]
+async fn test_async_client_with_scripted_transport() {
+    let client = AsyncGreenClient::with_transport(ClientConfig::default(), script());
+
+    let balance = client.get_balance().await.unwrap();
+    assert_eq!(balance.get("btc"), Some(100000));
+
+    let list = client.get_transactions(None, None, None).await.unwrap();
+    assert!(list.more);
+}
@@ -0,0 +1,87 @@
+//! Integration tests for `TxBuilder` signing and broadcasting
+//! These tests mock green-cli with a shell script stub to exercise `tx sign` and `tx send`
+
+mod common;
+
+use common::TestEnvironment;
+use green_rs::api::TxBuilder;
+use green_rs::types::FeeRate;
+use green_rs::{Error, GreenClient};
+
+const TXID: &str = "8f2d7ac5b5cb3d8e1c9a1d6a2e9c3b1f4e5d6c7b8a9f0e1d2c3b4a5968778695";
+
+fn builder() -> TxBuilder {
+    TxBuilder::with_client(GreenClient::new())
+        .add_output(
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_string(),
+            50000,
+        )
+        .set_fee_rate(FeeRate::from_sat_per_vb(10))
+        .set_subaccount(0)
+}
+
+#[test]
+fn test_sign_and_send_with_mock() {
+    let _env = TestEnvironment::new();
+
+    let signed = builder().dump().unwrap().sign().expect("Expected signing");
+    let payload = signed.signed_json().expect("Expected signed payload");
+    assert!(payload.contains("\"is_signed\": true"));
+
+    // The dumped file now holds the signed payload
+    let path = signed.get_temp_path().unwrap().to_string();
+    let on_disk = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(on_disk, payload);
+
+    let txid = signed.send().expect("Expected broadcast");
+    assert_eq!(txid, TXID);
+
+    // The temporary file is cleaned up with the builder
+    assert!(!std::path::Path::new(&path).exists());
+}
+
+#[test]
+fn test_sign_requires_dump() {
+    match builder().sign() {
+        Err(Error::Unexpected(msg)) => assert!(msg.contains("dump()")),
+        Ok(_) => panic!("Expected error when signing without dump"),
+        Err(e) => panic!("Unexpected error type: {:?}", e),
+    }
+}
+
+#[test]
+fn test_send_requires_sign() {
+    let _env = TestEnvironment::new();
+
+    match builder().dump().unwrap().send() {
+        Err(Error::Unexpected(msg)) => assert!(msg.contains("sign()")),
+        Ok(txid) => panic!("Expected error when sending unsigned tx, got {txid}"),
+        Err(e) => panic!("Unexpected error type: {:?}", e),
+    }
+}
+
+#[test]
+fn test_sign_cli_error_with_mock() {
+    let mut env = TestEnvironment::new();
+    env.set_var("MOCK_FAIL", "Wallet locked");
+
+    match builder().dump().unwrap().sign() {
+        Err(Error::Cli(msg)) => assert!(msg.message.contains("Wallet locked")),
+        Ok(_) => panic!("Expected CLI error, got successful result"),
+        Err(e) => panic!("Expected CLI error, got different error: {:?}", e),
+    }
+}
+
+#[test]
+fn test_send_without_txid_is_invalid_response() {
+    let mut env = TestEnvironment::new();
+
+    let signed = builder().dump().unwrap().sign().unwrap();
+    env.set_var("MOCK_SEND_NO_TXID", "1");
+
+    match signed.broadcast() {
+        Err(Error::InvalidResponse) => {}
+        Ok(txid) => panic!("Expected invalid response, got {txid}"),
+        Err(e) => panic!("Expected invalid response, got different error: {:?}", e),
+    }
+}
@@ -23,7 +23,7 @@ fn test_receive_address_serialization() {
     let addr = ReceiveAddress {
         address: "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh".to_string(),
         pointer: 5,
-        address_type: "p2wpkh".to_string(),
+        address_type: AddressType::P2wpkh,
         branch: 0,
         subaccount: 0,
         script_pubkey: Some("0014...".to_string()),
@@ -69,7 +69,7 @@ fn test_transaction_serialization() {
         size: Some(200),
         vsize: Some(100),
         fee: Some(1000),
-        fee_rate: Some(10.0),
+        fee_rate: Some(FeeRate::from_sat_per_vb(10)),
         block_hash: None,
         block_height: None,
         confirmations: 0,
@@ -117,3 +117,72 @@ fn test_type_aliases() {
         memo: None,
     };
 }
+
+#[test]
+fn test_fee_rate_units_and_serialization() {
+    let rate = FeeRate::from_sat_per_vb(12);
+    assert_eq!(rate, FeeRate::from_sat_per_kvb(12_000));
+    assert_eq!(rate.to_string(), "12 sat/vB");
+    assert_eq!(rate.fee_for_vsize(141), 1_692);
+    assert_eq!(serde_json::to_string(&rate).unwrap(), "12");
+
+    let liquid = FeeRate::from_sat_per_kvb(100);
+    assert_eq!(liquid.to_string(), "0.1 sat/vB");
+    assert_eq!(liquid.to_sat_per_vb_floor(), 0);
+    assert_eq!(liquid.to_sat_per_vb_ceil(), 1);
+    // 0.1 sat/vB over 1 255 vB is 125.5 sat, rounded up
+    assert_eq!(liquid.fee_for_vsize(1_255), 126);
+    assert_eq!(serde_json::to_string(&liquid).unwrap(), "0.1");
+
+    let parsed: FeeRate = serde_json::from_str("2.25").unwrap();
+    assert_eq!(parsed.to_sat_per_kvb(), 2_250);
+    assert!(serde_json::from_str::<FeeRate>("-1").is_err());
+    assert_eq!(
+        FeeRate::from_fee_and_vsize(1_410, 141),
+        FeeRate::from_sat_per_vb(10)
+    );
+}
+
+#[test]
+fn test_subaccount_and_address_type_spellings() {
+    let spellings = [
+        (SubaccountType::TwoOfTwo, "2of2"),
+        (SubaccountType::TwoOfTwoNoRecovery, "2of2_no_recovery"),
+        (SubaccountType::TwoOfThree, "2of3"),
+        (SubaccountType::P2shP2wpkh, "p2sh-p2wpkh"),
+        (SubaccountType::P2wpkh, "p2wpkh"),
+        (SubaccountType::P2pkh, "p2pkh"),
+        (SubaccountType::P2tr, "p2tr"),
+    ];
+    for (kind, name) in spellings {
+        assert_eq!(serde_json::to_value(&kind).unwrap(), name);
+        assert_eq!(
+            serde_json::from_value::<SubaccountType>(name.into()).unwrap(),
+            kind
+        );
+        assert_eq!(kind.to_string(), name);
+    }
+    assert_eq!(
+        SubaccountType::TwoOfTwo.default_address_type(),
+        Some(AddressType::Csv)
+    );
+    assert!(SubaccountType::TwoOfThree.is_multisig());
+    assert!(!SubaccountType::P2wpkh.is_multisig());
+
+    let address: AddressType = "p2sh-p2wpkh".parse().unwrap();
+    assert_eq!(address, AddressType::P2shP2wpkh);
+    assert_eq!(serde_json::to_value(&address).unwrap(), "p2sh-p2wpkh");
+
+    // Types added to green-cli later round-trip unchanged
+    let subaccount: Subaccount =
+        serde_json::from_str(r#"{"pointer": 2, "name": "Vault", "type": "3of5"}"#).unwrap();
+    assert_eq!(
+        subaccount.subaccount_type,
+        SubaccountType::Unknown("3of5".to_string())
+    );
+    assert_eq!(subaccount.subaccount_type.default_address_type(), None);
+    assert_eq!(serde_json::to_value(&subaccount).unwrap()["type"], "3of5");
+    let address: AddressType = serde_json::from_str(r#""p2a""#).unwrap();
+    assert_eq!(address, AddressType::Unknown("p2a".to_string()));
+    assert_eq!(serde_json::to_string(&address).unwrap(), r#""p2a""#);
+}
@@ -0,0 +1,260 @@
+//! Integration tests for freezing, thawing and labelling UTXOs
+//! These tests run against the stateful `FakeGreen` wallet, so frozen outputs
+//! must also stay out of coin selection
+
+use green_rs::api::subaccount::SubaccountExt;
+use green_rs::api::utxo::{AsyncUtxoApi, UtxoApi};
+use green_rs::api::{TransactionApi, WalletExt};
+use green_rs::testkit::FakeGreen;
+use green_rs::transport::{CliOutput, ScriptedTransport};
+use green_rs::types::subaccount::{CreateSubaccountParams, SubaccountType};
+use green_rs::types::utxo::{DustPolicy, UpdateUtxoRequest, UtxoReport};
+use green_rs::types::wallet::WalletSettings;
+use green_rs::types::UnspentOutput;
+use green_rs::types::{FeeRate, GetUnspentOutputsParams};
+use green_rs::{ClientConfig, ErrorKind, GreenClient, Network};
+use std::collections::HashMap;
+
+const RECIPIENT: &str = "bcrt1q6qk53s4wtrddddtfwwnny6urn8t8mmx4mcfkae";
+
+fn include_frozen() -> GetUnspentOutputsParams {
+    GetUnspentOutputsParams {
+        include_frozen: Some(true),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_frozen_outputs_are_not_spent() {
+    let wallet = FakeGreen::new();
+    let dust = wallet.fund(0, 600);
+    let big = wallet.fund(0, 80_000);
+    wallet.mine(1);
+    let client = wallet.client();
+
+    let frozen = client.freeze_utxo(&big, 0).unwrap();
+    assert!(frozen.is_frozen);
+    assert_eq!(frozen.confirmations, Some(1));
+
+    // Hidden from the default listing, and not selected for spending
+    let listed = client
+        .get_unspent_outputs(GetUnspentOutputsParams::default())
+        .unwrap();
+    assert_eq!(listed["btc"].len(), 1);
+    assert_eq!(listed["btc"][0].txhash, dust);
+    let err = client.send_to_address(RECIPIENT, 10_000, None).unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::InsufficientFunds));
+
+    let thawed = client.thaw_utxo(&big, 0).unwrap();
+    assert!(!thawed.is_frozen);
+    client.send_to_address(RECIPIENT, 10_000, None).unwrap();
+    assert!(client.get_balance().unwrap().get("btc").unwrap() < 70_600);
+}
+
+#[test]
+fn test_memo_update_keeps_freeze_status() {
+    let wallet = FakeGreen::new();
+    let txid = wallet.fund(0, 5_000);
+    let client = wallet.client();
+
+    client.freeze_utxo(&txid, 0).unwrap();
+    let labelled = client
+        .set_utxo_memo(&txid, 0, "dust attack, do not spend")
+        .unwrap();
+    assert!(labelled.is_frozen);
+    assert_eq!(labelled.memo.as_deref(), Some("dust attack, do not spend"));
+
+    let utxos = client.get_unspent_outputs(include_frozen()).unwrap();
+    assert_eq!(utxos["btc"][0].memo, labelled.memo);
+
+    let cleared = client.set_utxo_memo(&txid, 0, "").unwrap();
+    assert_eq!(cleared.memo, None);
+}
+
+#[test]
+fn test_batch_update_reports_each_outpoint() {
+    let wallet = FakeGreen::new();
+    let first = wallet.fund(0, 700);
+    let second = wallet.fund(0, 800);
+    let client = wallet.client();
+
+    let results = client.update_utxos(vec![
+        UpdateUtxoRequest::freeze(first.clone(), 0),
+        UpdateUtxoRequest::freeze("ff".repeat(32), 3),
+        UpdateUtxoRequest::freeze(second.clone(), 0),
+    ]);
+
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert_eq!(results[1].vout, 3);
+    let err = results[1].result.as_ref().unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::NotFound));
+    assert_eq!(results[2].txhash, second);
+    assert!(results[2].result.as_ref().unwrap().is_frozen);
+
+    assert!(wallet.utxos().iter().all(|utxo| utxo.is_frozen));
+}
+
+#[tokio::test]
+async fn test_async_freeze_thaw_and_memo() {
+    let wallet = FakeGreen::new();
+    let txid = wallet.fund(0, 9_000);
+    let client = wallet.async_client();
+
+    assert!(client.freeze_utxo(&txid, 0).await.unwrap().is_frozen);
+    let utxo = client.set_utxo_memo(&txid, 0, "quarantine").await.unwrap();
+    assert!(utxo.is_frozen);
+    assert!(!client.thaw_utxo(&txid, 0).await.unwrap().is_frozen);
+
+    let results = client
+        .update_utxos(vec![
+            UpdateUtxoRequest::freeze(txid.clone(), 0),
+            UpdateUtxoRequest::thaw(txid.clone(), 1),
+        ])
+        .await;
+    assert!(results[0].is_ok());
+    assert!(!results[1].is_ok());
+    assert_eq!(wallet.utxos()[0].memo.as_deref(), Some("quarantine"));
+}
+
+#[test]
+fn test_update_utxo_sends_only_changed_fields() {
+    let transport = ScriptedTransport::new().on_prefix(
+        ["update", "utxo"],
+        CliOutput::success(
+            r#"{"txhash": "aa", "vout": 1, "satoshi": 1000, "subaccount": 0, "pointer": 4, "memo": "label"}"#,
+        ),
+    );
+    let client = GreenClient::with_transport(ClientConfig::default(), transport.clone());
+
+    client.set_utxo_memo("aa", 1, "label").unwrap();
+    client.freeze_utxo("aa", 1).unwrap();
+
+    let calls = transport.calls();
+    assert_eq!(calls[0].args, ["update", "utxo", "--json"]);
+    let params: Vec<_> = calls
+        .iter()
+        .map(|call| call.params.as_ref().unwrap().json().to_string())
+        .collect();
+    assert_eq!(
+        params,
+        [
+            r#"{"txhash":"aa","vout":1,"memo":"label"}"#,
+            r#"{"txhash":"aa","vout":1,"is_frozen":true}"#,
+        ]
+    );
+}
+
+#[test]
+fn test_utxo_report_by_asset_and_subaccount() {
+    let wallet = FakeGreen::new();
+    let client = wallet.client();
+    client
+        .create_subaccount(CreateSubaccountParams {
+            name: "Fees".to_string(),
+            subaccount_type: SubaccountType::P2wpkh,
+            recovery_mnemonic: None,
+            recovery_xpub: None,
+        })
+        .unwrap();
+    let asset = "aa".repeat(32);
+
+    wallet.fund(0, 600);
+    let big = wallet.fund(0, 50_000);
+    wallet.mine(6);
+    wallet.fund(0, 2_000);
+    wallet.fund(1, 700);
+    wallet.fund_asset(1, 5_000, Some(asset.clone()));
+    wallet.mine(1);
+    wallet.fund(0, 300);
+    client.freeze_utxo(&big, 0).unwrap();
+
+    // At 10 sat/vB a csv input costs 970 sat and a p2wpkh input 680 sat
+    let settings = WalletSettings {
+        dust_limit: Some(500),
+        ..Default::default()
+    };
+    let report = client
+        .get_utxo_report(DustPolicy::from_settings(
+            &settings,
+            FeeRate::from_sat_per_vb(10),
+        ))
+        .unwrap();
+
+    let btc = &report.by_asset["btc"];
+    assert_eq!(btc.subaccount, None);
+    assert_eq!(btc.utxo_count, 5);
+    assert_eq!(btc.total_satoshi, 53_600);
+    assert_eq!((btc.frozen_count, btc.frozen_satoshi), (1, 50_000));
+    assert_eq!((btc.dust_count, btc.dust_satoshi), (2, 900));
+    let histogram: Vec<u32> = btc.confirmations.iter().map(|b| b.utxo_count).collect();
+    assert_eq!(histogram, [1, 2, 2, 0, 0]);
+    assert_eq!(btc.confirmations[2].min_confirmations, 6);
+    assert_eq!(btc.confirmations[2].max_confirmations, Some(99));
+    assert_eq!(btc.confirmations[4].max_confirmations, None);
+
+    let fees = report.subaccount_summary(1, "btc").unwrap();
+    assert_eq!(fees.subaccount, Some(1));
+    assert_eq!((fees.utxo_count, fees.dust_count), (1, 0));
+    assert_eq!(
+        report.subaccount_summary(1, &asset).unwrap().total_satoshi,
+        5_000
+    );
+    assert_eq!(report.subaccount_summary(0, "btc").unwrap().utxo_count, 4);
+    assert!(report.subaccount_summary(0, &asset).is_none());
+
+    let dust: Vec<u64> = report.dust.iter().map(|utxo| utxo.satoshi).collect();
+    assert_eq!(dust, [300, 600]);
+}
+
+#[test]
+fn test_liquid_assets_are_never_dust() {
+    let wallet = FakeGreen::with_network(Network::Liquid);
+    let usdt = "ce".repeat(32);
+    wallet.fund(0, 300);
+    wallet.fund_asset(0, 300, Some(usdt.clone()));
+    wallet.fund_asset(0, 5, Some(usdt.clone()));
+    let policy = DustPolicy::new(546, FeeRate::from_sat_per_vb(1));
+
+    // Only the L-BTC output is measured against the limit and L-BTC fee
+    let report = wallet.client().get_utxo_report(policy.clone()).unwrap();
+    assert_eq!(report.by_asset["btc"].dust_count, 1);
+    assert_eq!(report.by_asset[&usdt].utxo_count, 2);
+    assert_eq!(report.by_asset[&usdt].dust_count, 0);
+    let dust: Vec<Option<&str>> = report
+        .dust
+        .iter()
+        .map(|utxo| utxo.asset_id.as_deref())
+        .collect();
+    assert_eq!(dust, [None]);
+
+    // Outputs keyed by the policy asset ID, as returned on Liquid
+    let lbtc = "6f".repeat(32);
+    let output = |asset_id: &str| UnspentOutput {
+        asset_id: Some(asset_id.to_string()),
+        ..wallet.utxos()[0].clone()
+    };
+    let utxos = HashMap::from([
+        (lbtc.clone(), vec![output(&lbtc)]),
+        (usdt.clone(), vec![output(&usdt)]),
+    ]);
+    let report = UtxoReport::new(&utxos, policy.fee_asset(lbtc.clone()));
+    assert_eq!(report.by_asset[&lbtc].dust_count, 1);
+    assert_eq!(report.by_asset[&usdt].dust_count, 0);
+}
+
+#[tokio::test]
+async fn test_async_utxo_report_matches_sync() {
+    let wallet = FakeGreen::new();
+    wallet.fund(0, 400);
+    wallet.fund(0, 40_000);
+    wallet.mine(2);
+    let policy = DustPolicy::new(546, FeeRate::from_sat_per_vb(2));
+
+    let sync_report = wallet.client().get_utxo_report(policy.clone()).unwrap();
+    let async_report = wallet.async_client().get_utxo_report(policy).await.unwrap();
+
+    assert_eq!(sync_report, async_report);
+    assert_eq!(async_report.dust.len(), 1);
+    assert_eq!(async_report.by_asset["btc"].confirmations[1].utxo_count, 2);
+}
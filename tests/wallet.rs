@@ -3,8 +3,8 @@
 
 use assert_cmd::prelude::*;
 use green_rs::api::{AsyncWalletExt, WalletExt};
-use green_rs::types::{Balance, FeeEstimates};
-use green_rs::{AsyncGreenClient, Error, GreenClient};
+use green_rs::types::{Balance, FeeEstimates, FeePriority, FeeRate};
+use green_rs::{AsyncGreenClient, Error, GreenClient, Network};
 use predicates::prelude::*;
 use std::collections::HashMap;
 use std::env;
@@ -204,7 +204,7 @@ fn test_sync_wallet_cli_error_with_mock() {
     // Verify CLI error
     match result {
         Err(Error::Cli(msg)) => {
-            assert!(msg.contains("Wallet locked"));
+            assert!(msg.message.contains("Wallet locked"));
         }
         Ok(_) => panic!("Expected CLI error, got successful result"),
         Err(e) => panic!("Expected CLI error, got different error: {:?}", e),
@@ -222,10 +222,22 @@ fn test_sync_wallet_get_fee_estimates_with_mock() {
     match result {
         Ok(fee_estimates) => {
             assert_eq!(fee_estimates.fees.len(), 4);
-            assert_eq!(fee_estimates.fees.get(&1), Some(&50));
-            assert_eq!(fee_estimates.fees.get(&6), Some(&25));
-            assert_eq!(fee_estimates.fees.get(&12), Some(&15));
-            assert_eq!(fee_estimates.fees.get(&144), Some(&10));
+            assert_eq!(
+                fee_estimates.fees.get(&1),
+                Some(&FeeRate::from_sat_per_vb(50))
+            );
+            assert_eq!(
+                fee_estimates.fees.get(&6),
+                Some(&FeeRate::from_sat_per_vb(25))
+            );
+            assert_eq!(
+                fee_estimates.fees.get(&12),
+                Some(&FeeRate::from_sat_per_vb(15))
+            );
+            assert_eq!(
+                fee_estimates.fees.get(&144),
+                Some(&FeeRate::from_sat_per_vb(10))
+            );
         }
         Err(e) => panic!("Expected successful fee estimates, got error: {:?}", e),
     }
@@ -261,10 +273,22 @@ async fn test_async_wallet_get_fee_estimates_with_mock() {
     match result {
         Ok(fee_estimates) => {
             assert_eq!(fee_estimates.fees.len(), 4);
-            assert_eq!(fee_estimates.fees.get(&1), Some(&50));
-            assert_eq!(fee_estimates.fees.get(&6), Some(&25));
-            assert_eq!(fee_estimates.fees.get(&12), Some(&15));
-            assert_eq!(fee_estimates.fees.get(&144), Some(&10));
+            assert_eq!(
+                fee_estimates.fees.get(&1),
+                Some(&FeeRate::from_sat_per_vb(50))
+            );
+            assert_eq!(
+                fee_estimates.fees.get(&6),
+                Some(&FeeRate::from_sat_per_vb(25))
+            );
+            assert_eq!(
+                fee_estimates.fees.get(&12),
+                Some(&FeeRate::from_sat_per_vb(15))
+            );
+            assert_eq!(
+                fee_estimates.fees.get(&144),
+                Some(&FeeRate::from_sat_per_vb(10))
+            );
         }
         Err(e) => panic!("Expected successful fee estimates, got error: {:?}", e),
     }
@@ -281,7 +305,7 @@ async fn test_async_wallet_cli_error_with_mock() {
     // Verify CLI error
     match result {
         Err(Error::Cli(msg)) => {
-            assert!(msg.contains("Network error"));
+            assert!(msg.message.contains("Network error"));
         }
         Ok(_) => panic!("Expected CLI error, got successful result"),
         Err(e) => panic!("Expected CLI error, got different error: {:?}", e),
@@ -334,15 +358,74 @@ fn test_balance_type_functionality() {
 #[test]
 fn test_fee_estimates_type() {
     let mut fees = HashMap::new();
-    fees.insert(1, 50);
-    fees.insert(6, 25);
-    fees.insert(144, 10);
+    fees.insert(1, FeeRate::from_sat_per_vb(50));
+    fees.insert(6, FeeRate::from_sat_per_vb(25));
+    fees.insert(144, FeeRate::from_sat_per_vb(10));
 
     let fee_estimates = FeeEstimates { fees };
 
     // Verify structure
     assert_eq!(fee_estimates.fees.len(), 3);
-    assert_eq!(fee_estimates.fees.get(&1), Some(&50));
-    assert_eq!(fee_estimates.fees.get(&6), Some(&25));
-    assert_eq!(fee_estimates.fees.get(&144), Some(&10));
+    assert_eq!(
+        fee_estimates.fees.get(&1),
+        Some(&FeeRate::from_sat_per_vb(50))
+    );
+    assert_eq!(
+        fee_estimates.fees.get(&6),
+        Some(&FeeRate::from_sat_per_vb(25))
+    );
+    assert_eq!(
+        fee_estimates.fees.get(&144),
+        Some(&FeeRate::from_sat_per_vb(10))
+    );
+}
+
+#[test]
+fn test_fee_estimates_by_target_and_priority() {
+    let sat_per_vb = FeeRate::from_sat_per_vb;
+    let fees = HashMap::from([
+        (0, FeeRate::from_sat_per_kvb(1_500)),
+        (2, sat_per_vb(40)),
+        (6, sat_per_vb(20)),
+        (144, sat_per_vb(1)),
+    ]);
+    let estimates = FeeEstimates { fees };
+
+    assert_eq!(estimates.for_target(6), Some(sat_per_vb(20)));
+    // Halfway from 40 to 20 sat/vB
+    assert_eq!(estimates.for_target(4), Some(sat_per_vb(30)));
+    // Nearest target outside the estimated range
+    assert_eq!(estimates.for_target(1), Some(sat_per_vb(40)));
+    // Never below the reported minimum relay fee rate
+    assert_eq!(
+        estimates.for_target(1_000),
+        Some(FeeRate::from_sat_per_kvb(1_500))
+    );
+
+    let network = Network::Mainnet;
+    assert_eq!(
+        estimates.for_priority(FeePriority::Fast, network),
+        sat_per_vb(40)
+    );
+    assert_eq!(
+        estimates.for_priority(FeePriority::Normal, network),
+        sat_per_vb(20)
+    );
+    assert_eq!(
+        estimates.for_priority(FeePriority::Minimum, network),
+        FeeRate::from_sat_per_kvb(1_500)
+    );
+
+    let empty = FeeEstimates {
+        fees: HashMap::new(),
+    };
+    assert_eq!(empty.for_target(6), None);
+    assert_eq!(
+        empty.for_priority(FeePriority::Fast, Network::Liquid),
+        FeeRate::LIQUID_MIN_RELAY
+    );
+    assert_eq!(
+        empty.relay_floor(Network::Testnet),
+        FeeRate::BITCOIN_MIN_RELAY
+    );
 }